@@ -0,0 +1,2 @@
+-- Default Whisper model chosen in the model manager
+ALTER TABLE user_settings ADD COLUMN whisper_model VARCHAR;
@@ -0,0 +1,2 @@
+DROP TABLE speaking_attempts;
+ALTER TABLE user_settings DROP COLUMN save_speaking_attempts;
@@ -0,0 +1,18 @@
+-- Opt-in archive of spoken review attempts
+ALTER TABLE user_settings ADD COLUMN save_speaking_attempts BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE TABLE speaking_attempts (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    vocabulary_id TEXT NOT NULL,
+    audio_path TEXT NOT NULL,
+    audio_format TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    transcription TEXT,
+    score REAL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (vocabulary_id) REFERENCES vocabulary(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_speaking_attempts_vocabulary_id ON speaking_attempts(vocabulary_id);
+CREATE INDEX idx_speaking_attempts_user_created ON speaking_attempts(user_id, created_at);
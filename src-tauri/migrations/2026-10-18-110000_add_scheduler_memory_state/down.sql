@@ -0,0 +1,3 @@
+ALTER TABLE user_settings DROP COLUMN scheduler;
+ALTER TABLE vocabulary DROP COLUMN difficulty;
+ALTER TABLE vocabulary DROP COLUMN stability;
@@ -0,0 +1,6 @@
+-- FSRS memory state, NULL until an item is scheduled by FSRS
+ALTER TABLE vocabulary ADD COLUMN stability REAL;
+ALTER TABLE vocabulary ADD COLUMN difficulty REAL;
+
+-- Spaced-repetition algorithm chosen by the user
+ALTER TABLE user_settings ADD COLUMN scheduler VARCHAR NOT NULL DEFAULT 'ebbinghaus';
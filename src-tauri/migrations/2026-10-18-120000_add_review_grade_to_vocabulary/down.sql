@@ -0,0 +1,2 @@
+ALTER TABLE vocabulary DROP COLUMN last_response_ms;
+ALTER TABLE vocabulary DROP COLUMN last_grade;
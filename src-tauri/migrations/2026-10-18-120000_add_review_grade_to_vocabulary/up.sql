@@ -0,0 +1,3 @@
+-- Grade (again/hard/good/easy) and response time of the latest review
+ALTER TABLE vocabulary ADD COLUMN last_grade VARCHAR;
+ALTER TABLE vocabulary ADD COLUMN last_response_ms INTEGER;
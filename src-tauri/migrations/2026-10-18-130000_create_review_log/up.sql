@@ -0,0 +1,50 @@
+-- One row per review answer
+CREATE TABLE review_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    vocabulary_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    reviewed_at TEXT NOT NULL,
+    grade TEXT CHECK (grade IN ('again', 'hard', 'good', 'easy')),
+    previous_stage INTEGER NOT NULL,
+    new_stage INTEGER NOT NULL,
+    interval_days REAL NOT NULL,
+    days_late INTEGER NOT NULL,
+    was_late BOOLEAN NOT NULL,
+    response_ms INTEGER,
+    scheduler TEXT NOT NULL,
+    stability REAL,
+    difficulty REAL,
+    is_synthetic BOOLEAN NOT NULL DEFAULT 0,
+    FOREIGN KEY (vocabulary_id) REFERENCES vocabulary(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_review_log_vocabulary_id ON review_log(vocabulary_id, reviewed_at);
+CREATE INDEX idx_review_log_user_reviewed_at ON review_log(user_id, reviewed_at);
+
+-- Seed a synthetic baseline per existing item summarizing its state before the log
+-- existed. Baselines carry no grade.
+INSERT INTO review_log (
+    id, vocabulary_id, user_id, reviewed_at, grade, previous_stage, new_stage,
+    interval_days, days_late, was_late, response_ms, scheduler, stability, difficulty, is_synthetic
+)
+SELECT
+    lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+        substr(lower(hex(randomblob(2))), 2) || '-' ||
+        substr('89ab', 1 + (abs(random()) % 4), 1) || substr(lower(hex(randomblob(2))), 2) || '-' ||
+        lower(hex(randomblob(6))),
+    id,
+    user_id,
+    COALESCE(last_reviewed_at, created_at, strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now')),
+    NULL,
+    COALESCE(review_stage, 0),
+    COALESCE(review_stage, 0),
+    MAX(0, COALESCE(julianday(next_review_at) - julianday(COALESCE(last_reviewed_at, created_at)), 0)),
+    0,
+    COALESCE(was_late, 0),
+    NULL,
+    CASE WHEN stability IS NOT NULL THEN 'fsrs' ELSE 'ebbinghaus' END,
+    stability,
+    difficulty,
+    1
+FROM vocabulary
+WHERE id IS NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE user_settings DROP COLUMN undo_window_seconds;
+ALTER TABLE review_log DROP COLUMN snapshot;
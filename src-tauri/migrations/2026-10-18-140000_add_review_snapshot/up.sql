@@ -0,0 +1,5 @@
+-- Scheduling state of the item before the review, JSON, used to undo it
+ALTER TABLE review_log ADD COLUMN snapshot TEXT;
+
+-- How long after a review it can still be undone
+ALTER TABLE user_settings ADD COLUMN undo_window_seconds INTEGER NOT NULL DEFAULT 300;
@@ -0,0 +1,2 @@
+ALTER TABLE user_settings DROP COLUMN day_start_hour;
+ALTER TABLE user_settings DROP COLUMN timezone;
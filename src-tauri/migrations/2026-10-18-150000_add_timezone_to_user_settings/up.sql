@@ -0,0 +1,3 @@
+-- IANA timezone and local hour the study day starts at, used for "due today"
+ALTER TABLE user_settings ADD COLUMN timezone VARCHAR NOT NULL DEFAULT 'UTC';
+ALTER TABLE user_settings ADD COLUMN day_start_hour INTEGER NOT NULL DEFAULT 0 CHECK (day_start_hour BETWEEN 0 AND 23);
@@ -0,0 +1,25 @@
+DROP INDEX idx_vocabulary_user_next_review_at;
+DROP INDEX idx_review_log_user_reviewed_at;
+DROP INDEX idx_review_log_vocabulary_id;
+
+ALTER TABLE review_log ADD COLUMN reviewed_at_text TEXT NOT NULL DEFAULT '';
+UPDATE review_log SET reviewed_at_text = strftime('%Y-%m-%dT%H:%M:%f+00:00', reviewed_at / 1000.0, 'unixepoch');
+ALTER TABLE review_log DROP COLUMN reviewed_at;
+ALTER TABLE review_log RENAME COLUMN reviewed_at_text TO reviewed_at;
+CREATE INDEX idx_review_log_vocabulary_id ON review_log(vocabulary_id, reviewed_at);
+CREATE INDEX idx_review_log_user_reviewed_at ON review_log(user_id, reviewed_at);
+
+ALTER TABLE vocabulary ADD COLUMN last_reviewed_at_text TEXT;
+UPDATE vocabulary SET last_reviewed_at_text = strftime('%Y-%m-%dT%H:%M:%f+00:00', last_reviewed_at / 1000.0, 'unixepoch');
+ALTER TABLE vocabulary DROP COLUMN last_reviewed_at;
+ALTER TABLE vocabulary RENAME COLUMN last_reviewed_at_text TO last_reviewed_at;
+
+ALTER TABLE vocabulary ADD COLUMN scheduled_review_at_text TEXT;
+UPDATE vocabulary SET scheduled_review_at_text = strftime('%Y-%m-%dT%H:%M:%f+00:00', scheduled_review_at / 1000.0, 'unixepoch');
+ALTER TABLE vocabulary DROP COLUMN scheduled_review_at;
+ALTER TABLE vocabulary RENAME COLUMN scheduled_review_at_text TO scheduled_review_at;
+
+ALTER TABLE vocabulary ADD COLUMN next_review_at_text TEXT NOT NULL DEFAULT '';
+UPDATE vocabulary SET next_review_at_text = strftime('%Y-%m-%dT%H:%M:%f+00:00', next_review_at / 1000.0, 'unixepoch');
+ALTER TABLE vocabulary DROP COLUMN next_review_at;
+ALTER TABLE vocabulary RENAME COLUMN next_review_at_text TO next_review_at;
@@ -0,0 +1,39 @@
+-- Review timestamps were RFC 3339 text compared lexicographically, which breaks
+-- for non-UTC offsets. Store them as UTC epoch milliseconds instead; julianday()
+-- understands any offset. Columns are re-added, so they move to the end of the table.
+-- Required timestamps that don't parse fall back to when the item was created, or
+-- to now when that is unknown too.
+
+ALTER TABLE vocabulary ADD COLUMN next_review_at_ms BIGINT NOT NULL DEFAULT 0;
+UPDATE vocabulary SET next_review_at_ms = CAST(ROUND((
+    COALESCE(julianday(next_review_at), julianday(created_at), julianday('now')) - 2440587.5
+) * 86400000.0) AS INTEGER);
+ALTER TABLE vocabulary DROP COLUMN next_review_at;
+ALTER TABLE vocabulary RENAME COLUMN next_review_at_ms TO next_review_at;
+
+ALTER TABLE vocabulary ADD COLUMN scheduled_review_at_ms BIGINT;
+UPDATE vocabulary SET scheduled_review_at_ms = CAST(ROUND((julianday(scheduled_review_at) - 2440587.5) * 86400000.0) AS INTEGER);
+ALTER TABLE vocabulary DROP COLUMN scheduled_review_at;
+ALTER TABLE vocabulary RENAME COLUMN scheduled_review_at_ms TO scheduled_review_at;
+
+ALTER TABLE vocabulary ADD COLUMN last_reviewed_at_ms BIGINT;
+UPDATE vocabulary SET last_reviewed_at_ms = CAST(ROUND((julianday(last_reviewed_at) - 2440587.5) * 86400000.0) AS INTEGER);
+ALTER TABLE vocabulary DROP COLUMN last_reviewed_at;
+ALTER TABLE vocabulary RENAME COLUMN last_reviewed_at_ms TO last_reviewed_at;
+
+DROP INDEX idx_review_log_vocabulary_id;
+DROP INDEX idx_review_log_user_reviewed_at;
+ALTER TABLE review_log ADD COLUMN reviewed_at_ms BIGINT NOT NULL DEFAULT 0;
+UPDATE review_log SET reviewed_at_ms = CAST(ROUND((
+    COALESCE(
+        julianday(reviewed_at),
+        (SELECT julianday(v.created_at) FROM vocabulary v WHERE v.id = review_log.vocabulary_id),
+        julianday('now')
+    ) - 2440587.5
+) * 86400000.0) AS INTEGER);
+ALTER TABLE review_log DROP COLUMN reviewed_at;
+ALTER TABLE review_log RENAME COLUMN reviewed_at_ms TO reviewed_at;
+
+CREATE INDEX idx_review_log_vocabulary_id ON review_log(vocabulary_id, reviewed_at);
+CREATE INDEX idx_review_log_user_reviewed_at ON review_log(user_id, reviewed_at);
+CREATE INDEX idx_vocabulary_user_next_review_at ON vocabulary(user_id, next_review_at);
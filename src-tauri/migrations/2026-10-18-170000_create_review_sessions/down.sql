@@ -0,0 +1,4 @@
+DROP TABLE review_session_items;
+DROP TABLE review_sessions;
+ALTER TABLE user_settings DROP COLUMN reviews_per_day;
+ALTER TABLE user_settings DROP COLUMN new_per_day;
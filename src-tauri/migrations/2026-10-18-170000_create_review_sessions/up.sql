@@ -0,0 +1,31 @@
+-- Daily caps on items introduced and reviewed, per study day
+ALTER TABLE user_settings ADD COLUMN new_per_day INTEGER NOT NULL DEFAULT 20 CHECK (new_per_day >= 0);
+ALTER TABLE user_settings ADD COLUMN reviews_per_day INTEGER NOT NULL DEFAULT 200 CHECK (reviews_per_day >= 0);
+
+-- A planned review session, kept so it can be resumed after a restart
+CREATE TABLE review_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    -- Study day the session was built for (YYYY-MM-DD in the user's timezone)
+    study_date TEXT NOT NULL,
+    -- Video the session was limited to, NULL for all videos
+    video_id TEXT,
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'completed', 'abandoned')),
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_review_sessions_user_status ON review_sessions(user_id, status);
+
+CREATE TABLE review_session_items (
+    session_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    vocabulary_id TEXT NOT NULL,
+    is_new BOOLEAN NOT NULL,
+    completed_at BIGINT,
+    PRIMARY KEY (session_id, position),
+    FOREIGN KEY (session_id) REFERENCES review_sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (vocabulary_id) REFERENCES vocabulary(id)
+);
+
+CREATE INDEX idx_review_session_items_vocabulary_id ON review_session_items(vocabulary_id);
@@ -0,0 +1,5 @@
+ALTER TABLE user_settings DROP COLUMN suspend_leeches;
+ALTER TABLE user_settings DROP COLUMN leech_threshold;
+ALTER TABLE vocabulary DROP COLUMN suspended;
+ALTER TABLE vocabulary DROP COLUMN is_leech;
+ALTER TABLE vocabulary DROP COLUMN lapses;
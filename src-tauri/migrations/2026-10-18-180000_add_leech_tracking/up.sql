@@ -0,0 +1,26 @@
+-- Lapses are failed reviews of an item that had been reviewed before. Items
+-- reviewed before this migration count every wrong answer as a lapse.
+ALTER TABLE vocabulary ADD COLUMN lapses INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE vocabulary ADD COLUMN is_leech BOOLEAN NOT NULL DEFAULT 0;
+-- Suspended items are kept but never come up for review
+ALTER TABLE vocabulary ADD COLUMN suspended BOOLEAN NOT NULL DEFAULT 0;
+
+ALTER TABLE user_settings ADD COLUMN leech_threshold INTEGER NOT NULL DEFAULT 8 CHECK (leech_threshold >= 1);
+ALTER TABLE user_settings ADD COLUMN suspend_leeches BOOLEAN NOT NULL DEFAULT 0;
+
+UPDATE vocabulary
+SET lapses = MAX(COALESCE(review_count, 0) - COALESCE(correct_count, 0), 0);
+
+-- Each user's own threshold. Vocabulary user ids are text, ids that aren't a
+-- number belong to the local user 1, like settings_user_id() in the app.
+UPDATE vocabulary
+SET is_leech = 1
+WHERE lapses >= COALESCE(
+    (SELECT s.leech_threshold
+     FROM user_settings s
+     WHERE s.user_id = CASE
+         WHEN CAST(CAST(vocabulary.user_id AS INTEGER) AS TEXT) = vocabulary.user_id THEN CAST(vocabulary.user_id AS INTEGER)
+         ELSE 1
+     END),
+    8
+);
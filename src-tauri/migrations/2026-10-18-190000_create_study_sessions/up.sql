@@ -0,0 +1,14 @@
+-- Time spent studying. Sessions are extended by heartbeats while the learner is
+-- active; duration_ms only counts time covered by heartbeats, not idle gaps.
+CREATE TABLE study_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    activity TEXT NOT NULL CHECK (activity IN ('watching', 'reviewing', 'speaking')),
+    video_id TEXT,
+    started_at BIGINT NOT NULL,
+    last_active_at BIGINT NOT NULL,
+    ended_at BIGINT,
+    duration_ms BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_study_sessions_user_started_at ON study_sessions(user_id, started_at);
@@ -0,0 +1 @@
+DROP TABLE vocabulary_contexts;
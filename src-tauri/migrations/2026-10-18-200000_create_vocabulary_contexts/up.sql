@@ -0,0 +1,20 @@
+-- Further sightings of a vocabulary item. The context the item was saved from stays
+-- on the vocabulary row; merged duplicates and sentences added later go here.
+CREATE TABLE vocabulary_contexts (
+    id TEXT PRIMARY KEY NOT NULL,
+    vocabulary_id TEXT NOT NULL,
+    video_id TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    before_2_timestamp INTEGER,
+    before_2_en TEXT,
+    before_2_zh TEXT,
+    before_1_en TEXT,
+    before_1_zh TEXT,
+    target_en TEXT NOT NULL,
+    target_zh TEXT NOT NULL,
+    word_start_index INTEGER,
+    word_end_index INTEGER,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_vocabulary_contexts_vocabulary_id ON vocabulary_contexts(vocabulary_id);
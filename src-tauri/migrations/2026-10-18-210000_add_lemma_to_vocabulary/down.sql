@@ -0,0 +1,2 @@
+DROP INDEX idx_vocabulary_user_lemma;
+ALTER TABLE vocabulary DROP COLUMN lemma;
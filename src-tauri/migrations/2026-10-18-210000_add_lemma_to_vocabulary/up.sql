@@ -0,0 +1,5 @@
+-- Dictionary form of `word`, used to group inflections of the same word. Existing
+-- rows are filled in by the app after migrating, see `lemmatizer::backfill_lemmas`.
+ALTER TABLE vocabulary ADD COLUMN lemma TEXT;
+
+CREATE INDEX idx_vocabulary_user_lemma ON vocabulary(user_id, lemma);
@@ -0,0 +1 @@
+DROP TABLE llm_lookup_cache;
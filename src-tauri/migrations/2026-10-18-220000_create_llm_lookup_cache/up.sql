@@ -0,0 +1,12 @@
+-- Responses of `lookup_with_llm`, keyed by a hash of the model, word and context.
+-- `response` is the parsed lookup as JSON.
+CREATE TABLE llm_lookup_cache (
+    cache_key TEXT PRIMARY KEY NOT NULL,
+    model TEXT NOT NULL,
+    word TEXT NOT NULL,
+    response TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_llm_lookup_cache_expires_at ON llm_lookup_cache(expires_at);
@@ -0,0 +1,394 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::query_builder::{BoxedSqlQuery, SqlQuery};
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Double, Integer, Nullable, Text};
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use serde::{Deserialize, Serialize};
+
+use crate::models::study_session::DailyStudyTime;
+use crate::scheduler::{ebbinghaus::MAX_STAGE, StudyDay};
+use crate::timestamps;
+
+/// `vocabulary.created_at` is RFC 3339 text; this turns it into epoch milliseconds in SQL
+const CREATED_AT_MS: &str = "CAST(ROUND((julianday(v.created_at) - 2440587.5) * 86400000.0) AS INTEGER)";
+
+/// Review timestamps are grouped into quarter hours before mapping them to study days,
+/// every timezone offset and day start is a multiple of this
+const QUARTER_HOUR_MS: i64 = 15 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    /// Weeks start on Monday
+    Week,
+    Month,
+}
+
+/// A time range `[start_ms, end_ms)` covering one study period
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    /// First study day of the period
+    pub start: NaiveDate,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+/// First study day of the period containing `date`
+pub fn period_start(period: Period, date: NaiveDate) -> NaiveDate {
+    match period {
+        Period::Day => date,
+        Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        Period::Month => date.with_day(1).unwrap(),
+    }
+}
+
+fn next_period_start(period: Period, start: NaiveDate) -> NaiveDate {
+    match period {
+        Period::Day => start + Duration::days(1),
+        Period::Week => start + Duration::days(7),
+        Period::Month => start.checked_add_months(chrono::Months::new(1)).unwrap(),
+    }
+}
+
+/// The last `count` periods up to and including the one containing `today`, oldest first
+pub fn period_buckets(study_day: &StudyDay, period: Period, count: usize, today: NaiveDate) -> Vec<Bucket> {
+    let mut starts = vec![period_start(period, today)];
+    for _ in 1..count {
+        let previous = starts.last().unwrap().pred_opt().unwrap();
+        starts.push(period_start(period, previous));
+    }
+    starts.reverse();
+
+    starts
+        .into_iter()
+        .map(|start| Bucket {
+            start,
+            start_ms: study_day.start_of(start).timestamp_millis(),
+            end_ms: study_day.start_of(next_period_start(period, start)).timestamp_millis(),
+        })
+        .collect()
+}
+
+/// `WITH ranges(idx, start_ms, end_ms) AS (VALUES ...)` for `count` ranges. Study days
+/// follow the user's DST rules, so their bounds are computed in Rust and joined against.
+pub(crate) fn ranges_cte(count: usize) -> String {
+    format!("WITH ranges(idx, start_ms, end_ms) AS (VALUES {})", vec!["(?, ?, ?)"; count].join(", "))
+}
+
+/// Bind the values of a `ranges_cte`
+pub(crate) fn bind_ranges<'a>(
+    mut query: BoxedSqlQuery<'a, Sqlite, SqlQuery>,
+    ranges: impl IntoIterator<Item = (i64, i64)>,
+) -> BoxedSqlQuery<'a, Sqlite, SqlQuery> {
+    for (index, (start, end)) in ranges.into_iter().enumerate() {
+        query = query
+            .bind::<Integer, _>(index as i32)
+            .bind::<BigInt, _>(start)
+            .bind::<BigInt, _>(end);
+    }
+    query
+}
+
+/// Current and longest run of consecutive study days, from sorted distinct `dates`.
+/// A streak that ended yesterday is still current until today is over.
+pub fn streaks(dates: &[NaiveDate], today: NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in dates {
+        run = match previous {
+            Some(previous) if date - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(date);
+    }
+
+    let current = match previous {
+        Some(last) if last == today || last == today - Duration::days(1) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct StageRetention {
+    #[diesel(sql_type = Integer)]
+    pub stage: i32,
+    #[diesel(sql_type = BigInt)]
+    pub reviews: i64,
+    #[diesel(sql_type = BigInt)]
+    pub correct: i64,
+    /// Share of reviews at this stage that were recalled
+    #[diesel(sql_type = Double)]
+    pub retention: f64,
+}
+
+/// How well items are recalled at each stage, from graded reviews since `since_ms`
+pub fn retention_by_stage(conn: &mut SqliteConnection, user_id: &str, since_ms: Option<i64>) -> QueryResult<Vec<StageRetention>> {
+    sql_query(
+        "SELECT previous_stage AS stage,
+                COUNT(*) AS reviews,
+                SUM(grade <> 'again') AS correct,
+                AVG(grade <> 'again') AS retention
+         FROM review_log
+         WHERE user_id = ? AND is_synthetic = 0 AND grade IS NOT NULL AND reviewed_at >= ?
+         GROUP BY previous_stage
+         ORDER BY previous_stage",
+    )
+    .bind::<Text, _>(user_id)
+    .bind::<BigInt, _>(since_ms.unwrap_or(i64::MIN))
+    .load(conn)
+}
+
+#[derive(QueryableByName)]
+struct BucketAccuracy {
+    #[diesel(sql_type = Integer)]
+    idx: i32,
+    #[diesel(sql_type = BigInt)]
+    reviews: i64,
+    #[diesel(sql_type = BigInt)]
+    correct: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccuracyPoint {
+    /// First study day of the period, `YYYY-MM-DD`
+    pub period_start: String,
+    pub reviews: i64,
+    pub correct: i64,
+    /// `None` for periods without reviews
+    pub accuracy: Option<f64>,
+}
+
+/// Review accuracy per period, one point for every bucket including empty ones
+pub fn accuracy_over_time(conn: &mut SqliteConnection, user_id: &str, buckets: &[Bucket]) -> QueryResult<Vec<AccuracyPoint>> {
+    let sql = format!(
+        "{}
+         SELECT ranges.idx AS idx,
+                COUNT(r.id) AS reviews,
+                COALESCE(SUM(r.grade <> 'again'), 0) AS correct
+         FROM ranges
+         LEFT JOIN review_log r
+           ON r.reviewed_at >= ranges.start_ms AND r.reviewed_at < ranges.end_ms
+          AND r.user_id = ? AND r.is_synthetic = 0 AND r.grade IS NOT NULL
+         GROUP BY ranges.idx
+         ORDER BY ranges.idx",
+        ranges_cte(buckets.len())
+    );
+    let rows: Vec<BucketAccuracy> = bind_ranges(sql_query(sql).into_boxed(), buckets.iter().map(|b| (b.start_ms, b.end_ms)))
+        .bind::<Text, _>(user_id)
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AccuracyPoint {
+            period_start: buckets[row.idx as usize].start.format("%Y-%m-%d").to_string(),
+            reviews: row.reviews,
+            correct: row.correct,
+            accuracy: (row.reviews > 0).then(|| row.correct as f64 / row.reviews as f64),
+        })
+        .collect())
+}
+
+#[derive(QueryableByName)]
+struct BucketWords {
+    #[diesel(sql_type = Integer)]
+    idx: i32,
+    #[diesel(sql_type = Text)]
+    video_id: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    video_title: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    words: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WordsAddedPoint {
+    /// First study day of the period, `YYYY-MM-DD`
+    pub period_start: String,
+    pub video_id: String,
+    /// `None` if the video has been deleted
+    pub video_title: Option<String>,
+    pub words: i64,
+}
+
+/// Vocabulary saved per period and video. Periods without new words have no points.
+pub fn words_added(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    video_id: Option<&str>,
+    buckets: &[Bucket],
+) -> QueryResult<Vec<WordsAddedPoint>> {
+    let sql = format!(
+        "{}
+         SELECT ranges.idx AS idx, v.video_id AS video_id, videos.title AS video_title, COUNT(*) AS words
+         FROM ranges
+         JOIN vocabulary v ON {created} >= ranges.start_ms AND {created} < ranges.end_ms
+         LEFT JOIN videos ON videos.id = v.video_id
+         WHERE v.user_id = ? AND (? IS NULL OR v.video_id = ?)
+         GROUP BY ranges.idx, v.video_id
+         ORDER BY ranges.idx, words DESC",
+        ranges_cte(buckets.len()),
+        created = CREATED_AT_MS
+    );
+    let rows: Vec<BucketWords> = bind_ranges(sql_query(sql).into_boxed(), buckets.iter().map(|b| (b.start_ms, b.end_ms)))
+        .bind::<Text, _>(user_id)
+        .bind::<Nullable<Text>, _>(video_id)
+        .bind::<Nullable<Text>, _>(video_id)
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| WordsAddedPoint {
+            period_start: buckets[row.idx as usize].start.format("%Y-%m-%d").to_string(),
+            video_id: row.video_id,
+            video_title: row.video_title,
+            words: row.words,
+        })
+        .collect())
+}
+
+#[derive(QueryableByName)]
+struct QuarterHour {
+    #[diesel(sql_type = BigInt)]
+    quarter: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudyStreaks {
+    pub current_days: u32,
+    pub longest_days: u32,
+    pub studied_today: bool,
+    /// Most recent study day with a review, `YYYY-MM-DD`
+    pub last_study_date: Option<String>,
+}
+
+/// Runs of consecutive study days with at least one review
+pub fn study_streaks(conn: &mut SqliteConnection, user_id: &str, study_day: &StudyDay) -> QueryResult<StudyStreaks> {
+    let quarters: Vec<QuarterHour> = sql_query(
+        "SELECT DISTINCT reviewed_at / ? AS quarter
+         FROM review_log
+         WHERE user_id = ? AND is_synthetic = 0
+         ORDER BY quarter",
+    )
+    .bind::<BigInt, _>(QUARTER_HOUR_MS)
+    .bind::<Text, _>(user_id)
+    .load(conn)?;
+
+    let mut dates: Vec<NaiveDate> = quarters
+        .iter()
+        .map(|q| study_day.date_of(timestamps::to_datetime(q.quarter * QUARTER_HOUR_MS)))
+        .collect();
+    dates.dedup();
+
+    let today = study_day.date_of(Utc::now());
+    let (current_days, longest_days) = streaks(&dates, today);
+    Ok(StudyStreaks {
+        current_days,
+        longest_days,
+        studied_today: dates.last() == Some(&today),
+        last_study_date: dates.last().map(|date| date.format("%Y-%m-%d").to_string()),
+    })
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct TimeToMastery {
+    /// Items that reached the top stage since reviews have been logged
+    #[diesel(sql_type = BigInt)]
+    pub mastered_items: i64,
+    /// Days from saving an item to the review that took it to the top stage
+    #[diesel(sql_type = Nullable<Double>)]
+    pub average_days: Option<f64>,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub fastest_days: Option<f64>,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub slowest_days: Option<f64>,
+    /// Logged reviews up to and including that review
+    #[diesel(sql_type = Nullable<Double>)]
+    pub average_reviews: Option<f64>,
+}
+
+/// How long items take to reach the top stage. Items already there when the review
+/// log was introduced are left out, their path there wasn't recorded.
+pub fn time_to_mastery(conn: &mut SqliteConnection, user_id: &str) -> QueryResult<TimeToMastery> {
+    let sql = format!(
+        "WITH mastered AS (
+             SELECT vocabulary_id, MIN(reviewed_at) AS mastered_at
+             FROM review_log
+             WHERE user_id = ?1 AND is_synthetic = 0 AND new_stage >= ?2
+               AND vocabulary_id NOT IN (
+                   SELECT vocabulary_id FROM review_log WHERE is_synthetic = 1 AND new_stage >= ?2
+               )
+             GROUP BY vocabulary_id
+         ),
+         durations AS (
+             SELECT (m.mastered_at - {created}) / 86400000.0 AS days,
+                    (SELECT COUNT(*) FROM review_log r
+                     WHERE r.vocabulary_id = m.vocabulary_id AND r.is_synthetic = 0
+                       AND r.reviewed_at <= m.mastered_at) AS reviews
+             FROM mastered m
+             JOIN vocabulary v ON v.id = m.vocabulary_id
+             WHERE v.created_at IS NOT NULL
+         )
+         SELECT COUNT(*) AS mastered_items,
+                AVG(days) AS average_days,
+                MIN(days) AS fastest_days,
+                MAX(days) AS slowest_days,
+                AVG(reviews) AS average_reviews
+         FROM durations",
+        created = CREATED_AT_MS
+    );
+    sql_query(sql)
+        .bind::<Text, _>(user_id)
+        .bind::<Integer, _>(MAX_STAGE)
+        .get_result(conn)
+}
+
+#[derive(QueryableByName)]
+struct BucketStudyTime {
+    #[diesel(sql_type = Integer)]
+    idx: i32,
+    #[diesel(sql_type = Text)]
+    activity: String,
+    #[diesel(sql_type = BigInt)]
+    duration_ms: i64,
+}
+
+/// Study time per period split by activity, one entry for every bucket including
+/// empty ones. Sessions count towards the period they started in.
+pub fn study_time(conn: &mut SqliteConnection, user_id: &str, buckets: &[Bucket]) -> QueryResult<Vec<DailyStudyTime>> {
+    let sql = format!(
+        "{}
+         SELECT ranges.idx AS idx, s.activity AS activity, SUM(s.duration_ms) AS duration_ms
+         FROM ranges
+         JOIN study_sessions s ON s.started_at >= ranges.start_ms AND s.started_at < ranges.end_ms
+         WHERE s.user_id = ?
+         GROUP BY ranges.idx, s.activity",
+        ranges_cte(buckets.len())
+    );
+    let rows: Vec<BucketStudyTime> = bind_ranges(sql_query(sql).into_boxed(), buckets.iter().map(|b| (b.start_ms, b.end_ms)))
+        .bind::<Text, _>(user_id)
+        .load(conn)?;
+
+    let mut days: Vec<DailyStudyTime> = buckets
+        .iter()
+        .map(|bucket| DailyStudyTime {
+            date: bucket.start.format("%Y-%m-%d").to_string(),
+            ..Default::default()
+        })
+        .collect();
+    for row in rows {
+        let day = &mut days[row.idx as usize];
+        day.total_ms += row.duration_ms;
+        match row.activity.as_str() {
+            "watching" => day.watching_ms += row.duration_ms,
+            "reviewing" => day.reviewing_ms += row.duration_ms,
+            "speaking" => day.speaking_ms += row.duration_ms,
+            _ => {}
+        }
+    }
+    Ok(days)
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use crate::analytics::*;
+    use crate::scheduler::StudyDay;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_period_start() {
+        // 2025-06-04 is a Wednesday
+        assert_eq!(period_start(Period::Day, date(2025, 6, 4)), date(2025, 6, 4));
+        assert_eq!(period_start(Period::Week, date(2025, 6, 4)), date(2025, 6, 2));
+        assert_eq!(period_start(Period::Month, date(2025, 6, 4)), date(2025, 6, 1));
+    }
+
+    #[test]
+    fn test_month_buckets_follow_the_study_day() {
+        let study_day = StudyDay::new("Asia/Shanghai", 4).unwrap();
+        let buckets = period_buckets(&study_day, Period::Month, 3, date(2025, 3, 15));
+        let starts: Vec<_> = buckets.iter().map(|b| b.start).collect();
+        assert_eq!(starts, vec![date(2025, 1, 1), date(2025, 2, 1), date(2025, 3, 1)]);
+        // March starts at 04:00 local, 20:00 UTC the day before
+        assert_eq!(buckets[2].start_ms, Utc.with_ymd_and_hms(2025, 2, 28, 20, 0, 0).unwrap().timestamp_millis());
+        assert_eq!(buckets[1].end_ms, buckets[2].start_ms);
+    }
+
+    #[test]
+    fn test_streaks() {
+        let dates = [date(2025, 6, 1), date(2025, 6, 2), date(2025, 6, 3), date(2025, 6, 6), date(2025, 6, 7)];
+        assert_eq!(streaks(&dates, date(2025, 6, 7)), (2, 3));
+        // Not studied yet today, yesterday's streak still counts
+        assert_eq!(streaks(&dates, date(2025, 6, 8)), (2, 3));
+        assert_eq!(streaks(&dates, date(2025, 6, 9)), (0, 3));
+        assert_eq!(streaks(&[], date(2025, 6, 9)), (0, 0));
+    }
+}
@@ -0,0 +1,327 @@
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Text};
+use diesel::sqlite::SqliteConnection;
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+
+use crate::error::{AppError, Result};
+use crate::models::vocabulary::Vocabulary;
+use crate::scheduler::ebbinghaus::interval_days;
+
+/// Fixed ids so re-importing an export updates the same note type and deck
+pub const MODEL_ID: i64 = 1_760_000_000_001;
+pub const DECK_ID: i64 = 1_760_000_000_002;
+
+/// Anki's default ease for review cards, in permille
+const DEFAULT_FACTOR: i32 = 2500;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// Note fields in order, each vocabulary item becomes one note
+pub const FIELDS: [&str; 7] = ["Word", "Sentence", "Translation", "Context", "Dictionary", "Audio", "Image"];
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE col (id integer primary key, crt integer not null, mod integer not null, scm integer not null,
+        ver integer not null, dty integer not null, usn integer not null, ls integer not null, conf text not null,
+        models text not null, decks text not null, dconf text not null, tags text not null)",
+    "CREATE TABLE notes (id integer primary key, guid text not null, mid integer not null, mod integer not null,
+        usn integer not null, tags text not null, flds text not null, sfld integer not null, csum integer not null,
+        flags integer not null, data text not null)",
+    "CREATE TABLE cards (id integer primary key, nid integer not null, did integer not null, ord integer not null,
+        mod integer not null, usn integer not null, type integer not null, queue integer not null, due integer not null,
+        ivl integer not null, factor integer not null, reps integer not null, lapses integer not null,
+        left integer not null, odue integer not null, odid integer not null, flags integer not null, data text not null)",
+    "CREATE TABLE revlog (id integer primary key, cid integer not null, usn integer not null, ease integer not null,
+        ivl integer not null, lastIvl integer not null, factor integer not null, time integer not null, type integer not null)",
+    "CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null)",
+    "CREATE INDEX ix_notes_usn ON notes (usn)",
+    "CREATE INDEX ix_cards_usn ON cards (usn)",
+    "CREATE INDEX ix_revlog_usn ON revlog (usn)",
+    "CREATE INDEX ix_cards_nid ON cards (nid)",
+    "CREATE INDEX ix_cards_sched ON cards (did, queue, due)",
+    "CREATE INDEX ix_revlog_cid ON revlog (cid)",
+    "CREATE INDEX ix_notes_csum ON notes (csum)",
+];
+
+/// Scheduling columns of an Anki card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CardSchedule {
+    /// 0 = new, 2 = review
+    pub card_type: i32,
+    /// Same as `card_type`, or -1 for suspended cards
+    pub queue: i32,
+    /// New cards: position in the new queue. Review cards: days after the collection's creation day.
+    pub due: i64,
+    /// Interval in days, 0 for new cards
+    pub interval: i64,
+    pub factor: i32,
+    pub reps: i32,
+    pub lapses: i32,
+}
+
+/// Map an item's schedule onto an Anki card. Items never reviewed become new cards;
+/// the rest become review cards due on the same day, with the interval they were
+/// last scheduled with (or the ladder interval of their stage if unknown).
+/// `today_start_ms` is the collection's creation day.
+pub fn card_schedule(item: &Vocabulary, today_start_ms: i64, new_position: i64) -> CardSchedule {
+    let reps = item.review_count.unwrap_or(0);
+    let queue_unless_suspended = |queue| if item.suspended { -1 } else { queue };
+
+    if reps == 0 {
+        return CardSchedule {
+            card_type: 0,
+            queue: queue_unless_suspended(0),
+            due: new_position,
+            interval: 0,
+            factor: 0,
+            reps: 0,
+            lapses: 0,
+        };
+    }
+
+    let interval = item
+        .last_reviewed_at
+        .map(|last| ((item.next_review_at - last) as f64 / DAY_MS as f64).round() as i64)
+        .unwrap_or_else(|| interval_days(item.review_stage.unwrap_or(0)))
+        .max(1);
+
+    CardSchedule {
+        card_type: 2,
+        queue: queue_unless_suspended(2),
+        due: (item.next_review_at - today_start_ms).div_euclid(DAY_MS),
+        interval,
+        factor: DEFAULT_FACTOR,
+        reps,
+        lapses: item.lapses,
+    }
+}
+
+/// Anki's duplicate-check checksum: the first 8 hex digits of the SHA-1 of the sort field
+pub fn field_checksum(field: &str) -> i64 {
+    let digest = Sha1::digest(strip_html(field).as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as i64
+}
+
+fn strip_html(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "<br>")
+}
+
+/// The sentence as HTML with the saved word in bold. `word_start` is the word's
+/// offset in the sentence; if it doesn't point at the word the first match is used.
+pub fn highlight_word(sentence: &str, word: &str, word_start: Option<i32>) -> String {
+    let matches_at = |start: usize| {
+        start
+            .checked_add(word.len())
+            .and_then(|end| sentence.get(start..end))
+            .is_some_and(|found| found.eq_ignore_ascii_case(word))
+    };
+    let start = word_start
+        .and_then(|start| usize::try_from(start).ok())
+        .filter(|&start| matches_at(start))
+        .or_else(|| sentence.char_indices().map(|(i, _)| i).find(|&i| matches_at(i)));
+
+    match start {
+        Some(start) if !word.is_empty() => format!(
+            "{}<b>{}</b>{}",
+            escape_html(&sentence[..start]),
+            escape_html(&sentence[start..start + word.len()]),
+            escape_html(&sentence[start + word.len()..])
+        ),
+        _ => escape_html(sentence),
+    }
+}
+
+/// One note with its single card
+pub struct AnkiNote {
+    /// Stable across exports so Anki updates instead of duplicating notes
+    pub guid: String,
+    pub fields: [String; 7],
+    pub tags: Vec<String>,
+    pub schedule: CardSchedule,
+}
+
+fn models_json(now_secs: i64) -> serde_json::Value {
+    let fields: Vec<_> = FIELDS
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            serde_json::json!({
+                "name": name, "ord": ord, "sticky": false, "rtl": false,
+                "font": "Arial", "size": 20, "media": []
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        MODEL_ID.to_string(): {
+            "id": MODEL_ID,
+            "name": "Loud Mouth Vocabulary",
+            "type": 0,
+            "mod": now_secs,
+            "usn": -1,
+            "sortf": 0,
+            "did": DECK_ID,
+            "tmpls": [{
+                "name": "Recognition",
+                "ord": 0,
+                "qfmt": "<div class=\"word\">{{Word}}</div>\n<div class=\"sentence\">{{Sentence}}</div>\n{{Audio}}",
+                "afmt": "{{FrontSide}}\n<hr id=answer>\n<div class=\"translation\">{{Translation}}</div>\n<div class=\"context\">{{Context}}</div>\n<div class=\"dictionary\">{{Dictionary}}</div>\n{{Image}}",
+                "bqfmt": "", "bafmt": "", "did": null, "bfont": "", "bsize": 0
+            }],
+            "flds": fields,
+            "css": ".card { font-family: Arial; font-size: 20px; text-align: center; }\n.word { font-size: 28px; font-weight: bold; }\n.context, .dictionary { font-size: 16px; text-align: left; }\nimg { max-width: 100%; }",
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "latexsvg": false,
+            "req": [[0, "any", [0]]],
+            "tags": [],
+            "vers": []
+        }
+    })
+}
+
+fn deck_json(id: i64, name: &str, now_secs: i64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id, "name": name, "mod": now_secs, "usn": -1, "desc": "", "dyn": 0, "conf": 1,
+        "collapsed": false, "newToday": [0, 0], "revToday": [0, 0], "lrnToday": [0, 0],
+        "timeToday": [0, 0], "extendNew": 10, "extendRev": 50
+    })
+}
+
+fn dconf_json() -> serde_json::Value {
+    serde_json::json!({
+        "1": {
+            "id": 1, "name": "Default", "mod": 0, "usn": 0, "maxTaken": 60, "autoplay": true,
+            "timer": 0, "replayq": true, "dyn": false,
+            "new": { "delays": [1, 10], "ints": [1, 4, 7], "initialFactor": DEFAULT_FACTOR, "order": 1, "perDay": 20, "bury": true, "separate": true },
+            "rev": { "perDay": 200, "ease4": 1.3, "fuzz": 0.05, "maxIvl": 36500, "ivlFct": 1, "bury": true, "minSpace": 1 },
+            "lapse": { "delays": [10], "mult": 0, "minInt": 1, "leechFails": 8, "leechAction": 1 }
+        }
+    })
+}
+
+fn collection_error(e: impl ToString) -> AppError {
+    AppError::new("APKG_EXPORT_ERROR", "Failed to write Anki collection").with_details(e.to_string())
+}
+
+/// Create the `collection.anki2` SQLite database of a package at `path`, in the
+/// schema 11 layout every Anki version can import, holding `notes` in a deck called `deck_name`
+pub fn write_collection(path: &Path, deck_name: &str, notes: &[AnkiNote], today_start_ms: i64) -> Result<()> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let now_secs = now_ms / 1000;
+
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy()).map_err(collection_error)?;
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        for statement in SCHEMA {
+            diesel::sql_query(*statement).execute(conn)?;
+        }
+
+        let conf = serde_json::json!({
+            "nextPos": notes.len() + 1, "estTimes": true, "activeDecks": [DECK_ID], "sortType": "noteFld",
+            "timeLim": 0, "sortBackwards": false, "addToCur": true, "curDeck": DECK_ID, "newBury": true,
+            "newSpread": 0, "dueCounts": true, "curModel": MODEL_ID.to_string(), "collapseTime": 1200
+        });
+        let decks = serde_json::json!({
+            "1": deck_json(1, "Default", now_secs),
+            DECK_ID.to_string(): deck_json(DECK_ID, deck_name, now_secs),
+        });
+        diesel::sql_query(
+            "INSERT INTO col (id, crt, mod, scm, ver, dty, usn, ls, conf, models, decks, dconf, tags)
+             VALUES (1, ?, ?, ?, 11, 0, 0, 0, ?, ?, ?, ?, '{}')",
+        )
+        .bind::<BigInt, _>(today_start_ms / 1000)
+        .bind::<BigInt, _>(now_ms)
+        .bind::<BigInt, _>(now_ms)
+        .bind::<Text, _>(conf.to_string())
+        .bind::<Text, _>(models_json(now_secs).to_string())
+        .bind::<Text, _>(decks.to_string())
+        .bind::<Text, _>(dconf_json().to_string())
+        .execute(conn)?;
+
+        for (index, note) in notes.iter().enumerate() {
+            // Anki ids are creation times in milliseconds, offset to keep them unique
+            let id = now_ms + index as i64;
+            let tags = if note.tags.is_empty() { String::new() } else { format!(" {} ", note.tags.join(" ")) };
+            diesel::sql_query(
+                "INSERT INTO notes (id, guid, mid, mod, usn, tags, flds, sfld, csum, flags, data)
+                 VALUES (?, ?, ?, ?, -1, ?, ?, ?, ?, 0, '')",
+            )
+            .bind::<BigInt, _>(id)
+            .bind::<Text, _>(&note.guid)
+            .bind::<BigInt, _>(MODEL_ID)
+            .bind::<BigInt, _>(now_secs)
+            .bind::<Text, _>(tags)
+            .bind::<Text, _>(note.fields.join("\u{1f}"))
+            .bind::<Text, _>(strip_html(&note.fields[0]))
+            .bind::<BigInt, _>(field_checksum(&note.fields[0]))
+            .execute(conn)?;
+
+            let schedule = note.schedule;
+            diesel::sql_query(
+                "INSERT INTO cards (id, nid, did, ord, mod, usn, type, queue, due, ivl, factor, reps, lapses, left, odue, odid, flags, data)
+                 VALUES (?, ?, ?, 0, ?, -1, ?, ?, ?, ?, ?, ?, ?, 0, 0, 0, 0, '')",
+            )
+            .bind::<BigInt, _>(id)
+            .bind::<BigInt, _>(id)
+            .bind::<BigInt, _>(DECK_ID)
+            .bind::<BigInt, _>(now_secs)
+            .bind::<Integer, _>(schedule.card_type)
+            .bind::<Integer, _>(schedule.queue)
+            .bind::<BigInt, _>(schedule.due)
+            .bind::<BigInt, _>(schedule.interval)
+            .bind::<Integer, _>(schedule.factor)
+            .bind::<Integer, _>(schedule.reps)
+            .bind::<Integer, _>(schedule.lapses)
+            .execute(conn)?;
+        }
+        Ok(())
+    })
+    .map_err(collection_error)
+}
+
+/// Zip a collection and its media into an `.apkg`. Media is `(name used in fields, file)`.
+pub fn write_package(apkg_path: &Path, collection_path: &Path, media: &[(String, PathBuf)]) -> Result<()> {
+    let package_error =
+        |e: &dyn ToString| AppError::new("APKG_EXPORT_ERROR", "Failed to write Anki package").with_details(e.to_string());
+
+    let file = File::create(apkg_path).map_err(|e| package_error(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("collection.anki2", options).map_err(|e| package_error(&e))?;
+    io::copy(&mut File::open(collection_path)?, &mut zip)?;
+
+    // Media files are stored under their index, `media` maps indexes to names
+    let mut media_map = serde_json::Map::new();
+    for (index, (name, path)) in media.iter().enumerate() {
+        zip.start_file(index.to_string(), options).map_err(|e| package_error(&e))?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+        media_map.insert(index.to_string(), serde_json::Value::String(name.clone()));
+    }
+    zip.start_file("media", options).map_err(|e| package_error(&e))?;
+    zip.write_all(serde_json::Value::Object(media_map).to_string().as_bytes())?;
+
+    zip.finish().map_err(|e| package_error(&e))?;
+    Ok(())
+}
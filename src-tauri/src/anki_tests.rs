@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use crate::anki::*;
+
+    #[test]
+    fn test_field_checksum_ignores_html() {
+        // sha1("hello") = aaf4c61d...
+        assert_eq!(field_checksum("hello"), 0xaaf4c61d);
+        assert_eq!(field_checksum("<b>hello</b>"), field_checksum("hello"));
+    }
+
+    #[test]
+    fn test_highlight_word() {
+        assert_eq!(highlight_word("I run & jump", "run", Some(2)), "I <b>run</b> &amp; jump");
+        // A stale offset falls back to the first match
+        assert_eq!(highlight_word("Run, run!", "run", Some(40)), "<b>Run</b>, run!");
+        assert_eq!(highlight_word("<no match>", "run", None), "&lt;no match&gt;");
+        assert_eq!(highlight_word("I run", "run", Some(-3)), "I <b>run</b>");
+        assert_eq!(highlight_word("I run", "run", Some(i32::MAX)), "I <b>run</b>");
+        assert_eq!(highlight_word("I walk", "run", Some(-1)), "I walk");
+    }
+
+    #[test]
+    fn test_escape_html_keeps_line_breaks() {
+        assert_eq!(escape_html("a \"b\"\nc"), "a &quot;b&quot;<br>c");
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::error::{AppError, Result};
+
+/// Length of the clip when no subtitle cue covers the vocabulary timestamp
+pub const FALLBACK_CLIP_MS: i64 = 3000;
+
+/// Largest accepted padding around a cue
+pub const MAX_PADDING_MS: i64 = 5000;
+
+/// How far before a cue's start a timestamp may fall and still be matched to it
+const CUE_MATCH_TOLERANCE_MS: i64 = 1500;
+
+/// A subtitle cue's time range in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cue {
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+/// Parse a WebVTT timestamp (`HH:MM:SS.mmm` or `MM:SS.mmm`) into milliseconds
+pub fn parse_vtt_time(time: &str) -> Option<i64> {
+    let (clock, millis) = time.trim().split_once('.')?;
+    let millis: i64 = millis.get(..3)?.parse().ok()?;
+
+    let parts = clock
+        .split(':')
+        .map(|p| p.parse::<i64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let seconds = match parts.as_slice() {
+        [h, m, s] => h * 3600 + m * 60 + s,
+        [m, s] => m * 60 + s,
+        _ => return None,
+    };
+
+    Some(seconds * 1000 + millis)
+}
+
+/// Time ranges of all cues in a WebVTT document, in file order
+pub fn parse_vtt_cues(content: &str) -> Vec<Cue> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (start, rest) = line.split_once("-->")?;
+            // Cue settings (`align:start` ...) may follow the end time
+            let end = rest.split_whitespace().next()?;
+            Some(Cue {
+                start_ms: parse_vtt_time(start)?,
+                end_ms: parse_vtt_time(end)?,
+            })
+        })
+        .collect()
+}
+
+/// The cue a vocabulary timestamp belongs to: the one containing it, otherwise
+/// the next cue starting shortly after it
+pub fn find_cue(cues: &[Cue], timestamp_ms: i64) -> Option<Cue> {
+    cues.iter()
+        .find(|cue| cue.start_ms <= timestamp_ms && timestamp_ms < cue.end_ms)
+        .or_else(|| {
+            cues.iter()
+                .filter(|cue| cue.start_ms > timestamp_ms && cue.start_ms - timestamp_ms <= CUE_MATCH_TOLERANCE_MS)
+                .min_by_key(|cue| cue.start_ms)
+        })
+        .copied()
+}
+
+/// Pad a cue and clamp it to the video, `duration_ms` is unknown for some videos
+pub fn clip_range(cue: Cue, padding_ms: i64, duration_ms: Option<i64>) -> (i64, i64) {
+    let padding = padding_ms.clamp(0, MAX_PADDING_MS);
+    let start = (cue.start_ms - padding).max(0);
+    let mut end = cue.end_ms + padding;
+    if let Some(duration) = duration_ms.filter(|d| *d > start) {
+        end = end.min(duration);
+    }
+    (start, end)
+}
+
+pub fn get_audio_clip_directory(video_id: &str) -> PathBuf {
+    std::env::current_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("audio-clips")
+        .join(video_id)
+}
+
+/// Cached clip location, keyed by video and time range
+pub fn clip_path(video_id: &str, start_ms: i64, end_ms: i64) -> PathBuf {
+    get_audio_clip_directory(video_id).join(format!("{}-{}.m4a", start_ms, end_ms))
+}
+
+/// Cut the audio between `start_ms` and `end_ms` from a video into an AAC `.m4a` file
+pub fn extract_audio_clip(video_path: &str, start_ms: i64, end_ms: i64, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::new("FILESYSTEM_ERROR", "Failed to create audio clip directory")
+                .with_details(e.to_string()))?;
+    }
+
+    // Write next to the final path first so an interrupted cut never ends up in the cache
+    let partial_path = output_path.with_extension("partial.m4a");
+    let output = Command::new("ffmpeg")
+        .arg("-ss")
+        .arg(format!("{:.3}", start_ms as f64 / 1000.0))
+        .arg("-i")
+        .arg(video_path)
+        .arg("-t")
+        .arg(format!("{:.3}", (end_ms - start_ms) as f64 / 1000.0))
+        .arg("-map")
+        .arg("0:a:0")
+        .arg("-vn")
+        .arg("-c:a")
+        .arg("aac")
+        .arg("-b:a")
+        .arg("128k")
+        .arg("-y")
+        .arg(&partial_path)
+        .output()
+        .map_err(|e| AppError::new("FFMPEG_ERROR", "Failed to execute FFmpeg command")
+            .with_details(e.to_string()))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&partial_path);
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::new("FFMPEG_ERROR", "FFmpeg failed to extract audio clip")
+            .with_details(error_msg.to_string()));
+    }
+
+    std::fs::rename(&partial_path, output_path)
+        .map_err(|e| AppError::new("FILESYSTEM_ERROR", "Failed to store audio clip")
+            .with_details(e.to_string()))
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use crate::audio_clip::*;
+
+    const VTT: &str = "WEBVTT\n\n1\n00:00:01.000 --> 00:00:03.500\nHello there.\n\n2\n00:00:04.000 --> 00:00:06.250 align:start position:0%\nHow are you?\n\n3\n01:02:03.004 --> 01:02:05.000\nLate line\n";
+
+    #[test]
+    fn test_parse_vtt_time() {
+        assert_eq!(parse_vtt_time("00:00:01.500"), Some(1500));
+        assert_eq!(parse_vtt_time("01:02:03.004"), Some(3_723_004));
+        assert_eq!(parse_vtt_time("02:03.100"), Some(123_100));
+        assert_eq!(parse_vtt_time("garbage"), None);
+    }
+
+    #[test]
+    fn test_parse_vtt_cues_ignores_settings() {
+        let cues = parse_vtt_cues(VTT);
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues[1], Cue { start_ms: 4000, end_ms: 6250 });
+    }
+
+    #[test]
+    fn test_find_cue() {
+        let cues = parse_vtt_cues(VTT);
+        assert_eq!(find_cue(&cues, 2000), Some(cues[0]));
+        // Between cues, the upcoming one is close enough
+        assert_eq!(find_cue(&cues, 3700), Some(cues[1]));
+        assert_eq!(find_cue(&cues, 100_000), None);
+    }
+
+    #[test]
+    fn test_clip_range_pads_and_clamps() {
+        let cue = Cue { start_ms: 200, end_ms: 2000 };
+        assert_eq!(clip_range(cue, 300, None), (0, 2300));
+        assert_eq!(clip_range(cue, 300, Some(2100)), (0, 2100));
+        assert_eq!(clip_range(cue, 60_000, None), (0, 2000 + MAX_PADDING_MS));
+    }
+}
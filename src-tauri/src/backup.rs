@@ -0,0 +1,342 @@
+use diesel::migration::MigrationSource;
+use diesel::prelude::*;
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use diesel_migrations::MigrationHarness;
+use libsqlite3_sys as ffi;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::ptr;
+use std::time::Duration;
+use zip::write::FileOptions;
+
+use crate::app_error;
+use crate::database::MIGRATIONS;
+use crate::error::{AppError, Result};
+use crate::thumbnail;
+use crate::timestamps;
+
+/// Bumped when the archive layout changes, restores refuse archives from a newer version
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "database.sqlite";
+const THUMBNAILS_PREFIX: &str = "thumbnails";
+const SUBTITLES_PREFIX: &str = "subtitles";
+
+/// Extracted subtitles live in `subtitles/<video id>/` under the working directory
+pub const SUBTITLES_DIR: &str = "subtitles";
+
+/// Pages copied per backup step, between steps other connections can write
+const PAGES_PER_STEP: i32 = 256;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub created_at: i64,
+    /// Latest migration applied to the database in the archive
+    pub schema_version: Option<String>,
+    pub thumbnails: usize,
+    pub subtitles: usize,
+}
+
+fn backup_error(message: &str, details: impl Into<String>) -> AppError {
+    AppError::new("BACKUP_ERROR", message).with_details(details)
+}
+
+struct RawConnection(*mut ffi::sqlite3);
+
+impl RawConnection {
+    fn open(path: &Path, flags: i32) -> Result<Self> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|e| backup_error("Invalid database path", e.to_string()))?;
+        let mut db = ptr::null_mut();
+        let rc = unsafe { ffi::sqlite3_open_v2(c_path.as_ptr(), &mut db, flags, ptr::null()) };
+        // SQLite hands out a handle even when opening fails, it still has to be closed
+        let connection = Self(db);
+        if rc != ffi::SQLITE_OK {
+            return Err(backup_error("Failed to open database", connection.last_error()));
+        }
+        Ok(connection)
+    }
+
+    fn last_error(&self) -> String {
+        if self.0.is_null() {
+            return "out of memory".to_string();
+        }
+        unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.0)) }.to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for RawConnection {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::sqlite3_close(self.0);
+        }
+    }
+}
+
+/// Copy the database at `source` into `dest` with SQLite's online backup API, which
+/// gives a consistent snapshot while the app keeps using the database
+pub fn backup_database(source: &Path, dest: &Path) -> Result<()> {
+    if dest.exists() {
+        fs::remove_file(dest)?;
+    }
+    let source = RawConnection::open(source, ffi::SQLITE_OPEN_READWRITE)?;
+    let dest = RawConnection::open(dest, ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE)?;
+    unsafe {
+        ffi::sqlite3_busy_timeout(source.0, 10_000);
+    }
+
+    let main = CString::new("main").unwrap();
+    let backup = unsafe { ffi::sqlite3_backup_init(dest.0, main.as_ptr(), source.0, main.as_ptr()) };
+    if backup.is_null() {
+        return Err(backup_error("Failed to start database backup", dest.last_error()));
+    }
+    loop {
+        match unsafe { ffi::sqlite3_backup_step(backup, PAGES_PER_STEP) } {
+            ffi::SQLITE_OK => {}
+            ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => std::thread::sleep(Duration::from_millis(10)),
+            // Done, or an error that `sqlite3_backup_finish` reports
+            _ => break,
+        }
+    }
+    if unsafe { ffi::sqlite3_backup_finish(backup) } != ffi::SQLITE_OK {
+        return Err(backup_error("Failed to back up database", dest.last_error()));
+    }
+    Ok(())
+}
+
+/// Latest migration applied to a database, `None` before the first one
+pub fn schema_version(conn: &mut SqliteConnection) -> Result<Option<String>> {
+    let applied = conn
+        .applied_migrations()
+        .map_err(|e| backup_error("Failed to read applied migrations", e.to_string()))?;
+    Ok(applied.iter().map(|version| version.to_string()).max())
+}
+
+/// Files under `dir` as `(entry name, path)`, entry names start with `prefix`
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &name, files)?;
+        } else {
+            files.push((name, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+/// Zip a database snapshot together with the thumbnails and extracted subtitles
+pub fn write_archive(archive_path: &Path, database_snapshot: &Path, schema_version: Option<String>) -> Result<BackupManifest> {
+    let zip_error = |e: zip::result::ZipError| backup_error("Failed to write backup archive", e.to_string());
+
+    let mut thumbnails = Vec::new();
+    collect_files(&thumbnail::get_thumbnail_directory(), THUMBNAILS_PREFIX, &mut thumbnails)?;
+    let mut subtitles = Vec::new();
+    collect_files(Path::new(SUBTITLES_DIR), SUBTITLES_PREFIX, &mut subtitles)?;
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: timestamps::now_ms(),
+        schema_version,
+        thumbnails: thumbnails.len(),
+        subtitles: subtitles.len(),
+    };
+
+    let mut zip = zip::ZipWriter::new(File::create(archive_path)?);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(MANIFEST_ENTRY, options).map_err(zip_error)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest).unwrap_or_default().as_bytes())?;
+    zip.start_file(DATABASE_ENTRY, options).map_err(zip_error)?;
+    io::copy(&mut File::open(database_snapshot)?, &mut zip)?;
+    for (name, path) in thumbnails.iter().chain(&subtitles) {
+        zip.start_file(name.as_str(), options).map_err(zip_error)?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+    }
+    zip.finish().map_err(zip_error)?;
+
+    Ok(manifest)
+}
+
+fn open_archive(archive_path: &Path) -> Result<zip::ZipArchive<File>> {
+    zip::ZipArchive::new(File::open(archive_path)?)
+        .map_err(|e| app_error!("INVALID_BACKUP", "File is not a backup archive", e.to_string()))
+}
+
+/// Read and check the manifest of a backup archive
+pub fn read_manifest(archive_path: &Path) -> Result<BackupManifest> {
+    let mut archive = open_archive(archive_path)?;
+    let manifest: BackupManifest = {
+        let entry = archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(|_| app_error!("INVALID_BACKUP", "Backup archive has no manifest"))?;
+        serde_json::from_reader(entry)
+            .map_err(|e| app_error!("INVALID_BACKUP", "Backup manifest is malformed", e.to_string()))?
+    };
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(app_error!(
+            "BACKUP_TOO_NEW",
+            "Backup was made by a newer version of the app",
+            format!("format version {}, supported up to {}", manifest.format_version, BACKUP_FORMAT_VERSION)
+        ));
+    }
+    if archive.by_name(DATABASE_ENTRY).is_err() {
+        return Err(app_error!("INVALID_BACKUP", "Backup archive has no database"));
+    }
+    Ok(manifest)
+}
+
+/// Extract the archived database to `dest`
+pub fn extract_database(archive_path: &Path, dest: &Path) -> Result<()> {
+    let mut archive = open_archive(archive_path)?;
+    let mut entry = archive
+        .by_name(DATABASE_ENTRY)
+        .map_err(|_| app_error!("INVALID_BACKUP", "Backup archive has no database"))?;
+    io::copy(&mut entry, &mut File::create(dest)?)?;
+    Ok(())
+}
+
+/// Extract archived thumbnails and subtitles under `staging`, to be moved into their
+/// directories with `install_staged_files` once the restored database is in place
+pub fn stage_files(archive_path: &Path, staging: &Path) -> Result<()> {
+    extract_files_to(archive_path, &staging.join(THUMBNAILS_PREFIX), &staging.join(SUBTITLES_PREFIX))
+}
+
+/// Move files staged by `stage_files` into the thumbnail and subtitle directories,
+/// replacing files with the same name, and remove `staging`
+pub fn install_staged_files(staging: &Path) -> Result<()> {
+    install_staged_files_to(staging, &thumbnail::get_thumbnail_directory(), Path::new(SUBTITLES_DIR))
+}
+
+/// `install_staged_files` with the thumbnail and subtitle directories given
+pub(crate) fn install_staged_files_to(staging: &Path, thumbnails_dir: &Path, subtitles_dir: &Path) -> Result<()> {
+    move_files(&staging.join(THUMBNAILS_PREFIX), thumbnails_dir)?;
+    move_files(&staging.join(SUBTITLES_PREFIX), subtitles_dir)?;
+    // Nothing is staged for a backup without media
+    if staging.exists() {
+        fs::remove_dir_all(staging)?;
+    }
+    Ok(())
+}
+
+fn move_files(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            move_files(&entry.path(), &dest)?;
+        } else if fs::rename(entry.path(), &dest).is_err() {
+            // Renaming fails across file systems
+            fs::copy(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+/// Extract archived thumbnails and subtitles into the given directories, replacing
+/// files with the same name
+pub(crate) fn extract_files_to(archive_path: &Path, thumbnails_dir: &Path, subtitles_dir: &Path) -> Result<()> {
+    let mut archive = open_archive(archive_path)?;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| app_error!("INVALID_BACKUP", "Backup archive is damaged", e.to_string()))?;
+        // `enclosed_name` rejects absolute paths and `..` leaving the archive, plain
+        // components only keep "thumbnails/../x" from leaving its directory
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+            continue;
+        };
+        let (dir, rest) = if let Ok(rest) = name.strip_prefix(THUMBNAILS_PREFIX) {
+            (thumbnails_dir, rest)
+        } else if let Ok(rest) = name.strip_prefix(SUBTITLES_PREFIX) {
+            (subtitles_dir, rest)
+        } else {
+            continue;
+        };
+        if rest.as_os_str().is_empty() || !rest.components().all(|component| matches!(component, Component::Normal(_))) {
+            continue;
+        }
+        let dest = dir.join(rest);
+        if entry.is_dir() {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&dest)?)?;
+    }
+    Ok(())
+}
+
+/// Bring a restored database up to date: refuse it if it has migrations this build
+/// doesn't know, run the pending ones and point thumbnail and subtitle paths at this
+/// install's directories
+pub fn prepare_restored_database(path: &Path) -> Result<()> {
+    use crate::schema::{subtitles, videos};
+
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())
+        .map_err(|e| backup_error("Failed to open restored database", e.to_string()))?;
+
+    let known: HashSet<String> = MigrationSource::<Sqlite>::migrations(&MIGRATIONS)
+        .map_err(|e| backup_error("Failed to read migrations", e.to_string()))?
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect();
+    let applied = conn
+        .applied_migrations()
+        .map_err(|e| app_error!("INVALID_BACKUP", "Backup database has no migration history", e.to_string()))?;
+    if let Some(unknown) = applied.iter().find(|version| !known.contains(&version.to_string())) {
+        return Err(app_error!(
+            "BACKUP_TOO_NEW",
+            "Backup was made by a newer version of the app",
+            format!("unknown migration {}", unknown)
+        ));
+    }
+    conn.run_pending_migrations(MIGRATIONS)
+        .map_err(|e| AppError::new("MIGRATION_ERROR", "Failed to run database migrations").with_details(e.to_string()))?;
+
+    let file_name = |path: &str| Path::new(path).file_name().map(|name| name.to_owned());
+    let thumbnails_dir = thumbnail::get_thumbnail_directory();
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let thumbnails: Vec<(String, Option<String>)> = videos::table
+            .select((videos::id, videos::thumbnail_path))
+            .load(conn)?;
+        for (video_id, thumbnail_path) in thumbnails {
+            if let Some(name) = thumbnail_path.as_deref().and_then(file_name) {
+                diesel::update(videos::table.find(&video_id))
+                    .set(videos::thumbnail_path.eq(thumbnails_dir.join(name).to_string_lossy().to_string()))
+                    .execute(conn)?;
+            }
+        }
+
+        let subtitle_files: Vec<(String, String, String)> = subtitles::table
+            .select((subtitles::id, subtitles::video_id, subtitles::file_path))
+            .load(conn)?;
+        for (id, video_id, file_path) in subtitle_files {
+            if let Some(name) = file_name(&file_path) {
+                let relative = Path::new(SUBTITLES_DIR).join(&video_id).join(name);
+                diesel::update(subtitles::table.find(&id))
+                    .set(subtitles::file_path.eq(relative.to_string_lossy().to_string()))
+                    .execute(conn)?;
+            }
+        }
+        Ok(())
+    })
+    .map_err(|e| backup_error("Failed to update file paths in restored database", e.to_string()))
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use crate::backup::*;
+    use crate::test_support::migrated_connection;
+    use crate::thumbnail;
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use uuid::Uuid;
+    use zip::write::FileOptions;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("loud-mouth-backup-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn manifest_json(format_version: u32) -> String {
+        format!(
+            r#"{{"format_version":{},"app_version":"0.1.0","created_at":"2026-10-18T09:30:00.000Z","schema_version":null,"thumbnails":0,"subtitles":0}}"#,
+            format_version
+        )
+    }
+
+    #[test]
+    fn test_read_manifest_rejects_newer_format_version() {
+        let dir = temp_dir();
+        let current = dir.join("current.zip");
+        write_zip(&current, &[("manifest.json", &manifest_json(BACKUP_FORMAT_VERSION)), ("database.sqlite", "")]);
+        assert_eq!(read_manifest(&current).unwrap().format_version, BACKUP_FORMAT_VERSION);
+
+        let newer = dir.join("newer.zip");
+        write_zip(&newer, &[("manifest.json", &manifest_json(BACKUP_FORMAT_VERSION + 1)), ("database.sqlite", "")]);
+        assert_eq!(read_manifest(&newer).unwrap_err().code, "BACKUP_TOO_NEW");
+
+        let no_database = dir.join("no-database.zip");
+        write_zip(&no_database, &[("manifest.json", &manifest_json(BACKUP_FORMAT_VERSION))]);
+        assert_eq!(read_manifest(&no_database).unwrap_err().code, "INVALID_BACKUP");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_files_skips_paths_outside_their_directory() {
+        let dir = temp_dir();
+        let archive = dir.join("backup.zip");
+        write_zip(
+            &archive,
+            &[
+                ("thumbnails/video.jpg", "jpg"),
+                ("subtitles/video/en.vtt", "WEBVTT"),
+                ("../escaped.txt", "x"),
+                ("/absolute.txt", "x"),
+                ("thumbnails/../../escaped.txt", "x"),
+                ("thumbnails/../sibling.txt", "x"),
+                ("database.sqlite", "db"),
+            ],
+        );
+        let restore_dir = dir.join("restore");
+        let thumbnails_dir = restore_dir.join("thumbnails");
+        let subtitles_dir = restore_dir.join("subtitles");
+
+        extract_files_to(&archive, &thumbnails_dir, &subtitles_dir).unwrap();
+
+        assert_eq!(fs::read_to_string(thumbnails_dir.join("video.jpg")).unwrap(), "jpg");
+        assert_eq!(fs::read_to_string(subtitles_dir.join("video").join("en.vtt")).unwrap(), "WEBVTT");
+        assert!(!dir.join("escaped.txt").exists());
+        assert!(!restore_dir.join("sibling.txt").exists());
+        assert!(!restore_dir.join("database.sqlite").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_staged_files_are_moved_into_place() {
+        let dir = temp_dir();
+        let archive = dir.join("backup.zip");
+        write_zip(
+            &archive,
+            &[("thumbnails/video.jpg", "new"), ("subtitles/video/en.vtt", "WEBVTT"), ("database.sqlite", "db")],
+        );
+        let thumbnails_dir = dir.join("thumbnails");
+        let subtitles_dir = dir.join("subtitles");
+        fs::create_dir_all(&thumbnails_dir).unwrap();
+        fs::write(thumbnails_dir.join("video.jpg"), "old").unwrap();
+        fs::write(thumbnails_dir.join("other.jpg"), "other").unwrap();
+        let staging = dir.join("staging");
+
+        stage_files(&archive, &staging).unwrap();
+        assert_eq!(fs::read_to_string(thumbnails_dir.join("video.jpg")).unwrap(), "old");
+        assert!(!subtitles_dir.exists());
+
+        install_staged_files_to(&staging, &thumbnails_dir, &subtitles_dir).unwrap();
+        assert_eq!(fs::read_to_string(thumbnails_dir.join("video.jpg")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(thumbnails_dir.join("other.jpg")).unwrap(), "other");
+        assert_eq!(fs::read_to_string(subtitles_dir.join("video").join("en.vtt")).unwrap(), "WEBVTT");
+        assert!(!staging.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prepare_restored_database_rewrites_file_paths() {
+        use crate::schema::{subtitles, videos};
+
+        let dir = temp_dir();
+        let path = dir.join("restored.sqlite");
+        {
+            let mut conn = migrated_connection(&path.to_string_lossy());
+            diesel::insert_into(videos::table)
+                .values((
+                    videos::id.eq("video-1"),
+                    videos::user_id.eq(1),
+                    videos::title.eq("Episode 1"),
+                    videos::filename.eq("episode-1.mp4"),
+                    videos::original_name.eq("episode-1.mp4"),
+                    videos::path.eq("/media/episode-1.mp4"),
+                    videos::size.eq(1),
+                    videos::mtime.eq("2026-10-18T09:30:00Z"),
+                    videos::thumbnail_path.eq("/home/old/app/thumbnails/video-1.jpg"),
+                ))
+                .execute(&mut conn)
+                .unwrap();
+            diesel::insert_into(subtitles::table)
+                .values((
+                    subtitles::id.eq("subtitle-1"),
+                    subtitles::video_id.eq("video-1"),
+                    subtitles::language.eq("en"),
+                    subtitles::file_path.eq("/home/old/app/subtitles/video-1/en.vtt"),
+                ))
+                .execute(&mut conn)
+                .unwrap();
+        }
+
+        prepare_restored_database(&path).unwrap();
+
+        let mut conn = SqliteConnection::establish(&path.to_string_lossy()).unwrap();
+        let thumbnail_path: Option<String> = videos::table
+            .find("video-1")
+            .select(videos::thumbnail_path)
+            .first(&mut conn)
+            .unwrap();
+        let expected = thumbnail::get_thumbnail_directory().join("video-1.jpg");
+        assert_eq!(thumbnail_path.as_deref(), Some(expected.to_string_lossy().as_ref()));
+        let file_path: String = subtitles::table
+            .find("subtitle-1")
+            .select(subtitles::file_path)
+            .first(&mut conn)
+            .unwrap();
+        let expected = Path::new(SUBTITLES_DIR).join("video-1").join("en.vtt");
+        assert_eq!(file_path, expected.to_string_lossy());
+
+        drop(conn);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
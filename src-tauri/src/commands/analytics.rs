@@ -0,0 +1,67 @@
+use chrono::Utc;
+use diesel::sqlite::SqliteConnection;
+
+use crate::{
+    analytics::{self, AccuracyPoint, Bucket, Period, StageRetention, StudyStreaks, TimeToMastery, WordsAddedPoint},
+    commands::settings::{load_user_settings, settings_user_id},
+    database::establish_connection,
+    error::{AppError, Result},
+    timestamps,
+};
+
+const DEFAULT_PERIODS: u32 = 12;
+const MAX_PERIODS: u32 = 366;
+
+fn analytics_error(e: diesel::result::Error) -> AppError {
+    AppError::new("ANALYTICS_ERROR", "Failed to compute learning analytics").with_details(e.to_string())
+}
+
+/// The user's last `periods` periods in their timezone
+pub(crate) fn user_buckets(conn: &mut SqliteConnection, user_id: &str, period: Period, periods: Option<u32>) -> Result<Vec<Bucket>> {
+    let settings = load_user_settings(conn, settings_user_id(user_id))?;
+    let study_day = settings.study_day()?;
+    let count = periods.unwrap_or(DEFAULT_PERIODS).clamp(1, MAX_PERIODS) as usize;
+    Ok(analytics::period_buckets(&study_day, period, count, study_day.date_of(Utc::now())))
+}
+
+/// Share of graded reviews recalled at each stage, optionally since an RFC3339 time
+#[tauri::command]
+pub fn get_retention_by_stage(user_id: String, since: Option<String>) -> Result<Vec<StageRetention>> {
+    let since_ms = since.as_deref().map(timestamps::parse_rfc3339).transpose()?;
+    let mut conn = establish_connection()?;
+    analytics::retention_by_stage(&mut conn, &user_id, since_ms).map_err(analytics_error)
+}
+
+/// Review accuracy for each of the last `periods` days, weeks or months, oldest first
+#[tauri::command]
+pub fn get_accuracy_over_time(user_id: String, period: Period, periods: Option<u32>) -> Result<Vec<AccuracyPoint>> {
+    let mut conn = establish_connection()?;
+    let buckets = user_buckets(&mut conn, &user_id, period, periods)?;
+    analytics::accuracy_over_time(&mut conn, &user_id, &buckets).map_err(analytics_error)
+}
+
+/// Words saved per video in each of the last `periods` days, weeks or months
+#[tauri::command]
+pub fn get_words_added(
+    user_id: String,
+    period: Period,
+    periods: Option<u32>,
+    video_id: Option<String>,
+) -> Result<Vec<WordsAddedPoint>> {
+    let mut conn = establish_connection()?;
+    let buckets = user_buckets(&mut conn, &user_id, period, periods)?;
+    analytics::words_added(&mut conn, &user_id, video_id.as_deref(), &buckets).map_err(analytics_error)
+}
+
+#[tauri::command]
+pub fn get_study_streaks(user_id: String) -> Result<StudyStreaks> {
+    let mut conn = establish_connection()?;
+    let settings = load_user_settings(&mut conn, settings_user_id(&user_id))?;
+    analytics::study_streaks(&mut conn, &user_id, &settings.study_day()?).map_err(analytics_error)
+}
+
+#[tauri::command]
+pub fn get_time_to_mastery(user_id: String) -> Result<TimeToMastery> {
+    let mut conn = establish_connection()?;
+    analytics::time_to_mastery(&mut conn, &user_id).map_err(analytics_error)
+}
@@ -0,0 +1,88 @@
+use diesel::prelude::*;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::{
+    audio_clip::{self, Cue},
+    database::establish_connection,
+    error::{AppError, Result},
+    models::vocabulary::Vocabulary,
+};
+
+/// Padding used when the caller doesn't pass one
+pub(crate) const DEFAULT_PADDING_MS: i64 = 300;
+
+#[derive(Debug, Serialize)]
+pub struct SentenceAudioClip {
+    /// Absolute path of the `.m4a` clip, playable through the `stream` protocol
+    pub path: String,
+    pub mime_type: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Whether the clip was served from the on-disk cache
+    pub cached: bool,
+    /// Whether the range came from a subtitle cue rather than the fallback window
+    pub from_subtitles: bool,
+}
+
+fn find_sentence_cue(video_id: &str, timestamp_ms: i64) -> Option<Cue> {
+    let subtitle_path = PathBuf::from("subtitles")
+        .join(video_id)
+        .join(format!("{}.english.vtt", video_id));
+    let content = std::fs::read_to_string(subtitle_path).ok()?;
+    audio_clip::find_cue(&audio_clip::parse_vtt_cues(&content), timestamp_ms)
+}
+
+/// Extract the original audio of a vocabulary item's sentence for shadowing practice
+#[tauri::command]
+pub async fn get_sentence_audio_clip(vocabulary_id: String, padding_ms: Option<i64>) -> Result<SentenceAudioClip> {
+    use crate::schema::{videos, vocabulary};
+
+    let mut conn = establish_connection()?;
+    let item: Vocabulary = vocabulary::table
+        .filter(vocabulary::id.eq(&vocabulary_id))
+        .first(&mut *conn)
+        .optional()?
+        .ok_or_else(|| AppError::new("VOCABULARY_NOT_FOUND", "Vocabulary item not found")
+            .with_details(vocabulary_id.clone()))?;
+
+    let (video_path, duration): (String, Option<i32>) = videos::table
+        .filter(videos::id.eq(&item.video_id))
+        .select((videos::path, videos::duration))
+        .first(&mut *conn)
+        .optional()?
+        .ok_or_else(|| AppError::new("VIDEO_NOT_FOUND", "Video for vocabulary item not found")
+            .with_details(item.video_id.clone()))?;
+    drop(conn);
+
+    sentence_clip(&item, &video_path, duration, padding_ms.unwrap_or(DEFAULT_PADDING_MS))
+}
+
+/// Cut (or reuse the cached) clip of an item's sentence from its video
+pub(crate) fn sentence_clip(item: &Vocabulary, video_path: &str, duration: Option<i32>, padding_ms: i64) -> Result<SentenceAudioClip> {
+    let timestamp_ms = item.timestamp as i64;
+    let cue = find_sentence_cue(&item.video_id, timestamp_ms);
+    let from_subtitles = cue.is_some();
+    let cue = cue.unwrap_or(Cue {
+        start_ms: timestamp_ms,
+        end_ms: timestamp_ms + audio_clip::FALLBACK_CLIP_MS,
+    });
+
+    let duration_ms = duration.filter(|d| *d > 0).map(|d| d as i64 * 1000);
+    let (start_ms, end_ms) = audio_clip::clip_range(cue, padding_ms, duration_ms);
+
+    let clip_path = audio_clip::clip_path(&item.video_id, start_ms, end_ms);
+    let cached = clip_path.exists();
+    if !cached {
+        audio_clip::extract_audio_clip(video_path, start_ms, end_ms, &clip_path)?;
+    }
+
+    Ok(SentenceAudioClip {
+        path: clip_path.to_string_lossy().to_string(),
+        mime_type: "audio/mp4".to_string(),
+        start_ms,
+        end_ms,
+        cached,
+        from_subtitles,
+    })
+}
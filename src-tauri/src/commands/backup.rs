@@ -0,0 +1,93 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::{
+    app_error,
+    backup::{self, BackupManifest},
+    database,
+    error::{AppError, Result},
+    snapshots,
+    timestamps,
+};
+
+#[derive(Serialize, Debug)]
+pub struct BackupSummary {
+    pub path: String,
+    pub size_bytes: u64,
+    #[serde(flatten)]
+    pub manifest: BackupManifest,
+}
+
+/// Back up the database, thumbnails and extracted subtitles into a zip archive at
+/// `path`. Video files aren't included, restored videos point at their original files.
+#[tauri::command]
+pub async fn create_backup(path: String) -> Result<BackupSummary> {
+    let archive_path = PathBuf::from(&path);
+    if let Some(parent) = archive_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let snapshot = std::env::temp_dir().join(format!("loud-mouth-backup-{}.sqlite", Uuid::new_v4()));
+    let written = backup::backup_database(&database::database_path(), &snapshot).and_then(|_| {
+        let mut conn = SqliteConnection::establish(&snapshot.to_string_lossy())
+            .map_err(|e| AppError::new("BACKUP_ERROR", "Failed to open database snapshot").with_details(e.to_string()))?;
+        let schema_version = backup::schema_version(&mut conn)?;
+        drop(conn);
+        backup::write_archive(&archive_path, &snapshot, schema_version)
+    });
+    let _ = fs::remove_file(&snapshot);
+    let manifest = written?;
+
+    Ok(BackupSummary {
+        size_bytes: fs::metadata(&archive_path)?.len(),
+        path,
+        manifest,
+    })
+}
+
+/// Replace all data with a backup made by `create_backup`. The current database is
+/// kept next to it as `database.before-restore-<time>.sqlite` when it can be copied.
+#[tauri::command]
+pub async fn restore_backup(path: String) -> Result<BackupManifest> {
+    let archive_path = PathBuf::from(&path);
+    let manifest = backup::read_manifest(&archive_path)?;
+
+    let db_path = database::database_path();
+    let staged = db_path.with_extension("sqlite.restore");
+    let staged_media = db_path.with_extension("restore-media");
+    let _ = fs::remove_dir_all(&staged_media);
+    let prepared = backup::extract_database(&archive_path, &staged)
+        .and_then(|_| backup::prepare_restored_database(&staged))
+        .and_then(|_| backup::stage_files(&archive_path, &staged_media));
+    if let Err(e) = prepared {
+        let _ = fs::remove_file(&staged);
+        let _ = fs::remove_dir_all(&staged_media);
+        return Err(e);
+    }
+
+    // A corrupt database may not copy cleanly, that mustn't block restoring over it
+    let time = timestamps::to_datetime(timestamps::now_ms()).format(snapshots::FILE_TIME_FORMAT);
+    let kept = db_path.with_extension(format!("before-restore-{}.sqlite", time));
+    if let Err(e) = backup::backup_database(&db_path, &kept) {
+        eprintln!("Failed to keep a copy of the database before restoring: {}", e);
+    }
+    if let Err(e) = database::replace_database(&staged) {
+        let _ = fs::remove_file(&staged);
+        let _ = fs::remove_dir_all(&staged_media);
+        return Err(e);
+    }
+    // Media is only moved in once the database it belongs to is in place
+    backup::install_staged_files(&staged_media).map_err(|e| {
+        app_error!(
+            "RESTORE_INCOMPLETE",
+            "The database was restored but some thumbnails and subtitles could not be copied",
+            e.details.unwrap_or(e.message)
+        )
+    })?;
+
+    Ok(manifest)
+}
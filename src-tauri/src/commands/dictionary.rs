@@ -0,0 +1,99 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::{
+    app_error,
+    dictionary::{
+        self, headword_key,
+        store::{self, Dictionary, DictionaryDefinition},
+        DictionaryFormat,
+    },
+    error::{AppError, Result},
+    lemmatizer,
+};
+
+#[derive(Serialize, Debug)]
+pub struct WordLookup {
+    pub word: String,
+    /// The lemma the definitions are for, when `word` itself isn't in any dictionary
+    pub lemma: Option<String>,
+    pub definitions: Vec<DictionaryDefinition>,
+    /// `false` when no dictionary has been imported yet, the caller should look the
+    /// word up online instead
+    pub dictionaries_installed: bool,
+}
+
+/// Load an ECDICT CSV, CC-CEDICT or StarDict `.ifo` file into the offline dictionary.
+/// The format is guessed from the file name unless given.
+#[tauri::command]
+pub async fn import_dictionary(path: String, format: Option<DictionaryFormat>, name: Option<String>) -> Result<Dictionary> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(app_error!("FILE_NOT_FOUND", "Dictionary file not found", path.display().to_string()));
+    }
+    let format = format.or_else(|| DictionaryFormat::detect(&path)).ok_or_else(|| {
+        app_error!(
+            "UNSUPPORTED_DICTIONARY_FORMAT",
+            "Unrecognized dictionary file, expected ECDICT .csv, CC-CEDICT .u8 or StarDict .ifo",
+            path.display().to_string()
+        )
+    })?;
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| dictionary::default_name(format, &path));
+
+    let mut conn = store::open()?;
+    store::import(&mut conn, format, &path, name)
+}
+
+#[tauri::command]
+pub fn list_dictionaries() -> Result<Vec<Dictionary>> {
+    if !store::exists()? {
+        return Ok(Vec::new());
+    }
+    let mut conn = store::open()?;
+    store::list(&mut conn)
+        .map_err(|e| AppError::new("DICTIONARY_ERROR", "Failed to list dictionaries").with_details(e.to_string()))
+}
+
+#[tauri::command]
+pub fn remove_dictionary(dictionary_id: String) -> Result<()> {
+    let mut conn = store::open()?;
+    if store::remove(&mut conn, &dictionary_id)? == 0 {
+        return Err(app_error!("DICTIONARY_NOT_FOUND", "Dictionary not found", dictionary_id));
+    }
+    Ok(())
+}
+
+/// Look a word or phrase up in the offline dictionaries. When it isn't a headword its
+/// lemma is tried, so "ran" finds "run". Without any dictionary installed the result
+/// is empty rather than an error.
+#[tauri::command]
+pub fn lookup_word(word: String) -> Result<WordLookup> {
+    let mut lookup = WordLookup {
+        word,
+        lemma: None,
+        definitions: Vec::new(),
+        dictionaries_installed: false,
+    };
+    if !store::exists()? {
+        return Ok(lookup);
+    }
+    let mut conn = store::open()?;
+    if store::list(&mut conn)?.is_empty() {
+        return Ok(lookup);
+    }
+    lookup.dictionaries_installed = true;
+
+    let key = headword_key(&lookup.word);
+    lookup.definitions = store::lookup(&mut conn, &key)?;
+    if lookup.definitions.is_empty() {
+        let lemma = lemmatizer::lemmatize(&lookup.word);
+        if !lemma.is_empty() && lemma != key {
+            lookup.definitions = store::lookup(&mut conn, &lemma)?;
+            lookup.lemma = (!lookup.definitions.is_empty()).then_some(lemma);
+        }
+    }
+    Ok(lookup)
+}
@@ -0,0 +1,198 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::{
+    anki::{self, AnkiNote},
+    app_error,
+    commands::audio_clip::{sentence_clip, DEFAULT_PADDING_MS},
+    commands::settings::{load_user_settings, settings_user_id},
+    commands::video::resolve_thumbnail_path,
+    commands::vocabulary::load_filtered_vocabulary,
+    database::establish_connection,
+    error::Result,
+    models::vocabulary::{Vocabulary, VocabularyFilter},
+    vocabulary_io::{self, ExportFormat, VocabularyRecord},
+};
+
+const DEFAULT_DECK_NAME: &str = "Loud Mouth";
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ApkgExportOptions {
+    pub deck_name: Option<String>,
+    /// Cut each sentence's audio from its video, needs FFmpeg and takes a while
+    pub include_audio: Option<bool>,
+    /// Add the video thumbnail to the back of each card, on by default
+    pub include_thumbnails: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApkgExportSummary {
+    pub path: String,
+    pub notes: usize,
+    pub media_files: usize,
+    /// Audio clips or thumbnails that couldn't be added, the notes are exported without them
+    pub skipped_media: usize,
+}
+
+struct VideoMedia {
+    path: String,
+    duration: Option<i32>,
+    thumbnail_path: Option<String>,
+}
+
+fn context_html(item: &Vocabulary) -> String {
+    [&item.before_2_en, &item.before_2_zh, &item.before_1_en, &item.before_1_zh]
+        .into_iter()
+        .flatten()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| anki::escape_html(line))
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+/// Export vocabulary as CSV, TSV or JSON, returned as the file's contents. The
+/// columns match what `import_vocabulary` reads.
+#[tauri::command]
+pub fn export_vocabulary(user_id: String, format: ExportFormat, filter: Option<VocabularyFilter>) -> Result<String> {
+    use crate::schema::videos;
+
+    let mut conn = establish_connection()?;
+    let items = load_filtered_vocabulary(&mut conn, &user_id, &filter.unwrap_or_default())?;
+    let video_ids: Vec<&str> = items.iter().map(|item| item.video_id.as_str()).collect();
+    let titles: HashMap<String, String> = videos::table
+        .filter(videos::id.eq_any(video_ids))
+        .select((videos::id, videos::title))
+        .load::<(String, String)>(&mut *conn)?
+        .into_iter()
+        .collect();
+
+    let records: Vec<VocabularyRecord> = items
+        .into_iter()
+        .map(|item| {
+            let title = titles.get(&item.video_id).cloned();
+            VocabularyRecord::from_vocabulary(item, title)
+        })
+        .collect();
+    vocabulary_io::write_records(format, &records)
+}
+
+/// Export vocabulary as an Anki deck package (`.apkg`) at `path`. Each item becomes
+/// a note with its sentence, translation, context, dictionary entry and optionally
+/// the sentence audio and video thumbnail, scheduled as far along as it is here.
+#[tauri::command]
+pub async fn export_vocabulary_apkg(
+    user_id: String,
+    filter: Option<VocabularyFilter>,
+    path: String,
+    options: Option<ApkgExportOptions>,
+) -> Result<ApkgExportSummary> {
+    use crate::schema::videos;
+
+    let options = options.unwrap_or_default();
+    let deck_name = options
+        .deck_name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_DECK_NAME.to_string());
+
+    let mut conn = establish_connection()?;
+    let items = load_filtered_vocabulary(&mut conn, &user_id, &filter.unwrap_or_default())?;
+    if items.is_empty() {
+        return Err(app_error!("NOTHING_TO_EXPORT", "No vocabulary matches the export filter"));
+    }
+
+    let video_ids: Vec<&str> = items.iter().map(|item| item.video_id.as_str()).collect();
+    let videos: HashMap<String, VideoMedia> = videos::table
+        .filter(videos::id.eq_any(video_ids))
+        .select((videos::id, videos::path, videos::duration, videos::thumbnail_path))
+        .load::<(String, String, Option<i32>, Option<String>)>(&mut *conn)?
+        .into_iter()
+        .map(|(id, path, duration, thumbnail_path)| (id, VideoMedia { path, duration, thumbnail_path }))
+        .collect();
+
+    let study_day = load_user_settings(&mut conn, settings_user_id(&user_id))?.study_day()?;
+    let today_start_ms = study_day.start_of(study_day.date_of(Utc::now())).timestamp_millis();
+    drop(conn);
+
+    let mut media: Vec<(String, PathBuf)> = Vec::new();
+    let mut thumbnails: HashMap<String, Option<String>> = HashMap::new();
+    let mut skipped_media = 0;
+    let mut notes = Vec::with_capacity(items.len());
+
+    for (index, item) in items.iter().enumerate() {
+        let video = videos.get(&item.video_id);
+
+        let mut audio = String::new();
+        if options.include_audio.unwrap_or(false) {
+            let clip = video
+                .ok_or_else(|| app_error!("VIDEO_NOT_FOUND", "Video for vocabulary item not found"))
+                .and_then(|video| sentence_clip(item, &video.path, video.duration, DEFAULT_PADDING_MS));
+            match clip {
+                Ok(clip) => {
+                    let name = format!("loud-mouth-{}.m4a", item.id.as_deref().unwrap_or_default());
+                    audio = format!("[sound:{}]", name);
+                    media.push((name, PathBuf::from(clip.path)));
+                }
+                Err(_) => skipped_media += 1,
+            }
+        }
+
+        let mut image = String::new();
+        if options.include_thumbnails.unwrap_or(true) {
+            let name = thumbnails.entry(item.video_id.clone()).or_insert_with(|| {
+                let thumbnail = video
+                    .and_then(|video| video.thumbnail_path.clone())
+                    .and_then(|path| resolve_thumbnail_path(path).ok())
+                    .filter(|path| Path::new(path).is_file())?;
+                let name = format!("loud-mouth-{}.jpg", item.video_id);
+                media.push((name.clone(), PathBuf::from(thumbnail)));
+                Some(name)
+            });
+            match name {
+                Some(name) => image = format!("<img src=\"{}\">", name),
+                None => skipped_media += 1,
+            }
+        }
+
+        let mut tags = vec!["loud-mouth".to_string()];
+        if item.is_leech {
+            tags.push("leech".to_string());
+        }
+
+        notes.push(AnkiNote {
+            guid: item.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string()),
+            fields: [
+                anki::escape_html(&item.word),
+                anki::highlight_word(&item.target_en, &item.word, item.word_start_index),
+                anki::escape_html(&item.target_zh),
+                context_html(item),
+                item.dictionary_response.as_deref().map(anki::escape_html).unwrap_or_default(),
+                audio,
+                image,
+            ],
+            tags,
+            schedule: anki::card_schedule(item, today_start_ms, index as i64 + 1),
+        });
+    }
+
+    let apkg_path = PathBuf::from(&path);
+    if let Some(parent) = apkg_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let collection_path = std::env::temp_dir().join(format!("loud-mouth-{}.anki2", Uuid::new_v4()));
+    let written = anki::write_collection(&collection_path, &deck_name, &notes, today_start_ms)
+        .and_then(|_| anki::write_package(&apkg_path, &collection_path, &media));
+    let _ = std::fs::remove_file(&collection_path);
+    written?;
+
+    Ok(ApkgExportSummary {
+        path,
+        notes: notes.len(),
+        media_files: media.len(),
+        skipped_media,
+    })
+}
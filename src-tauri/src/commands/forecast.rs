@@ -0,0 +1,110 @@
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Text};
+use serde::Serialize;
+
+use crate::{
+    analytics,
+    commands::settings::{load_user_settings, settings_user_id},
+    commands::vocabulary::load_accuracy_stats,
+    database::establish_connection,
+    error::{AppError, Result},
+    scheduler::ebbinghaus::MAX_STAGE,
+    scheduler::forecast::{self, STAGE_COUNT},
+};
+
+const DEFAULT_FORECAST_DAYS: u32 = 30;
+const MAX_FORECAST_DAYS: u32 = 365;
+
+#[derive(Serialize)]
+pub struct ForecastDay {
+    /// Study day, `YYYY-MM-DD` in the user's timezone
+    pub date: String,
+    /// Items whose `next_review_at` falls on this day; today includes everything overdue
+    pub scheduled: u64,
+    /// `scheduled` split by review stage, indexed by stage
+    pub by_stage: [u64; STAGE_COUNT],
+    /// Expected reviews including follow-ups of earlier days' reviews
+    pub expected: f64,
+}
+
+#[derive(Serialize)]
+pub struct ReviewForecast {
+    /// Share of correct answers the simulation assumes
+    pub accuracy: f64,
+    pub days: Vec<ForecastDay>,
+}
+
+#[derive(QueryableByName)]
+struct ScheduledCount {
+    #[diesel(sql_type = Integer)]
+    day_index: i32,
+    #[diesel(sql_type = Integer)]
+    stage: i32,
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// Per-day review load for the next `days` study days, counted from the stored
+/// schedule, and a simulation of the reviews those will cause at the user's accuracy
+#[tauri::command]
+pub fn get_review_forecast(user_id: String, days: Option<u32>) -> Result<ReviewForecast> {
+    let days = days.unwrap_or(DEFAULT_FORECAST_DAYS).clamp(1, MAX_FORECAST_DAYS) as usize;
+
+    let mut conn = establish_connection()?;
+    let settings = load_user_settings(&mut conn, settings_user_id(&user_id))?;
+    let study_day = settings.study_day()?;
+    let today = study_day.date_of(Utc::now());
+
+    // Today starts at the beginning of time to include overdue items
+    let dates: Vec<_> = (0..=days as i64).map(|offset| today + Duration::days(offset)).collect();
+    let bounds: Vec<(i64, i64)> = dates
+        .windows(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let start = if index == 0 { i64::MIN } else { study_day.start_of(pair[0]).timestamp_millis() };
+            (start, study_day.start_of(pair[1]).timestamp_millis())
+        })
+        .collect();
+
+    let sql = format!(
+        "{}
+         SELECT ranges.idx AS day_index,
+                MIN(MAX(COALESCE(v.review_stage, 0), 0), ?) AS stage,
+                COUNT(*) AS count
+         FROM ranges
+         JOIN vocabulary v ON v.next_review_at >= ranges.start_ms AND v.next_review_at < ranges.end_ms
+         WHERE v.user_id = ? AND v.suspended = 0
+         GROUP BY ranges.idx, stage",
+        analytics::ranges_cte(days)
+    );
+    let counts: Vec<ScheduledCount> = analytics::bind_ranges(diesel::sql_query(sql).into_boxed(), bounds)
+        .bind::<Integer, _>(MAX_STAGE)
+        .bind::<Text, _>(&user_id)
+        .load(&mut *conn)
+        .map_err(|e| AppError::new("FORECAST_ERROR", "Failed to count scheduled reviews").with_details(e.to_string()))?;
+
+    let mut scheduled = vec![[0u64; STAGE_COUNT]; days];
+    for row in counts {
+        scheduled[row.day_index as usize][row.stage as usize] = row.count as u64;
+    }
+
+    let stats = load_accuracy_stats(&mut conn, &user_id)?;
+    let accuracy = forecast::expected_accuracy(stats.total_reviews, stats.total_correct);
+    let expected = forecast::simulate_reviews(&scheduled, accuracy);
+
+    Ok(ReviewForecast {
+        accuracy,
+        days: dates
+            .into_iter()
+            .zip(scheduled)
+            .zip(expected)
+            .map(|((date, by_stage), expected)| ForecastDay {
+                date: date.format("%Y-%m-%d").to_string(),
+                scheduled: by_stage.iter().sum(),
+                by_stage,
+                expected,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,195 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    commands::settings::settings_user_id,
+    commands::vocabulary::find_duplicate,
+    database::establish_connection,
+    error::{AppError, Result},
+    timestamps,
+    vocabulary_io::{self, ExportFormat, VocabularyRecord},
+};
+
+const IMPORTED_VIDEO_TITLE: &str = "Imported vocabulary";
+
+/// A row that matches an item the user already has, it's left out of the import
+#[derive(Serialize, Debug)]
+pub struct ImportConflict {
+    /// 1-based position of the row in the file, not counting the header
+    pub row: usize,
+    pub video_id: String,
+    pub word: String,
+    pub timestamp: i32,
+    pub existing_id: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub conflicts: Vec<ImportConflict>,
+    pub errors: Vec<ImportRowError>,
+    /// Set when rows whose video couldn't be found were added to the placeholder video
+    pub placeholder_video_id: Option<String>,
+}
+
+/// The video that imported rows go to when their own video isn't in the library,
+/// created the first time it's needed
+fn ensure_placeholder_video(conn: &mut SqliteConnection, video_user_id: i32) -> QueryResult<String> {
+    use crate::schema::videos;
+
+    let id = format!("imported-{}", video_user_id);
+    let exists = videos::table.find(&id).count().get_result::<i64>(conn)? > 0;
+    if !exists {
+        let now = timestamps::to_rfc3339(timestamps::now_ms());
+        diesel::insert_into(videos::table)
+            .values((
+                videos::id.eq(&id),
+                videos::user_id.eq(video_user_id),
+                videos::title.eq(IMPORTED_VIDEO_TITLE),
+                videos::filename.eq(""),
+                videos::original_name.eq(""),
+                videos::path.eq(""),
+                videos::size.eq(0),
+                videos::mtime.eq(&now),
+                videos::upload_date.eq(&now),
+            ))
+            .execute(conn)?;
+    }
+    Ok(id)
+}
+
+/// Import vocabulary from a CSV, TSV or JSON export. Rows are matched to the user's
+/// videos, or videos they already have vocabulary for, by `video_id` and then by
+/// `video_title`; the rest go to a placeholder "imported" video. Invalid rows and rows
+/// for a word already saved from, or sighted at, the same video and timestamp are
+/// skipped and reported.
+#[tauri::command]
+pub fn import_vocabulary(user_id: String, format: ExportFormat, content: String) -> Result<ImportSummary> {
+    let rows = vocabulary_io::read_records(format, &content)?;
+    let mut conn = establish_connection()?;
+    import_records(&mut conn, &user_id, rows, timestamps::now_ms())
+}
+
+/// Import rows read by `vocabulary_io::read_records`, see `import_vocabulary`
+pub(crate) fn import_records(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    rows: Vec<std::result::Result<VocabularyRecord, String>>,
+    now_ms: i64,
+) -> Result<ImportSummary> {
+    use crate::schema::{videos, vocabulary};
+
+    let video_user_id = settings_user_id(user_id);
+    let user_videos: Vec<(String, String)> = videos::table
+        .filter(videos::user_id.eq(video_user_id))
+        .select((videos::id, videos::title))
+        .load(conn)?;
+    let mut video_ids: HashSet<String> = user_videos.iter().map(|(id, _)| id.clone()).collect();
+    let videos_by_title: HashMap<String, String> = user_videos
+        .into_iter()
+        .map(|(id, title)| (title.trim().to_lowercase(), id))
+        .collect();
+
+    // Vocabulary outlives a deleted video, its rows still belong to that video
+    video_ids.extend(
+        vocabulary::table
+            .filter(vocabulary::user_id.eq(user_id))
+            .select(vocabulary::video_id)
+            .distinct()
+            .load::<String>(conn)?,
+    );
+    let mut taken_ids: HashSet<String> = vocabulary::table
+        .select(vocabulary::id)
+        .load::<Option<String>>(conn)?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let mut summary = ImportSummary::default();
+    conn.immediate_transaction::<_, diesel::result::Error, _>(|conn| {
+        for (index, row) in rows.into_iter().enumerate() {
+            let row_number = index + 1;
+            let record = match row {
+                Ok(record) => record,
+                Err(message) => {
+                    summary.errors.push(ImportRowError { row: row_number, message });
+                    continue;
+                }
+            };
+
+            let known_video = record
+                .video_id
+                .clone()
+                .filter(|id| video_ids.contains(id))
+                .or_else(|| {
+                    let title = record.video_title.as_deref()?.trim().to_lowercase();
+                    videos_by_title.get(&title).cloned()
+                });
+            let video_id = match known_video {
+                Some(video_id) => video_id,
+                None => match &summary.placeholder_video_id {
+                    Some(placeholder) => placeholder.clone(),
+                    None => {
+                        let placeholder = ensure_placeholder_video(conn, video_user_id)?;
+                        summary.placeholder_video_id = Some(placeholder.clone());
+                        placeholder
+                    }
+                },
+            };
+
+            let mut new_vocabulary = match record.into_new_vocabulary(user_id, &video_id, now_ms) {
+                Ok(new_vocabulary) => new_vocabulary,
+                Err(e) => {
+                    let message = match e.details {
+                        Some(details) => format!("{}: {}", e.message, details),
+                        None => e.message,
+                    };
+                    summary.errors.push(ImportRowError { row: row_number, message });
+                    continue;
+                }
+            };
+
+            // Rows imported earlier in this file are already in the transaction and count too
+            let duplicate = find_duplicate(
+                conn,
+                user_id,
+                &new_vocabulary.video_id,
+                new_vocabulary.timestamp,
+                &new_vocabulary.word,
+            )?;
+            if let Some(existing) = duplicate {
+                summary.conflicts.push(ImportConflict {
+                    row: row_number,
+                    video_id: new_vocabulary.video_id,
+                    word: new_vocabulary.word,
+                    timestamp: new_vocabulary.timestamp,
+                    existing_id: existing.id.unwrap_or_default(),
+                });
+                continue;
+            }
+            // Ids are kept so re-imports line up, unless another item already has it
+            if taken_ids.contains(&new_vocabulary.id) {
+                new_vocabulary.id = Uuid::new_v4().to_string();
+            }
+
+            diesel::insert_into(vocabulary::table)
+                .values(&new_vocabulary)
+                .execute(conn)?;
+            taken_ids.insert(new_vocabulary.id);
+            summary.imported += 1;
+        }
+        Ok(())
+    })
+    .map_err(|e| AppError::new("VOCABULARY_IMPORT_ERROR", "Failed to import vocabulary").with_details(e.to_string()))?;
+
+    Ok(summary)
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::import::{import_records, ImportSummary};
+    use crate::commands::vocabulary::insert_vocabulary;
+    use crate::models::vocabulary::Vocabulary;
+    use crate::models::vocabulary_context::VocabularyContext;
+    use crate::test_support::{migrated_connection, new_vocabulary};
+    use crate::vocabulary_io::{read_records, ExportFormat};
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+
+    const NOW_MS: i64 = 1_792_315_800_000;
+
+    fn add_video(conn: &mut SqliteConnection, id: &str, title: &str) {
+        use crate::schema::videos;
+
+        diesel::insert_into(videos::table)
+            .values((
+                videos::id.eq(id),
+                videos::user_id.eq(1),
+                videos::title.eq(title),
+                videos::filename.eq(format!("{}.mp4", id)),
+                videos::original_name.eq(format!("{}.mp4", id)),
+                videos::path.eq(format!("/videos/{}.mp4", id)),
+                videos::size.eq(1024),
+                videos::mtime.eq("2026-10-18T09:30:00Z"),
+                videos::upload_date.eq("2026-10-18T09:30:00Z"),
+            ))
+            .execute(conn)
+            .unwrap();
+    }
+
+    fn import(conn: &mut SqliteConnection, json: &str) -> ImportSummary {
+        import_records(conn, "1", read_records(ExportFormat::Json, json).unwrap(), NOW_MS).unwrap()
+    }
+
+    fn find_word(conn: &mut SqliteConnection, word: &str) -> Vocabulary {
+        use crate::schema::vocabulary;
+
+        vocabulary::table
+            .filter(vocabulary::word.eq(word))
+            .select(Vocabulary::as_select())
+            .first(conn)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_import_skips_saved_and_sighted_words_and_reports_bad_rows() {
+        use crate::schema::vocabulary_contexts;
+
+        let mut conn = migrated_connection(":memory:");
+        add_video(&mut conn, "video-2", "Dogs");
+        let saved = insert_vocabulary(&mut conn, &new_vocabulary("Bark", "video-1", 1_000)).unwrap();
+        let saved_id = saved.id.clone().unwrap();
+        diesel::insert_into(vocabulary_contexts::table)
+            .values(VocabularyContext {
+                video_id: "video-2".to_string(),
+                timestamp: 2_000,
+                ..VocabularyContext::from_vocabulary(&saved, &saved_id, NOW_MS)
+            })
+            .execute(&mut conn)
+            .unwrap();
+
+        let summary = import(
+            &mut conn,
+            r#"[
+                {"video_id": "video-1", "word": "bark", "timestamp": 1000, "target_en": "The dog will bark"},
+                {"video_id": "video-2", "word": "BARK", "timestamp": 2000, "target_en": "Hear it bark"},
+                {"video_id": "video-2", "word": "howl", "timestamp": 2000, "target_en": "Wolves howl"},
+                {"video_id": "video-2", "word": "Howl ", "timestamp": 2000, "target_en": "Wolves howl"},
+                {"video_id": "video-2", "word": "yelp", "timestamp": 3000, "target_en": " "},
+                {"video_title": "dogs", "word": "growl", "timestamp": 4000, "target_en": "Dogs growl"},
+                {"video_id": "deleted", "video_title": "Cats", "word": "whine", "timestamp": 5000, "target_en": "Puppies whine"}
+            ]"#,
+        );
+
+        assert_eq!(summary.imported, 3);
+        let howl_id = find_word(&mut conn, "howl").id.unwrap();
+        let conflicts: Vec<(usize, &str)> = summary
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.row, conflict.existing_id.as_str()))
+            .collect();
+        assert_eq!(conflicts, vec![(1, saved_id.as_str()), (2, saved_id.as_str()), (4, howl_id.as_str())]);
+        assert_eq!(summary.errors.iter().map(|error| error.row).collect::<Vec<_>>(), vec![5]);
+
+        assert_eq!(find_word(&mut conn, "growl").video_id, "video-2");
+        assert_eq!(summary.placeholder_video_id.as_deref(), Some("imported-1"));
+        assert_eq!(find_word(&mut conn, "whine").video_id, "imported-1");
+    }
+
+    #[test]
+    fn test_import_keeps_ids_without_overwriting_other_items() {
+        let mut conn = migrated_connection(":memory:");
+        let mut existing = new_vocabulary("bark", "video-1", 1_000);
+        existing.id = "a".to_string();
+        insert_vocabulary(&mut conn, &existing).unwrap();
+        let file = r#"[
+            {"id": "a", "video_id": "video-1", "word": "howl", "timestamp": 2000, "target_en": "Wolves howl"},
+            {"id": "z", "video_id": "video-1", "word": "yelp", "timestamp": 3000, "target_en": "Puppies yelp"}
+        ]"#;
+
+        let summary = import(&mut conn, file);
+        assert_eq!((summary.imported, summary.conflicts.len()), (2, 0));
+        assert_eq!(find_word(&mut conn, "bark").id.as_deref(), Some("a"));
+        assert_ne!(find_word(&mut conn, "howl").id.as_deref(), Some("a"));
+        assert_eq!(find_word(&mut conn, "yelp").id.as_deref(), Some("z"));
+
+        // Importing the same file again adds nothing
+        let again = import(&mut conn, file);
+        assert_eq!((again.imported, again.conflicts.len()), (0, 2));
+        assert_eq!(again.conflicts[1].existing_id, "z");
+    }
+}
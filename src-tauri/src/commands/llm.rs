@@ -0,0 +1,262 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{
+    app_error,
+    database::establish_connection,
+    error::{AppError, Result},
+    llm::{self, LlmConfig, LlmLookup, RateLimiter},
+    models::llm_lookup::LlmCacheEntry,
+    timestamps,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+/// Error responses are cut to this many characters in error details
+const MAX_ERROR_BODY_CHARS: usize = 500;
+
+static RATE_LIMITER: Mutex<RateLimiter> = Mutex::new(RateLimiter::new(Duration::from_secs(60)));
+
+/// LLM config as the frontend sees it, the API key never leaves the backend
+#[derive(Serialize, Debug)]
+pub struct LlmSettings {
+    pub base_url: String,
+    pub model: String,
+    pub has_api_key: bool,
+    pub cache_ttl_days: i64,
+    pub requests_per_minute: u32,
+}
+
+impl From<LlmConfig> for LlmSettings {
+    fn from(config: LlmConfig) -> Self {
+        Self {
+            base_url: config.base_url,
+            model: config.model,
+            has_api_key: config.api_key.is_some(),
+            cache_ttl_days: config.cache_ttl_days,
+            requests_per_minute: config.requests_per_minute,
+        }
+    }
+}
+
+/// Fields to change, `None` leaves the stored value untouched
+#[derive(Deserialize, Default)]
+pub struct UpdateLlmSettings {
+    pub base_url: Option<String>,
+    /// An empty key removes it
+    pub api_key: Option<String>,
+    pub model: Option<String>,
+    pub cache_ttl_days: Option<i64>,
+    pub requests_per_minute: Option<u32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LlmLookupResult {
+    pub word: String,
+    #[serde(flatten)]
+    pub lookup: LlmLookup,
+    /// The lookup as stored in `dictionary_response`
+    pub markdown: String,
+    pub model: String,
+    /// Answered from the cache without calling the endpoint
+    pub cached: bool,
+}
+
+#[tauri::command]
+pub fn get_llm_settings() -> Result<LlmSettings> {
+    Ok(llm::load_config()?.into())
+}
+
+#[tauri::command]
+pub fn update_llm_settings(changes: UpdateLlmSettings) -> Result<LlmSettings> {
+    let mut config = llm::load_config()?;
+
+    if let Some(base_url) = changes.base_url {
+        let base_url = base_url.trim().trim_end_matches('/').to_string();
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(app_error!("VALIDATION_ERROR", "Endpoint must be an http(s) URL", base_url));
+        }
+        config.base_url = base_url;
+    }
+    if let Some(api_key) = changes.api_key {
+        config.api_key = Some(api_key.trim().to_string()).filter(|key| !key.is_empty());
+    }
+    if let Some(model) = changes.model {
+        if model.trim().is_empty() {
+            return Err(app_error!("VALIDATION_ERROR", "Model is required"));
+        }
+        config.model = model.trim().to_string();
+    }
+    if let Some(days) = changes.cache_ttl_days {
+        if days < 0 {
+            return Err(app_error!("VALIDATION_ERROR", "Cache lifetime cannot be negative"));
+        }
+        config.cache_ttl_days = days;
+    }
+    if let Some(limit) = changes.requests_per_minute {
+        if limit < 1 {
+            return Err(app_error!("VALIDATION_ERROR", "Rate limit must be at least 1 request per minute"));
+        }
+        config.requests_per_minute = limit;
+    }
+
+    llm::save_config(&config)?;
+    Ok(config.into())
+}
+
+fn load_cached(cache_key: &str, now_ms: i64) -> Result<Option<LlmLookup>> {
+    use crate::schema::llm_lookup_cache;
+
+    let mut conn = establish_connection()?;
+    let response: Option<String> = llm_lookup_cache::table
+        .find(cache_key)
+        .filter(llm_lookup_cache::expires_at.gt(now_ms))
+        .select(llm_lookup_cache::response)
+        .first(&mut *conn)
+        .optional()?;
+    // An entry that no longer parses is treated as a miss and overwritten
+    Ok(response.and_then(|response| serde_json::from_str(&response).ok()))
+}
+
+fn store_cached(entry: &LlmCacheEntry) -> Result<()> {
+    use crate::schema::llm_lookup_cache;
+
+    let mut conn = establish_connection()?;
+    diesel::delete(llm_lookup_cache::table.filter(llm_lookup_cache::expires_at.le(entry.created_at))).execute(&mut *conn)?;
+    diesel::replace_into(llm_lookup_cache::table)
+        .values(entry)
+        .execute(&mut *conn)?;
+    Ok(())
+}
+
+async fn request_lookup(config: &LlmConfig, word: &str, context: Option<&str>) -> Result<LlmLookup> {
+    if config.api_key.is_none() && config.base_url == llm::DEFAULT_BASE_URL {
+        return Err(app_error!("LLM_NOT_CONFIGURED", "Set an API key or a local endpoint for word lookups"));
+    }
+    let acquired = RATE_LIMITER
+        .lock()
+        .unwrap()
+        .try_acquire(config.requests_per_minute as usize, Instant::now());
+    if let Err(wait) = acquired {
+        return Err(app_error!(
+            "RATE_LIMITED",
+            "Too many word lookups, try again shortly",
+            format!("Retry in {}s", wait.as_secs() + 1)
+        ));
+    }
+
+    let request_error = |e: reqwest::Error| app_error!("LLM_REQUEST_ERROR", "Lookup request failed", e.to_string());
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(request_error)?;
+    let body = llm::chat_request_body(&config.model, &llm::build_prompt(word, context));
+    let mut request = client
+        .post(llm::chat_completions_url(&config.base_url))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string());
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request.send().await.map_err(request_error)?;
+    let status = response.status();
+    let body = response.text().await.map_err(request_error)?;
+    let excerpt = || format!("{}: {}", status, body.chars().take(MAX_ERROR_BODY_CHARS).collect::<String>());
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(app_error!("RATE_LIMITED", "The lookup service is rate limiting requests", excerpt()));
+    }
+    if !status.is_success() {
+        return Err(app_error!("LLM_REQUEST_ERROR", "Lookup request failed", excerpt()));
+    }
+    llm::parse_lookup(&llm::completion_content(&body)?)
+}
+
+/// Look a word or phrase up with the configured LLM endpoint, as used in `context`.
+/// Answers are cached per model, word and context. With `vocabulary_id` the answer is
+/// also saved as that item's `dictionary_response`; `refresh` skips the cache.
+#[tauri::command]
+pub async fn lookup_with_llm(
+    word: String,
+    context: Option<String>,
+    vocabulary_id: Option<String>,
+    refresh: Option<bool>,
+) -> Result<LlmLookupResult> {
+    let word = word.split_whitespace().collect::<Vec<_>>().join(" ");
+    if word.is_empty() {
+        return Err(app_error!("VALIDATION_ERROR", "word is required"));
+    }
+    let context = context.map(|sentence| sentence.trim().to_string()).filter(|sentence| !sentence.is_empty());
+    if let Some(vocabulary_id) = &vocabulary_id {
+        use crate::schema::vocabulary;
+
+        let mut conn = establish_connection()?;
+        let exists = vocabulary::table
+            .filter(vocabulary::id.eq(vocabulary_id))
+            .count()
+            .get_result::<i64>(&mut *conn)?
+            > 0;
+        if !exists {
+            return Err(app_error!("VOCABULARY_NOT_FOUND", "Vocabulary item not found", vocabulary_id.clone()));
+        }
+    }
+    let config = llm::load_config()?;
+    let cache_key = llm::cache_key(&config.model, &word, context.as_deref());
+    let use_cache = config.cache_ttl_days > 0;
+
+    let cached = if use_cache && !refresh.unwrap_or(false) {
+        load_cached(&cache_key, timestamps::now_ms())?
+    } else {
+        None
+    };
+    let (lookup, was_cached) = match cached {
+        Some(lookup) => (lookup, true),
+        None => {
+            let lookup = request_lookup(&config, &word, context.as_deref()).await?;
+            if use_cache {
+                let now_ms = timestamps::now_ms();
+                store_cached(&LlmCacheEntry {
+                    cache_key,
+                    model: config.model.clone(),
+                    word: word.clone(),
+                    response: serde_json::to_string(&lookup)
+                        .map_err(|e| app_error!("LLM_RESPONSE_ERROR", "Failed to cache lookup", e.to_string()))?,
+                    created_at: now_ms,
+                    expires_at: now_ms + config.cache_ttl_days * DAY_MS,
+                })?;
+            }
+            (lookup, false)
+        }
+    };
+
+    let markdown = lookup.to_markdown(llm::is_phrase(&word));
+    if let Some(vocabulary_id) = vocabulary_id {
+        use crate::schema::vocabulary;
+
+        let mut conn = establish_connection()?;
+        diesel::update(vocabulary::table.filter(vocabulary::id.eq(&vocabulary_id)))
+            .set(vocabulary::dictionary_response.eq(&markdown))
+            .execute(&mut *conn)?;
+    }
+
+    Ok(LlmLookupResult {
+        word,
+        lookup,
+        markdown,
+        model: config.model,
+        cached: was_cached,
+    })
+}
+
+/// Drop every cached lookup, returns how many there were
+#[tauri::command]
+pub fn clear_llm_cache() -> Result<usize> {
+    use crate::schema::llm_lookup_cache;
+
+    let mut conn = establish_connection()?;
+    diesel::delete(llm_lookup_cache::table)
+        .execute(&mut *conn)
+        .map_err(|e| AppError::new("LLM_CACHE_ERROR", "Failed to clear lookup cache").with_details(e.to_string()))
+}
@@ -2,11 +2,55 @@ pub mod video;
 pub mod vocabulary;
 pub mod speech;
 pub mod video_progress;
+pub mod settings;
+pub mod whisper_models;
+pub mod speaking_attempts;
+pub mod audio_clip;
+pub mod review_log;
+pub mod review_session;
+pub mod forecast;
+pub mod analytics;
+pub mod study_sessions;
+pub mod export;
+pub mod import;
+pub mod backup;
+pub mod snapshots;
+pub mod vocabulary_contexts;
+pub mod dictionary;
+pub mod llm;
 
 #[cfg(test)]
 mod tests;
+#[cfg(test)]
+mod import_tests;
+#[cfg(test)]
+mod review_log_tests;
+#[cfg(test)]
+mod review_session_tests;
+#[cfg(test)]
+mod study_sessions_tests;
+#[cfg(test)]
+mod vocabulary_contexts_tests;
+#[cfg(test)]
+mod vocabulary_tests;
 
 pub use video::*;
 pub use vocabulary::*;
 pub use speech::*;
-pub use video_progress::*;
\ No newline at end of file
+pub use video_progress::*;
+pub use settings::*;
+pub use whisper_models::*;
+pub use speaking_attempts::*;
+pub use audio_clip::*;
+pub use review_log::*;
+pub use review_session::*;
+pub use forecast::*;
+pub use analytics::*;
+pub use study_sessions::*;
+pub use export::*;
+pub use import::*;
+pub use backup::*;
+pub use snapshots::*;
+pub use vocabulary_contexts::*;
+pub use dictionary::*;
+pub use llm::*;
@@ -0,0 +1,117 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+
+use crate::{
+    app_error,
+    commands::review_session::reopen_session_item,
+    commands::settings::{load_user_settings, settings_user_id},
+    database::establish_connection,
+    error::{AppError, Result},
+    models::review_log::{ReviewLogEntry, ReviewSnapshot},
+    models::vocabulary::Vocabulary,
+    timestamps,
+};
+
+/// Largest page returned by `get_review_history`
+const MAX_HISTORY_LIMIT: i64 = 5000;
+
+/// Full review history of one vocabulary item, oldest first
+#[tauri::command]
+pub fn get_review_log(vocabulary_id: String) -> Result<Vec<ReviewLogEntry>> {
+    use crate::schema::review_log;
+
+    let mut conn = establish_connection()?;
+    review_log::table
+        .filter(review_log::vocabulary_id.eq(vocabulary_id))
+        .order(review_log::reviewed_at.asc())
+        .select(ReviewLogEntry::as_select())
+        .load(&mut *conn)
+        .map_err(|e| AppError::new("REVIEW_LOG_FETCH_ERROR", "Failed to fetch review log").with_details(e.to_string()))
+}
+
+/// A user's reviews, newest first. `since`/`until` are RFC3339 bounds on the review time,
+/// synthetic baseline entries are left out unless `include_synthetic` is set.
+#[tauri::command]
+pub fn get_review_history(
+    user_id: String,
+    since: Option<String>,
+    until: Option<String>,
+    limit: Option<i64>,
+    include_synthetic: Option<bool>,
+) -> Result<Vec<ReviewLogEntry>> {
+    use crate::schema::review_log;
+
+    let mut conn = establish_connection()?;
+    let mut query = review_log::table
+        .filter(review_log::user_id.eq(user_id))
+        .into_boxed();
+
+    if let Some(since) = since {
+        query = query.filter(review_log::reviewed_at.ge(timestamps::parse_rfc3339(&since)?));
+    }
+    if let Some(until) = until {
+        query = query.filter(review_log::reviewed_at.lt(timestamps::parse_rfc3339(&until)?));
+    }
+    if !include_synthetic.unwrap_or(false) {
+        query = query.filter(review_log::is_synthetic.eq(false));
+    }
+
+    query
+        .order(review_log::reviewed_at.desc())
+        .limit(limit.unwrap_or(MAX_HISTORY_LIMIT).clamp(1, MAX_HISTORY_LIMIT))
+        .select(ReviewLogEntry::as_select())
+        .load(&mut *conn)
+        .map_err(|e| AppError::new("REVIEW_LOG_FETCH_ERROR", "Failed to fetch review history").with_details(e.to_string()))
+}
+
+/// Undo the user's most recent review, restoring the item's scheduling state from
+/// before it. Only reviews within the user's `undo_window_seconds` can be undone;
+/// calling it again undoes the review before that.
+#[tauri::command]
+pub fn undo_last_review(user_id: String) -> Result<Vocabulary> {
+    let mut conn = establish_connection()?;
+    undo_review(&mut conn, &user_id, timestamps::now_ms())
+}
+
+/// `undo_last_review` as of `now_ms`
+pub(crate) fn undo_review(conn: &mut SqliteConnection, user_id: &str, now_ms: i64) -> Result<Vocabulary> {
+    use crate::schema::{review_log, vocabulary};
+
+    conn.transaction::<_, AppError, _>(|conn| {
+        let settings = load_user_settings(conn, settings_user_id(user_id))?;
+
+        let entry = review_log::table
+            .filter(review_log::user_id.eq(user_id))
+            .filter(review_log::is_synthetic.eq(false))
+            .order(review_log::reviewed_at.desc())
+            .select(ReviewLogEntry::as_select())
+            .first(conn)
+            .optional()?
+            .ok_or_else(|| app_error!("NO_REVIEW_TO_UNDO", "There is no review to undo"))?;
+
+        if now_ms - entry.reviewed_at > settings.undo_window_seconds as i64 * 1000 {
+            return Err(app_error!(
+                "UNDO_WINDOW_EXPIRED",
+                "The last review is too old to undo",
+                format!("Reviewed at {}, undo window is {}s", timestamps::to_rfc3339(entry.reviewed_at), settings.undo_window_seconds)
+            ));
+        }
+
+        let snapshot: ReviewSnapshot = entry
+            .snapshot
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            // Snapshots are cleared when the item is reset
+            .ok_or_else(|| app_error!("UNDO_ERROR", "The last review can no longer be undone"))?;
+
+        diesel::update(vocabulary::table.filter(vocabulary::id.eq(&entry.vocabulary_id)))
+            .set(&snapshot)
+            .execute(conn)?;
+        diesel::delete(review_log::table.find(&entry.id)).execute(conn)?;
+        reopen_session_item(conn, &entry.vocabulary_id, entry.reviewed_at)?;
+
+        Ok(vocabulary::table
+            .filter(vocabulary::id.eq(&entry.vocabulary_id))
+            .first(conn)?)
+    })
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::review_log::undo_review;
+    use crate::commands::vocabulary::{insert_vocabulary, record_review};
+    use crate::models::review_session::{ReviewSession, ReviewSessionItem};
+    use crate::scheduler::ReviewGrade;
+    use crate::test_support::{migrated_connection, new_vocabulary};
+    use chrono::{Duration, Utc};
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+
+    fn review_count(conn: &mut SqliteConnection) -> i64 {
+        use crate::schema::review_log;
+
+        review_log::table.count().get_result(conn).unwrap()
+    }
+
+    fn session_status(conn: &mut SqliteConnection) -> String {
+        use crate::schema::review_sessions;
+
+        review_sessions::table
+            .find("session-1")
+            .select(review_sessions::status)
+            .first(conn)
+            .unwrap()
+    }
+
+    fn start_session(conn: &mut SqliteConnection, vocabulary_id: &str, now_ms: i64) {
+        use crate::schema::{review_session_items, review_sessions};
+
+        diesel::insert_into(review_sessions::table)
+            .values(&ReviewSession {
+                id: "session-1".to_string(),
+                user_id: "1".to_string(),
+                study_date: "2026-10-18".to_string(),
+                video_id: None,
+                status: "active".to_string(),
+                created_at: now_ms,
+                updated_at: now_ms,
+            })
+            .execute(conn)
+            .unwrap();
+        diesel::insert_into(review_session_items::table)
+            .values(&ReviewSessionItem {
+                session_id: "session-1".to_string(),
+                position: 0,
+                vocabulary_id: vocabulary_id.to_string(),
+                is_new: true,
+                completed_at: None,
+            })
+            .execute(conn)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_undo_with_nothing_reviewed() {
+        let mut conn = migrated_connection(":memory:");
+        insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap();
+
+        let error = undo_review(&mut conn, "1", Utc::now().timestamp_millis()).unwrap_err();
+        assert_eq!(error.code, "NO_REVIEW_TO_UNDO");
+    }
+
+    #[test]
+    fn test_undo_restores_the_item_and_reopens_its_session() {
+        use crate::schema::review_session_items;
+
+        let mut conn = migrated_connection(":memory:");
+        let saved = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap();
+        let id = saved.id.clone().unwrap();
+        let now = Utc::now();
+        start_session(&mut conn, &id, now.timestamp_millis());
+
+        let reviewed = record_review(&mut conn, &id, ReviewGrade::Good, Some(1500), now).unwrap();
+        assert_eq!(reviewed.review_count, Some(1));
+        assert_eq!(session_status(&mut conn), "completed");
+
+        let restored = undo_review(&mut conn, "1", (now + Duration::seconds(10)).timestamp_millis()).unwrap();
+        assert_eq!(restored.review_count, saved.review_count);
+        assert_eq!(restored.review_stage, saved.review_stage);
+        assert_eq!(restored.next_review_at, saved.next_review_at);
+        assert_eq!((restored.last_reviewed_at, restored.last_grade), (None, None));
+        assert_eq!(review_count(&mut conn), 0);
+
+        let completed_at: Option<i64> = review_session_items::table
+            .select(review_session_items::completed_at)
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(completed_at, None);
+        assert_eq!(session_status(&mut conn), "active");
+    }
+
+    #[test]
+    fn test_undo_steps_back_through_reviews_within_the_window() {
+        let mut conn = migrated_connection(":memory:");
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+        let start = Utc::now();
+        record_review(&mut conn, &id, ReviewGrade::Good, None, start).unwrap();
+        record_review(&mut conn, &id, ReviewGrade::Again, None, start + Duration::seconds(30)).unwrap();
+
+        // The default window is five minutes
+        let late = (start + Duration::seconds(30) + Duration::seconds(301)).timestamp_millis();
+        assert_eq!(undo_review(&mut conn, "1", late).unwrap_err().code, "UNDO_WINDOW_EXPIRED");
+        assert_eq!(review_count(&mut conn), 2);
+
+        let now_ms = (start + Duration::seconds(60)).timestamp_millis();
+        assert_eq!(undo_review(&mut conn, "1", now_ms).unwrap().review_count, Some(1));
+        assert_eq!(undo_review(&mut conn, "1", now_ms).unwrap().review_count, Some(0));
+        assert_eq!(undo_review(&mut conn, "1", now_ms).unwrap_err().code, "NO_REVIEW_TO_UNDO");
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    app_error,
+    commands::settings::{load_user_settings, settings_user_id},
+    commands::vocabulary_contexts::{load_contexts_by_vocabulary, review_context},
+    database::establish_connection,
+    error::{AppError, Result},
+    models::review_log::ReviewSnapshot,
+    models::review_session::{
+        ReviewSession, ReviewSessionDetails, ReviewSessionEntry, ReviewSessionItem, ReviewSessionOptions,
+    },
+    models::vocabulary::Vocabulary,
+    scheduler::session::{self, SessionCandidate},
+    timestamps,
+};
+
+const ACTIVE: &str = "active";
+const COMPLETED: &str = "completed";
+const ABANDONED: &str = "abandoned";
+
+/// New items introduced and other items reviewed by a user since `since_ms`, each
+/// item counted once. An item is new if its first review today found it unreviewed.
+fn count_reviewed_since(conn: &mut SqliteConnection, user_id: &str, since_ms: i64) -> QueryResult<(i32, i32)> {
+    use crate::schema::review_log;
+
+    let entries: Vec<(String, Option<String>)> = review_log::table
+        .filter(review_log::user_id.eq(user_id))
+        .filter(review_log::is_synthetic.eq(false))
+        .filter(review_log::reviewed_at.ge(since_ms))
+        .select((review_log::vocabulary_id, review_log::snapshot))
+        .load(conn)?;
+
+    let mut was_new: HashMap<String, bool> = HashMap::new();
+    for (vocabulary_id, snapshot) in entries {
+        let new = snapshot
+            .and_then(|json| serde_json::from_str::<ReviewSnapshot>(&json).ok())
+            .is_some_and(|s| s.review_count.unwrap_or(0) == 0);
+        *was_new.entry(vocabulary_id).or_insert(false) |= new;
+    }
+
+    let new_count = was_new.values().filter(|new| **new).count() as i32;
+    Ok((new_count, was_new.len() as i32 - new_count))
+}
+
+fn find_active_session(conn: &mut SqliteConnection, user_id: &str) -> QueryResult<Option<ReviewSession>> {
+    use crate::schema::review_sessions;
+
+    review_sessions::table
+        .filter(review_sessions::user_id.eq(user_id))
+        .filter(review_sessions::status.eq(ACTIVE))
+        .order(review_sessions::created_at.desc())
+        .select(ReviewSession::as_select())
+        .first(conn)
+        .optional()
+}
+
+fn set_session_status(conn: &mut SqliteConnection, session_id: &str, status: &str) -> QueryResult<usize> {
+    use crate::schema::review_sessions;
+
+    diesel::update(review_sessions::table.find(session_id))
+        .set((
+            review_sessions::status.eq(status),
+            review_sessions::updated_at.eq(timestamps::now_ms()),
+        ))
+        .execute(conn)
+}
+
+fn load_session_details(conn: &mut SqliteConnection, session: ReviewSession) -> QueryResult<ReviewSessionDetails> {
+    use crate::schema::{review_session_items, vocabulary};
+
+    let rows = review_session_items::table
+        .inner_join(vocabulary::table)
+        .filter(review_session_items::session_id.eq(&session.id))
+        .order(review_session_items::position.asc())
+        .select((ReviewSessionItem::as_select(), Vocabulary::as_select()))
+        .load::<(ReviewSessionItem, Vocabulary)>(conn)?;
+    let vocabulary_ids: Vec<&str> = rows.iter().map(|(item, _)| item.vocabulary_id.as_str()).collect();
+    let mut contexts = load_contexts_by_vocabulary(conn, &vocabulary_ids)?;
+
+    let items: Vec<ReviewSessionEntry> = rows
+        .into_iter()
+        .map(|(item, vocabulary)| {
+            let sightings = contexts.remove(&item.vocabulary_id).unwrap_or_default();
+            ReviewSessionEntry {
+                position: item.position,
+                is_new: item.is_new,
+                completed_at: item.completed_at,
+                context: review_context(&vocabulary, sightings),
+                vocabulary,
+            }
+        })
+        .collect();
+
+    let remaining = items.iter().filter(|item| item.completed_at.is_none()).count();
+    Ok(ReviewSessionDetails { session, items, remaining })
+}
+
+/// Mark an item reviewed in the user's active session and complete the session
+/// once nothing is left. Runs inside the review's transaction.
+pub(crate) fn mark_session_item_reviewed(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    vocabulary_id: &str,
+    reviewed_at: i64,
+) -> QueryResult<()> {
+    use crate::schema::{review_session_items, review_sessions};
+
+    let Some(session) = find_active_session(conn, user_id)? else {
+        return Ok(());
+    };
+
+    let marked = diesel::update(
+        review_session_items::table
+            .filter(review_session_items::session_id.eq(&session.id))
+            .filter(review_session_items::vocabulary_id.eq(vocabulary_id))
+            .filter(review_session_items::completed_at.is_null()),
+    )
+    .set(review_session_items::completed_at.eq(reviewed_at))
+    .execute(conn)?;
+    if marked == 0 {
+        return Ok(());
+    }
+
+    let remaining: i64 = review_session_items::table
+        .filter(review_session_items::session_id.eq(&session.id))
+        .filter(review_session_items::completed_at.is_null())
+        .count()
+        .get_result(conn)?;
+    let status = if remaining == 0 { COMPLETED } else { ACTIVE };
+
+    diesel::update(review_sessions::table.find(&session.id))
+        .set((review_sessions::status.eq(status), review_sessions::updated_at.eq(reviewed_at)))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Put an undone review's item back into the session it was completed in
+pub(crate) fn reopen_session_item(conn: &mut SqliteConnection, vocabulary_id: &str, reviewed_at: i64) -> QueryResult<()> {
+    use crate::schema::{review_session_items, review_sessions};
+
+    let session_ids: Vec<String> = review_session_items::table
+        .filter(review_session_items::vocabulary_id.eq(vocabulary_id))
+        .filter(review_session_items::completed_at.eq(reviewed_at))
+        .select(review_session_items::session_id)
+        .load(conn)?;
+    if session_ids.is_empty() {
+        return Ok(());
+    }
+
+    diesel::update(
+        review_session_items::table
+            .filter(review_session_items::vocabulary_id.eq(vocabulary_id))
+            .filter(review_session_items::completed_at.eq(reviewed_at)),
+    )
+    .set(review_session_items::completed_at.eq(None::<i64>))
+    .execute(conn)?;
+
+    diesel::update(
+        review_sessions::table
+            .filter(review_sessions::id.eq_any(&session_ids))
+            .filter(review_sessions::status.eq(COMPLETED)),
+    )
+    .set((review_sessions::status.eq(ACTIVE), review_sessions::updated_at.eq(timestamps::now_ms())))
+    .execute(conn)?;
+    Ok(())
+}
+
+/// Drop a vocabulary item that is being deleted from any session it was planned into
+pub(crate) fn delete_session_items_for_vocabulary(conn: &mut SqliteConnection, vocabulary_id: &str) -> QueryResult<usize> {
+    use crate::schema::review_session_items;
+
+    diesel::delete(review_session_items::table.filter(review_session_items::vocabulary_id.eq(vocabulary_id)))
+        .execute(conn)
+}
+
+/// Plan today's review session: due reviews and new items up to what the daily
+/// `reviews_per_day` / `new_per_day` caps still allow, leeches and the most overdue
+/// first, spread across videos. An active session from the same study day and video
+/// is resumed instead, unless `rebuild` is set.
+#[tauri::command]
+pub fn build_review_session(user_id: String, options: Option<ReviewSessionOptions>) -> Result<ReviewSessionDetails> {
+    let options = options.unwrap_or_default();
+    if options.new_limit.is_some_and(|limit| limit < 0) || options.review_limit.is_some_and(|limit| limit < 0) {
+        return Err(app_error!("VALIDATION_ERROR", "Session limits cannot be negative"));
+    }
+
+    let mut conn = establish_connection()?;
+    plan_review_session(&mut conn, &user_id, &options, Utc::now())
+}
+
+/// `build_review_session` as of `now`
+pub(crate) fn plan_review_session(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    options: &ReviewSessionOptions,
+    now: DateTime<Utc>,
+) -> Result<ReviewSessionDetails> {
+    use crate::schema::{review_session_items, review_sessions, vocabulary};
+
+    conn.transaction::<_, AppError, _>(|conn| {
+        let settings = load_user_settings(conn, settings_user_id(user_id))?;
+        let study_day = settings.study_day()?;
+        let now_ms = now.timestamp_millis();
+        let today = study_day.date_of(now);
+        let study_date = today.format("%Y-%m-%d").to_string();
+
+        if let Some(active) = find_active_session(conn, user_id)? {
+            let resumable = active.study_date == study_date && active.video_id == options.video_id;
+            if resumable && !options.rebuild.unwrap_or(false) {
+                return Ok(load_session_details(conn, active)?);
+            }
+            set_session_status(conn, &active.id, ABANDONED)?;
+        }
+
+        let (new_done, reviews_done) = count_reviewed_since(conn, user_id, study_day.start_of(today).timestamp_millis())?;
+        let cap = |daily: i32, done: i32, requested: Option<i32>| {
+            let left = (daily - done).max(0);
+            requested.map_or(left, |requested| requested.min(left)) as usize
+        };
+        let new_limit = cap(settings.new_per_day, new_done, options.new_limit);
+        let review_limit = cap(settings.reviews_per_day, reviews_done, options.review_limit);
+
+        let mut query = vocabulary::table
+            .filter(vocabulary::user_id.eq(user_id))
+            .filter(vocabulary::suspended.eq(false))
+            .filter(vocabulary::next_review_at.lt(study_day.end_of_day(now).timestamp_millis()))
+            .into_boxed();
+        if let Some(video_id) = &options.video_id {
+            query = query.filter(vocabulary::video_id.eq(video_id));
+        }
+        let due: Vec<Vocabulary> = query
+            .order(vocabulary::created_at.asc())
+            .select(Vocabulary::as_select())
+            .load(conn)?;
+
+        let candidates = due
+            .into_iter()
+            .filter_map(|item| {
+                let review_count = item.review_count.unwrap_or(0);
+                Some(SessionCandidate {
+                    vocabulary_id: item.id?,
+                    video_id: item.video_id,
+                    is_new: review_count == 0,
+                    is_leech: item.is_leech,
+                    overdue_ms: now_ms - item.next_review_at,
+                })
+            })
+            .collect();
+        let planned = session::plan_session(candidates, new_limit, review_limit);
+
+        let new_session = ReviewSession {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            study_date,
+            video_id: options.video_id.clone(),
+            status: if planned.is_empty() { COMPLETED } else { ACTIVE }.to_string(),
+            created_at: now_ms,
+            updated_at: now_ms,
+        };
+        diesel::insert_into(review_sessions::table)
+            .values(&new_session)
+            .execute(conn)?;
+
+        let items: Vec<ReviewSessionItem> = planned
+            .into_iter()
+            .enumerate()
+            .map(|(position, candidate)| ReviewSessionItem {
+                session_id: new_session.id.clone(),
+                position: position as i32,
+                vocabulary_id: candidate.vocabulary_id,
+                is_new: candidate.is_new,
+                completed_at: None,
+            })
+            .collect();
+        diesel::insert_into(review_session_items::table)
+            .values(&items)
+            .execute(conn)?;
+
+        Ok(load_session_details(conn, new_session)?)
+    })
+}
+
+/// The session to resume, if the user has one in progress
+#[tauri::command]
+pub fn get_active_review_session(user_id: String) -> Result<Option<ReviewSessionDetails>> {
+    let mut conn = establish_connection()?;
+    let session = find_active_session(&mut conn, &user_id)?;
+    session
+        .map(|session| load_session_details(&mut conn, session))
+        .transpose()
+        .map_err(|e| AppError::new("REVIEW_SESSION_FETCH_ERROR", "Failed to fetch review session").with_details(e.to_string()))
+}
+
+/// Stop a session without finishing it, the next `build_review_session` plans a fresh one
+#[tauri::command]
+pub fn abandon_review_session(session_id: String) -> Result<()> {
+    let mut conn = establish_connection()?;
+    if set_session_status(&mut conn, &session_id, ABANDONED)? == 0 {
+        return Err(app_error!("REVIEW_SESSION_NOT_FOUND", "Review session not found", session_id));
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::review_session::plan_review_session;
+    use crate::commands::settings::load_user_settings;
+    use crate::commands::vocabulary::{insert_vocabulary, record_review, set_suspended};
+    use crate::models::review_session::ReviewSessionOptions;
+    use crate::scheduler::ReviewGrade;
+    use crate::test_support::{migrated_connection, new_vocabulary};
+    use chrono::{DateTime, Duration, Utc};
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+
+    /// A day after the test items fall due
+    fn today() -> DateTime<Utc> {
+        "2026-10-21T12:00:00Z".parse().unwrap()
+    }
+
+    fn save(conn: &mut SqliteConnection, word: &str, video_id: &str) -> String {
+        insert_vocabulary(conn, &new_vocabulary(word, video_id, 1_000)).unwrap().id.unwrap()
+    }
+
+    fn planned_words(conn: &mut SqliteConnection, options: &ReviewSessionOptions) -> Vec<String> {
+        let mut words: Vec<String> = plan_review_session(conn, "1", options, today())
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| item.vocabulary.word)
+            .collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn test_active_session_is_resumed_for_the_same_day_and_video() {
+        use crate::schema::review_sessions;
+
+        let mut conn = migrated_connection(":memory:");
+        save(&mut conn, "bark", "video-1");
+        save(&mut conn, "howl", "video-2");
+        let all = ReviewSessionOptions::default();
+
+        let first = plan_review_session(&mut conn, "1", &all, today()).unwrap();
+        assert_eq!((first.session.status.as_str(), first.remaining), ("active", 2));
+        let resumed = plan_review_session(&mut conn, "1", &all, today() + Duration::hours(1)).unwrap();
+        assert_eq!(resumed.session.id, first.session.id);
+
+        let one_video = ReviewSessionOptions { video_id: Some("video-2".to_string()), ..Default::default() };
+        let other = plan_review_session(&mut conn, "1", &one_video, today()).unwrap();
+        assert_ne!(other.session.id, first.session.id);
+        assert_eq!(other.remaining, 1);
+        let status: String = review_sessions::table
+            .find(&first.session.id)
+            .select(review_sessions::status)
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(status, "abandoned");
+
+        let next_day = plan_review_session(&mut conn, "1", &one_video, today() + Duration::days(1)).unwrap();
+        assert_ne!(next_day.session.id, other.session.id);
+        let rebuilt = ReviewSessionOptions { rebuild: Some(true), ..one_video };
+        let fresh = plan_review_session(&mut conn, "1", &rebuilt, today() + Duration::days(1)).unwrap();
+        assert_ne!(fresh.session.id, next_day.session.id);
+    }
+
+    #[test]
+    fn test_reviews_logged_today_count_against_the_daily_caps() {
+        use crate::schema::user_settings;
+
+        let mut conn = migrated_connection(":memory:");
+        let settings = load_user_settings(&mut conn, 1).unwrap();
+        diesel::update(user_settings::table.find(settings.id))
+            .set((user_settings::new_per_day.eq(2), user_settings::reviews_per_day.eq(1)))
+            .execute(&mut conn)
+            .unwrap();
+
+        // Learned before today and due again
+        let learned = save(&mut conn, "learned", "video-1");
+        record_review(&mut conn, &learned, ReviewGrade::Good, None, today() - Duration::days(10)).unwrap();
+        // One review and one new item are already done today
+        let reviewed = save(&mut conn, "reviewed", "video-1");
+        record_review(&mut conn, &reviewed, ReviewGrade::Good, None, today() - Duration::days(1)).unwrap();
+        record_review(&mut conn, &reviewed, ReviewGrade::Again, None, today() - Duration::hours(2)).unwrap();
+        let introduced = save(&mut conn, "introduced", "video-1");
+        record_review(&mut conn, &introduced, ReviewGrade::Again, None, today() - Duration::hours(1)).unwrap();
+        save(&mut conn, "fresh", "video-1");
+        save(&mut conn, "fresher", "video-2");
+
+        let planned = plan_review_session(&mut conn, "1", &ReviewSessionOptions::default(), today()).unwrap();
+        assert_eq!(planned.items.len(), 1);
+        assert!(planned.items[0].is_new);
+        assert!(["fresh", "fresher"].contains(&planned.items[0].vocabulary.word.as_str()));
+
+        diesel::update(user_settings::table.find(settings.id))
+            .set((user_settings::new_per_day.eq(20), user_settings::reviews_per_day.eq(200)))
+            .execute(&mut conn)
+            .unwrap();
+        let rebuilt = ReviewSessionOptions { rebuild: Some(true), ..Default::default() };
+        let words = planned_words(&mut conn, &rebuilt);
+        assert!(["fresh", "fresher", "learned"].iter().all(|word| words.contains(&word.to_string())));
+    }
+
+    #[test]
+    fn test_suspended_items_are_left_out() {
+        let mut conn = migrated_connection(":memory:");
+        save(&mut conn, "bark", "video-1");
+        let suspended = save(&mut conn, "howl", "video-1");
+        set_suspended(&mut conn, suspended, true).unwrap();
+
+        assert_eq!(planned_words(&mut conn, &ReviewSessionOptions::default()), vec!["bark"]);
+    }
+}
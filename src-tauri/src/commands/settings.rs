@@ -0,0 +1,106 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use serde::Deserialize;
+
+use crate::{
+    database::establish_connection,
+    error::{AppError, Result},
+    models::user_settings::UserSettings,
+    scheduler::{self, StudyDay},
+};
+
+/// The single local account settings are stored under
+pub(crate) const LOCAL_USER_ID: i32 = 1;
+
+/// Settings are keyed by the numeric users id while vocabulary rows carry the
+/// frontend's string user id, which falls back to the local account
+pub(crate) fn settings_user_id(user_id: &str) -> i32 {
+    user_id.parse().unwrap_or(LOCAL_USER_ID)
+}
+
+/// Fields to change, `None` leaves the stored value untouched
+#[derive(Deserialize, AsChangeset, Default)]
+#[diesel(table_name = crate::schema::user_settings)]
+pub struct UpdateUserSettings {
+    pub theme: Option<String>,
+    pub notifications_enabled: Option<bool>,
+    pub language: Option<String>,
+    pub save_speaking_attempts: Option<bool>,
+    pub scheduler: Option<String>,
+    pub undo_window_seconds: Option<i32>,
+    pub timezone: Option<String>,
+    pub day_start_hour: Option<i32>,
+    pub new_per_day: Option<i32>,
+    pub reviews_per_day: Option<i32>,
+    pub leech_threshold: Option<i32>,
+    pub suspend_leeches: Option<bool>,
+}
+
+/// Load the settings row for a user, creating one with the column defaults if missing
+pub(crate) fn load_user_settings(conn: &mut SqliteConnection, user_id: i32) -> QueryResult<UserSettings> {
+    use crate::schema::user_settings;
+
+    let existing = user_settings::table
+        .filter(user_settings::user_id.eq(user_id))
+        .select(UserSettings::as_select())
+        .first(conn)
+        .optional()?;
+
+    if let Some(settings) = existing {
+        return Ok(settings);
+    }
+
+    diesel::insert_into(user_settings::table)
+        .values(user_settings::user_id.eq(user_id))
+        .execute(conn)?;
+
+    user_settings::table
+        .filter(user_settings::user_id.eq(user_id))
+        .select(UserSettings::as_select())
+        .first(conn)
+}
+
+#[tauri::command]
+pub fn get_user_settings(user_id: i32) -> Result<UserSettings> {
+    let mut conn = establish_connection()?;
+    load_user_settings(&mut conn, user_id)
+        .map_err(|e| AppError::new("SETTINGS_FETCH_ERROR", "Failed to fetch user settings").with_details(e.to_string()))
+}
+
+#[tauri::command]
+pub fn update_user_settings(user_id: i32, changes: UpdateUserSettings) -> Result<UserSettings> {
+    use crate::schema::user_settings;
+
+    if let Some(name) = &changes.scheduler {
+        if scheduler::by_name(name).is_none() {
+            return Err(AppError::new("INVALID_SCHEDULER", "Unknown spaced-repetition scheduler")
+                .with_details(format!("{} (expected one of: {})", name, scheduler::SCHEDULER_NAMES.join(", "))));
+        }
+    }
+
+    if changes.undo_window_seconds.is_some_and(|seconds| seconds < 0) {
+        return Err(AppError::new("VALIDATION_ERROR", "Undo window cannot be negative"));
+    }
+    if changes.new_per_day.is_some_and(|limit| limit < 0) || changes.reviews_per_day.is_some_and(|limit| limit < 0) {
+        return Err(AppError::new("VALIDATION_ERROR", "Daily limits cannot be negative"));
+    }
+    if changes.leech_threshold.is_some_and(|threshold| threshold < 1) {
+        return Err(AppError::new("VALIDATION_ERROR", "Leech threshold must be at least 1"));
+    }
+    if let Some(timezone) = &changes.timezone {
+        scheduler::study_day::parse_timezone(timezone)?;
+    }
+    if let Some(hour) = changes.day_start_hour {
+        StudyDay::new("UTC", hour)?;
+    }
+
+    let mut conn = establish_connection()?;
+    load_user_settings(&mut conn, user_id)
+        .and_then(|_| {
+            diesel::update(user_settings::table.filter(user_settings::user_id.eq(user_id)))
+                .set((&changes, user_settings::updated_at.eq(diesel::dsl::now)))
+                .execute(&mut conn)?;
+            load_user_settings(&mut conn, user_id)
+        })
+        .map_err(|e| AppError::new("SETTINGS_UPDATE_ERROR", "Failed to update user settings").with_details(e.to_string()))
+}
@@ -0,0 +1,28 @@
+use crate::{
+    database::establish_connection,
+    error::{AppError, Result},
+    snapshots::{self, DatabaseSnapshot, IntegrityReport},
+};
+
+/// Automatic database snapshots, newest first
+#[tauri::command]
+pub fn list_database_snapshots() -> Result<Vec<DatabaseSnapshot>> {
+    snapshots::list_snapshots()
+}
+
+/// Replace the database with the snapshot named `name`
+#[tauri::command]
+pub async fn rollback_database_snapshot(name: String) -> Result<DatabaseSnapshot> {
+    snapshots::rollback_to_snapshot(&name)
+}
+
+#[tauri::command]
+pub async fn check_database_integrity() -> Result<IntegrityReport> {
+    let mut conn = establish_connection()?;
+    let problems = snapshots::check_integrity(&mut conn)
+        .map_err(|e| AppError::new("INTEGRITY_CHECK_ERROR", "Failed to check database integrity").with_details(e.to_string()))?;
+    Ok(IntegrityReport {
+        ok: problems.is_empty(),
+        problems,
+    })
+}
@@ -0,0 +1,203 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashMap;
+use std::fs;
+use uuid::Uuid;
+
+use crate::{
+    app_error,
+    commands::settings::{load_user_settings, settings_user_id},
+    commands::speech::convert_to_whisper_format,
+    database::establish_connection,
+    error::{AppError, Result},
+    models::speaking_attempt::{NewSpeakingAttempt, SaveSpeakingAttemptRequest, SpeakingAttempt, SpeakingAttemptAudio},
+    speech::recording,
+};
+
+/// Delete recordings whose rows are gone. Call it once the deleting transaction has
+/// committed, a rollback would otherwise leave rows without their audio.
+pub(crate) fn remove_audio_files(audio_paths: &[String]) {
+    for path in audio_paths {
+        if let Err(e) = fs::remove_file(path) {
+            eprintln!("Failed to remove recording {}: {}", path, e);
+        }
+    }
+}
+
+/// Delete the attempt rows and return their recordings for `remove_audio_files`
+fn delete_attempts(conn: &mut SqliteConnection, attempts: &[SpeakingAttempt]) -> QueryResult<Vec<String>> {
+    use crate::schema::speaking_attempts;
+
+    let ids: Vec<&str> = attempts.iter().map(|a| a.id.as_str()).collect();
+    diesel::delete(speaking_attempts::table.filter(speaking_attempts::id.eq_any(ids)))
+        .execute(conn)?;
+    Ok(attempts.iter().map(|attempt| attempt.audio_path.clone()).collect())
+}
+
+/// Remove the archived attempts of a vocabulary item that is being deleted. Returns
+/// their recordings, which the caller removes after committing.
+pub(crate) fn delete_attempts_for_vocabulary(conn: &mut SqliteConnection, vocabulary_id: &str) -> QueryResult<Vec<String>> {
+    use crate::schema::speaking_attempts;
+
+    let attempts = speaking_attempts::table
+        .filter(speaking_attempts::vocabulary_id.eq(vocabulary_id))
+        .select(SpeakingAttempt::as_select())
+        .load(conn)?;
+    delete_attempts(conn, &attempts)
+}
+
+/// Archive a spoken review attempt. Returns `None` without storing anything
+/// unless the user opted in with the `save_speaking_attempts` setting.
+#[tauri::command]
+pub fn save_speaking_attempt(request: SaveSpeakingAttemptRequest) -> Result<Option<SpeakingAttempt>> {
+    use crate::schema::{speaking_attempts, vocabulary};
+
+    let mut conn = establish_connection()?;
+    let settings = load_user_settings(&mut conn, settings_user_id(&request.user_id))
+        .map_err(|e| app_error!("SETTINGS_FETCH_ERROR", "Failed to fetch user settings", e.to_string()))?;
+    if !settings.save_speaking_attempts {
+        return Ok(None);
+    }
+
+    let exists = vocabulary::table
+        .filter(vocabulary::id.eq(&request.vocabulary_id))
+        .count()
+        .get_result::<i64>(&mut *conn)?
+        > 0;
+    if !exists {
+        return Err(app_error!("VOCABULARY_NOT_FOUND", "Vocabulary item not found", request.vocabulary_id));
+    }
+
+    let audio_data = general_purpose::STANDARD
+        .decode(&request.audio_base64)
+        .map_err(|e| app_error!("DECODE_ERROR", "Failed to decode audio data", e.to_string()))?;
+    let samples = convert_to_whisper_format(&audio_data)?;
+    let speech = recording::trim_to_speech(&samples);
+
+    let id = Uuid::new_v4().to_string();
+    let dir = recording::recordings_dir()?.join(&request.vocabulary_id);
+    let (path, format) = recording::save_compressed(speech, &dir, &id)?;
+
+    let new_attempt = NewSpeakingAttempt {
+        id: id.clone(),
+        user_id: request.user_id,
+        vocabulary_id: request.vocabulary_id,
+        audio_path: path.to_string_lossy().to_string(),
+        audio_format: format.to_string(),
+        duration_ms: recording::duration_ms(speech),
+        transcription: request.transcription,
+        score: request.score,
+        created_at: Utc::now().to_rfc3339(),
+    };
+
+    if let Err(e) = diesel::insert_into(speaking_attempts::table)
+        .values(&new_attempt)
+        .execute(&mut *conn)
+    {
+        let _ = fs::remove_file(&path);
+        return Err(AppError::new("SPEAKING_ATTEMPT_SAVE_ERROR", "Failed to save speaking attempt").with_details(e.to_string()));
+    }
+
+    speaking_attempts::table
+        .find(id)
+        .select(SpeakingAttempt::as_select())
+        .first(&mut *conn)
+        .map(Some)
+        .map_err(|e| AppError::new("SPEAKING_ATTEMPT_FETCH_ERROR", "Failed to fetch saved speaking attempt").with_details(e.to_string()))
+}
+
+/// Attempts for a vocabulary item, newest first
+#[tauri::command]
+pub fn list_speaking_attempts(vocabulary_id: String) -> Result<Vec<SpeakingAttempt>> {
+    use crate::schema::speaking_attempts;
+
+    let mut conn = establish_connection()?;
+    speaking_attempts::table
+        .filter(speaking_attempts::vocabulary_id.eq(vocabulary_id))
+        .order(speaking_attempts::created_at.desc())
+        .select(SpeakingAttempt::as_select())
+        .load(&mut *conn)
+        .map_err(|e| AppError::new("SPEAKING_ATTEMPT_FETCH_ERROR", "Failed to fetch speaking attempts").with_details(e.to_string()))
+}
+
+#[tauri::command]
+pub fn get_speaking_attempt_audio(attempt_id: String) -> Result<SpeakingAttemptAudio> {
+    use crate::schema::speaking_attempts;
+
+    let mut conn = establish_connection()?;
+    let attempt = speaking_attempts::table
+        .find(&attempt_id)
+        .select(SpeakingAttempt::as_select())
+        .first(&mut *conn)
+        .optional()?
+        .ok_or_else(|| app_error!("SPEAKING_ATTEMPT_NOT_FOUND", "Speaking attempt not found", attempt_id))?;
+
+    let audio = fs::read(&attempt.audio_path)
+        .map_err(|e| app_error!("RECORDING_NOT_FOUND", "Recording file is missing", e.to_string()))?;
+
+    Ok(SpeakingAttemptAudio {
+        mime_type: recording::mime_type(&attempt.audio_format).to_string(),
+        audio_base64: general_purpose::STANDARD.encode(audio),
+    })
+}
+
+#[tauri::command]
+pub fn delete_speaking_attempt(attempt_id: String) -> Result<()> {
+    use crate::schema::speaking_attempts;
+
+    let mut conn = establish_connection()?;
+    let attempts = speaking_attempts::table
+        .find(&attempt_id)
+        .select(SpeakingAttempt::as_select())
+        .load(&mut *conn)?;
+    if attempts.is_empty() {
+        return Err(app_error!("SPEAKING_ATTEMPT_NOT_FOUND", "Speaking attempt not found", attempt_id));
+    }
+
+    let audio_paths = delete_attempts(&mut conn, &attempts)?;
+    remove_audio_files(&audio_paths);
+    Ok(())
+}
+
+/// Delete a user's attempts beyond the newest `keep_latest` per vocabulary item
+/// and/or older than `older_than_days`. Returns the number of attempts removed.
+#[tauri::command]
+pub fn prune_speaking_attempts(
+    user_id: String,
+    keep_latest: Option<usize>,
+    older_than_days: Option<i64>,
+) -> Result<usize> {
+    use crate::schema::speaking_attempts;
+
+    if keep_latest.is_none() && older_than_days.is_none() {
+        return Err(app_error!("VALIDATION_ERROR", "Specify keep_latest and/or older_than_days"));
+    }
+
+    let mut conn = establish_connection()?;
+    let attempts = speaking_attempts::table
+        .filter(speaking_attempts::user_id.eq(user_id))
+        .order(speaking_attempts::created_at.desc())
+        .select(SpeakingAttempt::as_select())
+        .load(&mut *conn)?;
+
+    let cutoff = older_than_days.map(|days| (Utc::now() - Duration::days(days)).to_rfc3339());
+    let mut seen_per_item: HashMap<&str, usize> = HashMap::new();
+    let to_delete: Vec<SpeakingAttempt> = attempts
+        .iter()
+        .filter(|attempt| {
+            let rank = seen_per_item.entry(attempt.vocabulary_id.as_str()).or_insert(0);
+            *rank += 1;
+            let beyond_limit = keep_latest.is_some_and(|keep| *rank > keep);
+            let too_old = cutoff.as_ref().is_some_and(|cutoff| attempt.created_at < *cutoff);
+            beyond_limit || too_old
+        })
+        .cloned()
+        .collect();
+
+    let audio_paths = delete_attempts(&mut conn, &to_delete)
+        .map_err(|e| AppError::new("SPEAKING_ATTEMPT_DELETE_ERROR", "Failed to prune speaking attempts").with_details(e.to_string()))?;
+    remove_audio_files(&audio_paths);
+    Ok(audio_paths.len())
+}
@@ -1,103 +1,29 @@
 use crate::error::AppError;
 use crate::app_error;
+use crate::speech::language;
+use crate::commands::whisper_models::default_model_for_user;
+use crate::speech::models;
+use crate::speech::vad::{self, SpeechRegion, VadConfig};
+use crate::speech::WHISPER_SAMPLE_RATE;
 use base64::{engine::general_purpose, Engine as _};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::fs;
 use std::collections::HashMap;
 use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
 use hound;
-use tauri::Emitter;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranscriptionResult {
     pub text: String,
     pub language: Option<String>,
+    /// Regions of the submitted recording that were detected as speech
+    pub speech_regions: Vec<SpeechRegion>,
 }
 
-/// Get the path to store Whisper models
-fn get_model_path() -> Result<PathBuf, AppError> {
-    let home_dir = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| app_error!("PATH_ERROR", "Failed to get home directory"))?;
-    
-    let app_dir = PathBuf::from(home_dir).join(".loud-mouth");
-    let model_dir = app_dir.join("whisper-models");
-    fs::create_dir_all(&model_dir)?;
-    
-    Ok(model_dir)
-}
+/// Silence inserted between voiced regions when they are stitched together
+const VOICED_GAP_MS: u32 = 200;
 
-/// Check if a Whisper model is available
-#[tauri::command]
-pub async fn check_whisper_model(model_name: Option<String>) -> Result<bool, AppError> {
-    let model = model_name.unwrap_or_else(|| "ggml-small.bin".to_string());
-    let model_path = get_model_path()?.join(&model);
-    
-    Ok(model_path.exists() && model_path.is_file())
-}
-
-/// Download a Whisper model
-#[tauri::command]
-pub async fn download_whisper_model(
-    model_name: Option<String>,
-    window: tauri::Window,
-) -> Result<(), AppError> {
-    let model = model_name.unwrap_or_else(|| "ggml-small.bin".to_string());
-    let model_path = get_model_path()?.join(&model);
-    
-    if model_path.exists() {
-        return Ok(());
-    }
-    
-    // Model URLs from Hugging Face
-    let model_url = match model.as_str() {
-        "ggml-tiny.bin" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
-        "ggml-base.bin" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
-        "ggml-small.bin" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
-        "ggml-medium.bin" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
-        _ => return Err(app_error!("MODEL_ERROR", "Unknown model name", model)),
-    };
-    
-    // Emit download progress events
-    window.emit("whisper-model-download-start", &model)
-        .map_err(|e| app_error!("EVENT_ERROR", "Failed to emit event", e.to_string()))?;
-    
-    // Download the model file
-    let response = reqwest::get(model_url).await
-        .map_err(|e| app_error!("DOWNLOAD_ERROR", "Failed to download model", e.to_string()))?;
-    let total_size = response.content_length().unwrap_or(0);
-    
-    let mut downloaded = 0;
-    let mut stream = response.bytes_stream();
-    let mut file_content = Vec::new();
-    
-    use futures_util::StreamExt;
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk
-            .map_err(|e| app_error!("DOWNLOAD_ERROR", "Failed to download chunk", e.to_string()))?;
-        file_content.extend_from_slice(&chunk);
-        downloaded += chunk.len() as u64;
-        
-        // Emit progress
-        let progress = if total_size > 0 {
-            (downloaded as f64 / total_size as f64) * 100.0
-        } else {
-            0.0
-        };
-        
-        window.emit("whisper-model-download-progress", progress)
-            .map_err(|e| app_error!("EVENT_ERROR", "Failed to emit progress", e.to_string()))?;
-    }
-    
-    // Save the model file
-    fs::write(&model_path, &file_content)?;
-    
-    window.emit("whisper-model-download-complete", &model)
-        .map_err(|e| app_error!("EVENT_ERROR", "Failed to emit complete event", e.to_string()))?;
-    
-    Ok(())
-}
+/// Whisper skips inputs shorter than one second, so voiced audio is padded up to this
+const MIN_WHISPER_INPUT_MS: u32 = 1100;
 
 /// Check if audio contains actual speech (not just silence)
 fn check_audio_has_content(samples: &[f32]) -> bool {
@@ -242,7 +168,7 @@ fn calculate_text_entropy(text: &str) -> f32 {
 }
 
 /// Convert audio data to format required by Whisper (16kHz mono f32)
-fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<f32>, AppError> {
+pub(crate) fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<f32>, AppError> {
     let cursor = std::io::Cursor::new(audio_data);
     let reader = hound::WavReader::new(cursor)
         .map_err(|e| app_error!("AUDIO_ERROR", "Failed to read WAV data", e.to_string()))?;
@@ -298,10 +224,15 @@ fn convert_to_whisper_format(audio_data: &[u8]) -> Result<Vec<f32>, AppError> {
 }
 
 /// Transcribe audio data using Whisper
+///
+/// `language` is the study language as an ISO code (`en`, `zh-CN`, ...); `None` or
+/// `"auto"` lets Whisper detect it. Without `model_name` the user's default model is used.
 #[tauri::command]
 pub async fn transcribe_audio(
     audio_base64: String,
     model_name: Option<String>,
+    language: Option<String>,
+    user_id: Option<String>,
 ) -> Result<TranscriptionResult, AppError> {
     // Decode base64 audio data
     let audio_data = general_purpose::STANDARD
@@ -309,8 +240,12 @@ pub async fn transcribe_audio(
         .map_err(|e| app_error!("DECODE_ERROR", "Failed to decode audio data", e.to_string()))?;
     
     // Get model path
-    let model = model_name.unwrap_or_else(|| "ggml-small.bin".to_string());
-    let model_path = get_model_path()?.join(&model);
+    let model = match model_name {
+        Some(name) => name,
+        None => default_model_for_user(user_id.as_deref())?,
+    };
+    models::validate_model_name(&model)?;
+    let model_path = models::model_dir()?.join(&model);
     
     if !model_path.exists() {
         return Err(app_error!(
@@ -320,6 +255,30 @@ pub async fn transcribe_audio(
         ));
     }
     
+    // Resolve the recognition language, `None` means auto-detect
+    let requested_language = language.as_deref().and_then(language::normalize_language);
+    if let Some(code) = &requested_language {
+        if whisper_rs::get_lang_id(code).is_none() {
+            return Err(app_error!("UNSUPPORTED_LANGUAGE", "Language is not supported by Whisper", code.clone()));
+        }
+    }
+    
+    // English-only models can't recognise anything else, so there is nothing to detect
+    let requested_language = if models::is_english_only(&model) {
+        match requested_language.as_deref() {
+            None | Some("en") => Some("en".to_string()),
+            Some(other) => {
+                return Err(app_error!(
+                    "MODEL_LANGUAGE_MISMATCH",
+                    "The selected Whisper model only supports English",
+                    format!("Model '{}' cannot transcribe '{}'. Please use a multilingual model.", model, other)
+                ));
+            }
+        }
+    } else {
+        requested_language
+    };
+    
     // Convert audio to Whisper format
     let audio_samples = convert_to_whisper_format(&audio_data)?;
     
@@ -332,6 +291,25 @@ pub async fn transcribe_audio(
         ));
     }
     
+    // Trim leading/trailing silence and split on long pauses so Whisper only sees speech
+    let speech_regions = vad::detect_speech_regions(&audio_samples, WHISPER_SAMPLE_RATE, &VadConfig::default());
+    if speech_regions.is_empty() {
+        return Err(app_error!(
+            "SILENT_AUDIO",
+            "No speech detected in the recording",
+            "Please speak louder and ensure your microphone is working"
+        ));
+    }
+    eprintln!("VAD detected {} speech region(s): {:?}", speech_regions.len(), speech_regions);
+    
+    let voiced_samples = vad::extract_voiced_samples(
+        &audio_samples,
+        WHISPER_SAMPLE_RATE,
+        &speech_regions,
+        VOICED_GAP_MS,
+        MIN_WHISPER_INPUT_MS,
+    );
+    
     // Initialize Whisper context
     let ctx = WhisperContext::new_with_params(
         model_path.to_str().unwrap(),
@@ -347,7 +325,7 @@ pub async fn transcribe_audio(
     // Configure parameters for better accuracy and to avoid hallucinations
     params.set_n_threads(4);
     params.set_translate(false);
-    params.set_language(Some("en"));
+    params.set_language(Some(requested_language.as_deref().unwrap_or(language::AUTO_DETECT)));
     params.set_print_special(false);
     params.set_print_progress(false);
     params.set_print_realtime(false);
@@ -369,9 +347,20 @@ pub async fn transcribe_audio(
         .map_err(|e| app_error!("STATE_ERROR", "Failed to create whisper state", e.to_string()))?;
     
     // Run the transcription
-    state.full(params, &audio_samples)
+    state.full(params, &voiced_samples)
         .map_err(|e| app_error!("TRANSCRIPTION_ERROR", "Failed to transcribe audio", e.to_string()))?;
     
+    // Report the language Whisper actually decoded with when auto-detecting
+    let lang = match requested_language {
+        Some(code) => code,
+        None => state.full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .unwrap_or("en")
+            .to_string(),
+    };
+    eprintln!("Transcription language: {}", lang);
+    
     // Get the transcribed text with segment-level validation
     let num_segments = state.full_n_segments()
         .map_err(|_| app_error!("TRANSCRIPTION_ERROR", "Failed to get number of segments"))?;
@@ -393,7 +382,7 @@ pub async fn transcribe_audio(
         
         // Skip very short segments that might be artifacts
         let trimmed = segment.trim();
-        if trimmed.len() < 3 || trimmed.split_whitespace().count() < 2 {
+        if trimmed.len() < 3 || language::count_words(trimmed, &lang) < 2 {
             eprintln!("  Skipping segment {} due to being too short", i);
             continue;
         }
@@ -418,12 +407,14 @@ pub async fn transcribe_audio(
         ));
     }
     
-    // Trim and clean up the text
-    let mut text = text.trim().to_string();
+    // Trim and apply language-specific cleanup
+    let mut text = language::post_process(text.trim(), &lang);
     
-    // Clean potential hallucinated endings BEFORE other checks
-    text = clean_hallucinated_endings(&text);
-    eprintln!("Text after cleaning endings: '{}'", text);
+    // Clean potential hallucinated endings BEFORE other checks (English phrases only)
+    if lang == "en" {
+        text = clean_hallucinated_endings(&text);
+        eprintln!("Text after cleaning endings: '{}'", text);
+    }
     
     // Check if we have meaningful text after cleaning
     if text.is_empty() {
@@ -434,7 +425,7 @@ pub async fn transcribe_audio(
         ));
     }
     
-    if text.len() < 5 || text.split_whitespace().count() < 2 {
+    if text.len() < 5 || language::count_words(&text, &lang) < 2 {
         eprintln!("Text too short after cleaning: '{}'", text);
         return Err(app_error!(
             "INSUFFICIENT_SPEECH",
@@ -471,9 +462,10 @@ pub async fn transcribe_audio(
     ];
     
     // Check if the entire transcription is just a known hallucination
-    let is_exact_hallucination = exact_hallucinations.iter().any(|phrase| {
-        text_lower.trim() == *phrase
-    });
+    let is_exact_hallucination = exact_hallucinations
+        .iter()
+        .chain(language::hallucination_phrases(&lang))
+        .any(|phrase| text_lower.trim() == *phrase);
     
     // Check for purely repetitive text (all words are the same)
     let is_pure_repetition = {
@@ -507,7 +499,7 @@ pub async fn transcribe_audio(
     
     // Only check entropy for longer text, and be more lenient
     // Normal English text has entropy around 3.0-4.5
-    if text.len() > 30 && (entropy < 1.0 || entropy > 5.5) {
+    if language::has_alphabetic_script(&lang) && text.len() > 30 && (entropy < 1.0 || entropy > 5.5) {
         eprintln!("Abnormal text entropy detected: {}", text);
         return Err(app_error!(
             "LOW_QUALITY_TRANSCRIPTION",
@@ -516,10 +508,11 @@ pub async fn transcribe_audio(
         ));
     }
     
-    // Calculate speech rate to detect anomalies
-    let audio_duration = audio_samples.len() as f32 / 16000.0; // 16kHz sample rate
-    let word_count = text.split_whitespace().count();
+    // Calculate speech rate over the voiced audio only, so pauses don't skew it
+    let audio_duration = speech_regions.iter().map(|r| r.duration_ms()).sum::<u32>() as f32 / 1000.0;
+    let word_count = language::count_words(&text, &lang);
     let words_per_second = word_count as f32 / audio_duration;
+    let (min_rate, max_rate) = language::speech_rate_bounds(&lang);
     
     eprintln!("Speech rate: {:.2} words/second (duration: {:.2}s, words: {})", 
               words_per_second, audio_duration, word_count);
@@ -527,7 +520,7 @@ pub async fn transcribe_audio(
     // Normal speech is typically 2-3 words per second
     // Allow wider range for different speaking speeds but flag only extreme cases
     // Some people speak slowly (1 word/sec) or quickly (5 words/sec)
-    if audio_duration > 2.0 && (words_per_second < 0.3 || words_per_second > 8.0) {
+    if audio_duration > 2.0 && (words_per_second < min_rate || words_per_second > max_rate) {
         eprintln!("Abnormal speech rate detected: {:.2} words/sec", words_per_second);
         return Err(app_error!(
             "ABNORMAL_SPEECH_RATE",
@@ -548,17 +541,7 @@ pub async fn transcribe_audio(
     
     Ok(TranscriptionResult {
         text,
-        language: Some("en".to_string()),
+        language: Some(lang),
+        speech_regions,
     })
 }
-
-/// Get available Whisper models
-#[tauri::command]
-pub fn get_available_whisper_models() -> Vec<&'static str> {
-    vec![
-        "ggml-tiny.bin",    // 39 MB - Fastest, lower accuracy
-        "ggml-base.bin",    // 74 MB - Good balance
-        "ggml-small.bin",   // 244 MB - Best accuracy for most use cases (default)
-        "ggml-medium.bin",  // 769 MB - Even better accuracy, slower
-    ]
-}
\ No newline at end of file
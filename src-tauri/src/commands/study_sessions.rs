@@ -0,0 +1,130 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+
+use crate::{
+    analytics::{self, Period},
+    app_error,
+    commands::analytics::user_buckets,
+    database::establish_connection,
+    error::{AppError, Result},
+    models::study_session::{DailyStudyTime, StudyActivity, StudySession, MIN_SESSION_MS},
+    timestamps,
+};
+
+const DEFAULT_STUDY_TIME_DAYS: u32 = 30;
+
+fn find_session(conn: &mut SqliteConnection, session_id: &str) -> Result<StudySession> {
+    use crate::schema::study_sessions;
+
+    study_sessions::table
+        .find(session_id)
+        .select(StudySession::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| app_error!("STUDY_SESSION_NOT_FOUND", "Study session not found", session_id.to_string()))
+}
+
+/// Insert a new session, ending the user's sessions that are still open at their
+/// last heartbeat
+pub(crate) fn begin_session(conn: &mut SqliteConnection, session: &StudySession) -> QueryResult<()> {
+    use crate::schema::study_sessions;
+
+    conn.transaction(|conn| {
+        diesel::update(
+            study_sessions::table
+                .filter(study_sessions::user_id.eq(&session.user_id))
+                .filter(study_sessions::ended_at.is_null()),
+        )
+        .set(study_sessions::ended_at.eq(study_sessions::last_active_at.nullable()))
+        .execute(conn)?;
+
+        diesel::insert_into(study_sessions::table)
+            .values(session)
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+/// Count the time since the last heartbeat and mark the session active at `now_ms`
+pub(crate) fn record_heartbeat(conn: &mut SqliteConnection, session_id: &str, now_ms: i64) -> Result<StudySession> {
+    use crate::schema::study_sessions;
+
+    let mut session = find_session(conn, session_id)?;
+    if session.ended_at.is_some() {
+        return Err(app_error!("STUDY_SESSION_ENDED", "Study session has already ended", session_id.to_string()));
+    }
+
+    session.duration_ms = session.duration_until(now_ms);
+    session.last_active_at = now_ms;
+    diesel::update(study_sessions::table.find(session_id))
+        .set((
+            study_sessions::duration_ms.eq(session.duration_ms),
+            study_sessions::last_active_at.eq(now_ms),
+        ))
+        .execute(conn)?;
+
+    Ok(session)
+}
+
+/// End a session at `now_ms`, deleting it when it is shorter than `MIN_SESSION_MS`
+pub(crate) fn end_session(conn: &mut SqliteConnection, session_id: &str, now_ms: i64) -> Result<Option<StudySession>> {
+    use crate::schema::study_sessions;
+
+    let mut session = find_session(conn, session_id)?;
+    if session.ended_at.is_some() {
+        return Ok(Some(session));
+    }
+
+    session.duration_ms = session.duration_until(now_ms);
+    if session.duration_ms < MIN_SESSION_MS {
+        diesel::delete(study_sessions::table.find(session_id)).execute(conn)?;
+        return Ok(None);
+    }
+
+    session.last_active_at = now_ms;
+    session.ended_at = Some(now_ms);
+    diesel::update(study_sessions::table.find(session_id))
+        .set((
+            study_sessions::duration_ms.eq(session.duration_ms),
+            study_sessions::last_active_at.eq(now_ms),
+            study_sessions::ended_at.eq(now_ms),
+        ))
+        .execute(conn)?;
+
+    Ok(Some(session))
+}
+
+/// Start tracking study time. Sessions the user left running, e.g. because the app
+/// was closed, are ended at their last heartbeat.
+#[tauri::command]
+pub fn start_study_session(user_id: String, activity: StudyActivity, video_id: Option<String>) -> Result<StudySession> {
+    let session = StudySession::start(user_id, activity, video_id, timestamps::now_ms());
+    let mut conn = establish_connection()?;
+    begin_session(&mut conn, &session)
+        .map_err(|e| AppError::new("STUDY_SESSION_ERROR", "Failed to start study session").with_details(e.to_string()))?;
+
+    Ok(session)
+}
+
+/// Record that the learner is still active, the frontend calls this about once a minute
+#[tauri::command]
+pub fn heartbeat_study_session(session_id: String) -> Result<StudySession> {
+    let mut conn = establish_connection()?;
+    record_heartbeat(&mut conn, &session_id, timestamps::now_ms())
+}
+
+/// End a session. Sessions shorter than a few seconds are discarded and `None` is returned.
+#[tauri::command]
+pub fn stop_study_session(session_id: String) -> Result<Option<StudySession>> {
+    let mut conn = establish_connection()?;
+    end_session(&mut conn, &session_id, timestamps::now_ms())
+}
+
+/// Study time for each of the last `days` study days, oldest first
+#[tauri::command]
+pub fn get_daily_study_time(user_id: String, days: Option<u32>) -> Result<Vec<DailyStudyTime>> {
+    let mut conn = establish_connection()?;
+    let buckets = user_buckets(&mut conn, &user_id, Period::Day, Some(days.unwrap_or(DEFAULT_STUDY_TIME_DAYS)))?;
+    analytics::study_time(&mut conn, &user_id, &buckets)
+        .map_err(|e| AppError::new("STUDY_TIME_FETCH_ERROR", "Failed to fetch study time").with_details(e.to_string()))
+}
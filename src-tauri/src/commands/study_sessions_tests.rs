@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::study_sessions::{begin_session, end_session, record_heartbeat};
+    use crate::models::study_session::{StudyActivity, StudySession, MAX_HEARTBEAT_GAP_MS, MIN_SESSION_MS};
+    use crate::test_support::migrated_connection;
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+
+    const START: i64 = 1_792_315_800_000;
+
+    fn load(conn: &mut SqliteConnection, id: &str) -> Option<StudySession> {
+        use crate::schema::study_sessions;
+
+        study_sessions::table
+            .find(id)
+            .select(StudySession::as_select())
+            .first(conn)
+            .optional()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_duration_until_clamps_idle_gaps() {
+        let mut session = StudySession::start("1".into(), StudyActivity::Watching, None, START);
+        session.duration_ms = 60_000;
+
+        assert_eq!(session.duration_until(START + 30_000), 90_000);
+        assert_eq!(session.duration_until(START + 3 * 60 * 60 * 1000), 60_000 + MAX_HEARTBEAT_GAP_MS);
+        // A clock that went backwards adds nothing
+        assert_eq!(session.duration_until(START - 5_000), 60_000);
+    }
+
+    #[test]
+    fn test_heartbeats_count_only_active_time() {
+        let mut conn = migrated_connection(":memory:");
+        let session = StudySession::start("1".into(), StudyActivity::Reviewing, None, START);
+        begin_session(&mut conn, &session).unwrap();
+
+        record_heartbeat(&mut conn, &session.id, START + 60_000).unwrap();
+        // The app slept for an hour before the next heartbeat
+        let session = record_heartbeat(&mut conn, &session.id, START + 60_000 + 60 * 60 * 1000).unwrap();
+        assert_eq!(session.duration_ms, 60_000 + MAX_HEARTBEAT_GAP_MS);
+
+        let stopped = end_session(&mut conn, &session.id, session.last_active_at + 10_000).unwrap().unwrap();
+        assert_eq!(stopped.duration_ms, 70_000 + MAX_HEARTBEAT_GAP_MS);
+        assert_eq!(stopped.ended_at, Some(stopped.last_active_at));
+        assert_eq!(record_heartbeat(&mut conn, &session.id, START).unwrap_err().code, "STUDY_SESSION_ENDED");
+    }
+
+    #[test]
+    fn test_sessions_shorter_than_the_minimum_are_discarded() {
+        let mut conn = migrated_connection(":memory:");
+        let short = StudySession::start("1".into(), StudyActivity::Speaking, None, START);
+        begin_session(&mut conn, &short).unwrap();
+
+        assert!(end_session(&mut conn, &short.id, START + MIN_SESSION_MS - 1).unwrap().is_none());
+        assert!(load(&mut conn, &short.id).is_none());
+
+        let long = StudySession::start("1".into(), StudyActivity::Speaking, None, START);
+        begin_session(&mut conn, &long).unwrap();
+        let stopped = end_session(&mut conn, &long.id, START + MIN_SESSION_MS).unwrap().unwrap();
+        assert_eq!(stopped.duration_ms, MIN_SESSION_MS);
+        assert!(load(&mut conn, &long.id).is_some());
+    }
+
+    #[test]
+    fn test_starting_a_session_closes_stale_ones_of_the_same_user() {
+        let mut conn = migrated_connection(":memory:");
+        let stale = StudySession::start("1".into(), StudyActivity::Watching, Some("video".into()), START);
+        let other_user = StudySession::start("2".into(), StudyActivity::Watching, None, START);
+        begin_session(&mut conn, &stale).unwrap();
+        begin_session(&mut conn, &other_user).unwrap();
+        record_heartbeat(&mut conn, &stale.id, START + 60_000).unwrap();
+
+        let next = StudySession::start("1".into(), StudyActivity::Reviewing, None, START + 24 * 60 * 60 * 1000);
+        begin_session(&mut conn, &next).unwrap();
+
+        let stale = load(&mut conn, &stale.id).unwrap();
+        assert_eq!(stale.ended_at, Some(START + 60_000));
+        assert_eq!(stale.duration_ms, 60_000);
+        assert_eq!(load(&mut conn, &other_user.id).unwrap().ended_at, None);
+        assert_eq!(load(&mut conn, &next.id).unwrap().ended_at, None);
+    }
+}
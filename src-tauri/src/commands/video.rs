@@ -396,6 +396,14 @@ pub async fn delete_video(video_id: String) -> Result<()> {
                 eprintln!("Failed to delete subtitles directory {}: {}", subtitles_dir.display(), e);
             }
         }
+
+        // Delete cached sentence audio clips
+        let clips_dir = crate::audio_clip::get_audio_clip_directory(&video_id);
+        if clips_dir.exists() {
+            if let Err(e) = tokio::fs::remove_dir_all(&clips_dir).await {
+                eprintln!("Failed to delete audio clips directory {}: {}", clips_dir.display(), e);
+            }
+        }
     }
     
     Ok(())
@@ -1,12 +1,26 @@
 use diesel::prelude::*;
-use chrono::{DateTime, Duration, Utc};
+use diesel::sqlite::SqliteConnection;
+use chrono::{DateTime, Utc};
 
 use crate::{
+    app_error,
+    commands::review_session::{delete_session_items_for_vocabulary, mark_session_item_reviewed},
+    commands::settings::{load_user_settings, settings_user_id},
+    commands::speaking_attempts::{delete_attempts_for_vocabulary, remove_audio_files},
+    commands::vocabulary_contexts::{delete_contexts_for_vocabulary, load_contexts},
     database::establish_connection,
     error::{AppError, Result},
-    models::vocabulary::{CreateVocabularyRequest, Vocabulary},
+    lemmatizer,
+    models::review_log::{NewReviewLogEntry, ReviewSnapshot},
+    models::vocabulary::{CreateVocabularyRequest, NewVocabulary, Vocabulary, VocabularyFilter},
+    models::vocabulary_context::VocabularyContext,
+    scheduler::{self, ebbinghaus::MAX_STAGE, MemoryState, Review, ReviewGrade},
+    timestamps,
+    vocabulary_io::normalize_created_at,
 };
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
 #[derive(Serialize)]
 pub struct AccuracyStats {
@@ -16,16 +30,64 @@ pub struct AccuracyStats {
     pub words_reviewed: i32,
 }
 
+/// The user's item for `word` saved from, or already sighted at, the same video and
+/// timestamp. Words are compared ignoring case.
+pub(crate) fn find_duplicate(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    video_id: &str,
+    timestamp: i32,
+    word: &str,
+) -> QueryResult<Option<Vocabulary>> {
+    use crate::schema::{vocabulary, vocabulary_contexts};
+
+    let sighted = vocabulary_contexts::table
+        .filter(vocabulary_contexts::video_id.eq(video_id))
+        .filter(vocabulary_contexts::timestamp.eq(timestamp))
+        .select(vocabulary_contexts::vocabulary_id.nullable());
+    let candidates: Vec<Vocabulary> = vocabulary::table
+        .filter(vocabulary::user_id.eq(user_id))
+        .filter(
+            vocabulary::video_id
+                .eq(video_id)
+                .and(vocabulary::timestamp.eq(timestamp))
+                .or(vocabulary::id.eq_any(sighted)),
+        )
+        .select(Vocabulary::as_select())
+        .load(conn)?;
+
+    let word = word.trim().to_lowercase();
+    Ok(candidates.into_iter().find(|item| item.word.trim().to_lowercase() == word))
+}
+
+/// Save a word. If the user already saved it from the same sentence the existing
+/// item is returned instead of a duplicate.
 #[tauri::command]
 pub fn create_vocabulary(request: CreateVocabularyRequest) -> Result<Vocabulary> {
+    let new_vocabulary = request.into_new_vocabulary()?;
+    let mut conn = establish_connection()?;
+    insert_vocabulary(&mut conn, &new_vocabulary)
+}
+
+/// Insert an item unless it duplicates one the user already has, which is returned instead.
+/// The check and the insert share a write transaction so concurrent saves can't both insert.
+pub(crate) fn insert_vocabulary(conn: &mut SqliteConnection, new_vocabulary: &NewVocabulary) -> Result<Vocabulary> {
     use crate::schema::vocabulary;
 
-    let mut conn = establish_connection()?;
-    let new_vocabulary = request.into_new_vocabulary();
+    conn.immediate_transaction::<_, diesel::result::Error, _>(|conn| {
+        let duplicate = find_duplicate(
+            conn,
+            &new_vocabulary.user_id,
+            &new_vocabulary.video_id,
+            new_vocabulary.timestamp,
+            &new_vocabulary.word,
+        )?;
+        if let Some(existing) = duplicate {
+            return Ok(existing);
+        }
 
-    conn.transaction::<_, diesel::result::Error, _>(|conn| {
         diesel::insert_into(vocabulary::table)
-            .values(&new_vocabulary)
+            .values(new_vocabulary)
             .execute(conn)?;
 
         vocabulary::table
@@ -67,51 +129,102 @@ pub fn update_vocabulary_review(
     next_review_at: String,
 ) -> Result<()> {
     use crate::schema::vocabulary;
-    use chrono::Utc;
 
-    let mut conn = establish_connection()?;
-    let now = Utc::now().to_rfc3339();
+    if !(0..=MAX_STAGE).contains(&review_stage) {
+        return Err(AppError::new("VALIDATION_ERROR", "Invalid review stage")
+            .with_details(format!("Expected 0 to {}, got {}", MAX_STAGE, review_stage)));
+    }
+    let next_review_at = timestamps::parse_rfc3339(&next_review_at)?;
 
-    diesel::update(vocabulary::table.filter(vocabulary::id.eq(vocabulary_id)))
+    let mut conn = establish_connection()?;
+    let updated = diesel::update(vocabulary::table.filter(vocabulary::id.eq(&vocabulary_id)))
         .set((
             vocabulary::review_stage.eq(review_stage),
             vocabulary::next_review_at.eq(next_review_at),
-            vocabulary::last_reviewed_at.eq(now),
+            vocabulary::last_reviewed_at.eq(timestamps::now_ms()),
         ))
         .execute(&mut *conn)?;
 
+    if updated == 0 {
+        return Err(AppError::new("VOCABULARY_NOT_FOUND", "Vocabulary item not found").with_details(vocabulary_id));
+    }
+
     Ok(())
 }
 
 #[tauri::command]
 pub fn delete_vocabulary(vocabulary_id: String) -> Result<()> {
-    use crate::schema::vocabulary;
+    use crate::schema::{review_log, vocabulary};
 
     let mut conn = establish_connection()?;
-    diesel::delete(vocabulary::table.filter(vocabulary::id.eq(vocabulary_id)))
-        .execute(&mut *conn)?;
+    let audio_paths = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let audio_paths = delete_attempts_for_vocabulary(conn, &vocabulary_id)?;
+        delete_session_items_for_vocabulary(conn, &vocabulary_id)?;
+        delete_contexts_for_vocabulary(conn, &vocabulary_id)?;
+        diesel::delete(review_log::table.filter(review_log::vocabulary_id.eq(&vocabulary_id)))
+            .execute(conn)?;
+        diesel::delete(vocabulary::table.filter(vocabulary::id.eq(&vocabulary_id)))
+            .execute(conn)?;
+        Ok(audio_paths)
+    })?;
+    remove_audio_files(&audio_paths);
 
     Ok(())
 }
 
+/// A user's items matching `filter`, oldest first
+pub(crate) fn load_filtered_vocabulary(
+    conn: &mut SqliteConnection,
+    user_id: &str,
+    filter: &VocabularyFilter,
+) -> QueryResult<Vec<Vocabulary>> {
+    use crate::schema::vocabulary;
+
+    let mut query = vocabulary::table
+        .filter(vocabulary::user_id.eq(user_id))
+        .into_boxed();
+    if let Some(video_id) = &filter.video_id {
+        query = query.filter(vocabulary::video_id.eq(video_id));
+    }
+    if let Some(ids) = &filter.ids {
+        query = query.filter(vocabulary::id.eq_any(ids));
+    }
+    if let Some(min_stage) = filter.min_stage {
+        query = query.filter(vocabulary::review_stage.ge(min_stage));
+    }
+    if let Some(max_stage) = filter.max_stage {
+        query = query.filter(vocabulary::review_stage.le(max_stage));
+    }
+    if filter.leeches_only.unwrap_or(false) {
+        query = query.filter(vocabulary::is_leech.eq(true));
+    }
+    if !filter.include_suspended.unwrap_or(true) {
+        query = query.filter(vocabulary::suspended.eq(false));
+    }
+
+    query
+        .order(vocabulary::created_at.asc())
+        .select(Vocabulary::as_select())
+        .load(conn)
+}
+
+/// End of the user's current study day in their timezone; items due before it are due today
+pub(crate) fn end_of_study_day(conn: &mut SqliteConnection, user_id: &str) -> Result<DateTime<Utc>> {
+    let settings = load_user_settings(conn, settings_user_id(user_id))?;
+    Ok(settings.study_day()?.end_of_day(Utc::now()))
+}
+
 #[tauri::command]
 pub fn get_vocabulary_due_for_review(user_id: String) -> Result<Vec<Vocabulary>> {
     use crate::schema::vocabulary;
-    use chrono::{Utc, Timelike};
 
     let mut conn = establish_connection()?;
-    // Get today's date at midnight UTC
-    let today_midnight = Utc::now()
-        .with_hour(0).unwrap()
-        .with_minute(0).unwrap()
-        .with_second(0).unwrap()
-        .with_nanosecond(0).unwrap();
-    // Add one day to get tomorrow at midnight
-    let tomorrow_midnight = today_midnight + Duration::days(1);
+    let end_of_today = end_of_study_day(&mut conn, &user_id)?;
     
     vocabulary::table
         .filter(vocabulary::user_id.eq(user_id))
-        .filter(vocabulary::next_review_at.lt(tomorrow_midnight.to_rfc3339()))
+        .filter(vocabulary::suspended.eq(false))
+        .filter(vocabulary::next_review_at.lt(end_of_today.timestamp_millis()))
         .order(vocabulary::next_review_at.asc())
         .load(&mut *conn)
         .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch vocabulary due for review").with_details(e.to_string()))
@@ -120,82 +233,93 @@ pub fn get_vocabulary_due_for_review(user_id: String) -> Result<Vec<Vocabulary>>
 #[tauri::command]
 pub fn get_vocabulary_due_for_review_by_video(user_id: String, video_id: String) -> Result<Vec<Vocabulary>> {
     use crate::schema::vocabulary;
-    use chrono::{Utc, Timelike};
 
     let mut conn = establish_connection()?;
-    // Get today's date at midnight UTC
-    let today_midnight = Utc::now()
-        .with_hour(0).unwrap()
-        .with_minute(0).unwrap()
-        .with_second(0).unwrap()
-        .with_nanosecond(0).unwrap();
-    // Add one day to get tomorrow at midnight
-    let tomorrow_midnight = today_midnight + Duration::days(1);
+    let end_of_today = end_of_study_day(&mut conn, &user_id)?;
     
     vocabulary::table
         .filter(vocabulary::user_id.eq(user_id))
         .filter(vocabulary::video_id.eq(video_id))
-        .filter(vocabulary::next_review_at.lt(tomorrow_midnight.to_rfc3339()))
+        .filter(vocabulary::suspended.eq(false))
+        .filter(vocabulary::next_review_at.lt(end_of_today.timestamp_millis()))
         .order(vocabulary::next_review_at.asc())
         .load(&mut *conn)
         .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch vocabulary due for review by video").with_details(e.to_string()))
 }
 
-// Helper function to get review interval in days based on stage
-fn get_review_interval_days(stage: i32) -> i32 {
-    match stage {
-        0 => 1,   // Day 1
-        1 => 3,   // Day 3
-        2 => 7,   // Day 7
-        3 => 14,  // Day 14
-        4 => 30,  // Day 30
-        _ => 30,  // Mastered (keep at 30 days)
-    }
-}
-
+/// Right/wrong review kept for existing callers, the same as grading `good` or `again`
 #[tauri::command]
 pub fn update_vocabulary_review_with_result(
     vocabulary_id: String,
     is_correct: bool,
 ) -> Result<Vocabulary> {
-    use crate::schema::vocabulary;
+    review_vocabulary(vocabulary_id, ReviewGrade::from_correct(is_correct), None)
+}
+
+/// Record a graded review. `response_ms` is how long the learner took to answer;
+/// slow answers are scheduled as one grade harder.
+#[tauri::command]
+pub fn review_vocabulary(
+    vocabulary_id: String,
+    grade: ReviewGrade,
+    response_ms: Option<i32>,
+) -> Result<Vocabulary> {
+    if response_ms.is_some_and(|ms| ms < 0) {
+        return Err(AppError::new("VALIDATION_ERROR", "Response time cannot be negative"));
+    }
+    let grade = grade.adjusted_for_response(response_ms);
 
     let mut conn = establish_connection()?;
-    
+    record_review(&mut conn, &vocabulary_id, grade, response_ms, Utc::now())
+}
+
+/// Grade an item at `now`, updating its schedule, lapses and leech flag and logging the review
+pub(crate) fn record_review(
+    conn: &mut SqliteConnection,
+    vocabulary_id: &str,
+    grade: ReviewGrade,
+    response_ms: Option<i32>,
+    now: DateTime<Utc>,
+) -> Result<Vocabulary> {
+    use crate::schema::{review_log, vocabulary};
+
+    let is_correct = grade.is_correct();
     conn.transaction::<_, diesel::result::Error, _>(|conn| {
         // First, fetch the current vocabulary item
         let vocab: Vocabulary = vocabulary::table
-            .filter(vocabulary::id.eq(&vocabulary_id))
+            .filter(vocabulary::id.eq(vocabulary_id))
             .first(conn)?;
         
-        let now = Utc::now();
-        let now_str = now.to_rfc3339();
+        let now_ms = now.timestamp_millis();
         
         // Check if the review is late
-        let scheduled_review_at = vocab.scheduled_review_at.as_ref()
+        let scheduled_review_at = vocab.scheduled_review_at
             .ok_or_else(|| diesel::result::Error::NotFound)?;
+        let scheduled_date = timestamps::to_datetime(scheduled_review_at);
         
-        let scheduled_date = DateTime::parse_from_rfc3339(scheduled_review_at)
-            .map_err(|_| diesel::result::Error::NotFound)?
-            .with_timezone(&Utc);
+        let last_reviewed_at = vocab.last_reviewed_at.map(timestamps::to_datetime);
         
-        let days_late = (now - scheduled_date).num_days();
-        let is_late = days_late > 3;
+        let review = Review {
+            grade,
+            reviewed_at: now,
+            due_at: scheduled_date,
+            last_reviewed_at,
+        };
+        let is_late = review.is_late();
         
-        // Calculate new review stage
-        let current_stage = vocab.review_stage.unwrap_or(0);
-        let new_stage = if is_late {
-            0 // Reset to beginning if late
-        } else if !is_correct {
-            current_stage // Keep current stage if incorrect but not late
-        } else {
-            (current_stage + 1).min(5) // Advance stage if correct and not late
+        // Calculate the new memory state with the user's scheduler
+        let settings = load_user_settings(conn, settings_user_id(&vocab.user_id))?;
+        let scheduler = scheduler::by_name(&settings.scheduler)
+            .unwrap_or_else(|| Box::new(scheduler::Ebbinghaus));
+        let state = MemoryState {
+            review_stage: vocab.review_stage.unwrap_or(0),
+            stability: vocab.stability,
+            difficulty: vocab.difficulty,
         };
+        let schedule = scheduler.schedule(&state, &review);
         
         // Calculate next review date
-        let interval_days = get_review_interval_days(new_stage);
-        let next_review_date = now + Duration::days(interval_days as i64);
-        let next_review_str = next_review_date.to_rfc3339();
+        let next_review_at = schedule.next_review_at(now).timestamp_millis();
         
         // Update review count and consecutive correct
         let new_review_count = vocab.review_count.unwrap_or(0) + 1;
@@ -212,28 +336,66 @@ pub fn update_vocabulary_review_with_result(
             vocab.correct_count.unwrap_or(0)
         };
         
+        // A failed review of an item that had been learned before is a lapse
+        let is_lapse = !is_correct && vocab.review_count.unwrap_or(0) > 0;
+        let lapses = vocab.lapses + is_lapse as i32;
+        
+        // Leeches stay flagged until the item is reset, and may be suspended right away
+        let became_leech = !vocab.is_leech && lapses >= settings.leech_threshold;
+        let is_leech = vocab.is_leech || became_leech;
+        let suspended = vocab.suspended || (became_leech && settings.suspend_leeches);
+        
         // Determine if this item should be marked as ever_overdue
         // Once marked as overdue, it stays overdue forever
         let should_mark_overdue = is_late || vocab.ever_overdue;
         
         // Update the vocabulary item
-        diesel::update(vocabulary::table.filter(vocabulary::id.eq(&vocabulary_id)))
+        diesel::update(vocabulary::table.filter(vocabulary::id.eq(vocabulary_id)))
             .set((
-                vocabulary::review_stage.eq(new_stage),
-                vocabulary::next_review_at.eq(&next_review_str),
-                vocabulary::scheduled_review_at.eq(&next_review_str),
-                vocabulary::last_reviewed_at.eq(&now_str),
+                vocabulary::review_stage.eq(schedule.state.review_stage),
+                vocabulary::stability.eq(schedule.state.stability),
+                vocabulary::difficulty.eq(schedule.state.difficulty),
+                vocabulary::next_review_at.eq(next_review_at),
+                vocabulary::scheduled_review_at.eq(next_review_at),
+                vocabulary::last_reviewed_at.eq(now_ms),
                 vocabulary::review_count.eq(new_review_count),
                 vocabulary::consecutive_correct.eq(new_consecutive_correct),
                 vocabulary::was_late.eq(is_late),
                 vocabulary::ever_overdue.eq(should_mark_overdue),
                 vocabulary::correct_count.eq(new_correct_count),
+                vocabulary::last_grade.eq(grade.as_str()),
+                vocabulary::last_response_ms.eq(response_ms),
+                vocabulary::lapses.eq(lapses),
+                vocabulary::is_leech.eq(is_leech),
+                vocabulary::suspended.eq(suspended),
             ))
             .execute(conn)?;
         
+        // Log the review in the same transaction so history and state never diverge
+        diesel::insert_into(review_log::table)
+            .values(&NewReviewLogEntry {
+                id: Uuid::new_v4().to_string(),
+                vocabulary_id: vocabulary_id.to_string(),
+                user_id: vocab.user_id.clone(),
+                reviewed_at: now_ms,
+                grade: Some(grade.as_str().to_string()),
+                previous_stage: state.review_stage,
+                new_stage: schedule.state.review_stage,
+                interval_days: schedule.interval.num_seconds() as f64 / 86_400.0,
+                days_late: review.days_late() as i32,
+                was_late: is_late,
+                response_ms,
+                scheduler: scheduler.name().to_string(),
+                stability: schedule.state.stability,
+                difficulty: schedule.state.difficulty,
+                snapshot: serde_json::to_string(&ReviewSnapshot::from(&vocab)).ok(),
+            })
+            .execute(conn)?;
+        mark_session_item_reviewed(conn, &vocab.user_id, vocabulary_id, now_ms)?;
+        
         // Return the updated vocabulary item
         vocabulary::table
-            .filter(vocabulary::id.eq(&vocabulary_id))
+            .filter(vocabulary::id.eq(vocabulary_id))
             .first(conn)
     })
     .map_err(|e| AppError::new("VOCABULARY_UPDATE_ERROR", "Failed to update vocabulary review").with_details(e.to_string()))
@@ -252,31 +414,271 @@ pub fn get_overdue_vocabulary(user_id: String) -> Result<Vec<Vocabulary>> {
         .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch overdue vocabulary").with_details(e.to_string()))
 }
 
+/// The item after an update of `updated` rows, or not found if nothing matched
+fn fetch_updated(conn: &mut SqliteConnection, updated: usize, vocabulary_id: String) -> Result<Vocabulary> {
+    use crate::schema::vocabulary;
+
+    if updated == 0 {
+        return Err(AppError::new("VOCABULARY_NOT_FOUND", "Vocabulary item not found").with_details(vocabulary_id));
+    }
+    Ok(vocabulary::table
+        .filter(vocabulary::id.eq(&vocabulary_id))
+        .first(conn)?)
+}
+
+pub(crate) fn set_suspended(conn: &mut SqliteConnection, vocabulary_id: String, suspended: bool) -> Result<Vocabulary> {
+    use crate::schema::vocabulary;
+
+    let updated = diesel::update(vocabulary::table.filter(vocabulary::id.eq(&vocabulary_id)))
+        .set(vocabulary::suspended.eq(suspended))
+        .execute(conn)?;
+    fetch_updated(conn, updated, vocabulary_id)
+}
+
+/// Keep an item but stop it from coming up for review
 #[tauri::command]
-pub fn get_vocabulary_accuracy_stats(user_id: String) -> Result<AccuracyStats> {
+pub fn suspend_vocabulary(vocabulary_id: String) -> Result<Vocabulary> {
+    let mut conn = establish_connection()?;
+    set_suspended(&mut conn, vocabulary_id, true)
+}
+
+#[tauri::command]
+pub fn unsuspend_vocabulary(vocabulary_id: String) -> Result<Vocabulary> {
+    let mut conn = establish_connection()?;
+    set_suspended(&mut conn, vocabulary_id, false)
+}
+
+/// Start an item over as if it had just been saved: due now, no review progress,
+/// lapses or leech flag. Its review log is kept, but reviews from before the reset
+/// can no longer be undone.
+#[tauri::command]
+pub fn reset_vocabulary(vocabulary_id: String) -> Result<Vocabulary> {
+    let mut conn = establish_connection()?;
+    reset_item(&mut conn, vocabulary_id, timestamps::now_ms())
+}
+
+pub(crate) fn reset_item(conn: &mut SqliteConnection, vocabulary_id: String, now_ms: i64) -> Result<Vocabulary> {
+    use crate::schema::{review_log, vocabulary};
+
+    conn.transaction(|conn| {
+        let updated = diesel::update(vocabulary::table.filter(vocabulary::id.eq(&vocabulary_id)))
+            .set((
+                vocabulary::review_stage.eq(0),
+                vocabulary::stability.eq(None::<f64>),
+                vocabulary::difficulty.eq(None::<f64>),
+                vocabulary::next_review_at.eq(now_ms),
+                vocabulary::scheduled_review_at.eq(now_ms),
+                vocabulary::last_reviewed_at.eq(None::<i64>),
+                vocabulary::review_count.eq(0),
+                vocabulary::consecutive_correct.eq(0),
+                vocabulary::correct_count.eq(0),
+                vocabulary::was_late.eq(false),
+                vocabulary::ever_overdue.eq(false),
+                vocabulary::last_grade.eq(None::<String>),
+                vocabulary::last_response_ms.eq(None::<i32>),
+                vocabulary::lapses.eq(0),
+                vocabulary::is_leech.eq(false),
+                vocabulary::suspended.eq(false),
+            ))
+            .execute(conn)?;
+        // Undoing one of those reviews would bring back the state the reset cleared
+        diesel::update(review_log::table.filter(review_log::vocabulary_id.eq(&vocabulary_id)))
+            .set(review_log::snapshot.eq(None::<String>))
+            .execute(conn)?;
+        fetch_updated(conn, updated, vocabulary_id)
+    })
+}
+
+/// Items flagged as leeches, the most lapsed first, including suspended ones
+#[tauri::command]
+pub fn get_leeches(user_id: String) -> Result<Vec<Vocabulary>> {
     use crate::schema::vocabulary;
 
     let mut conn = establish_connection()?;
-    
-    // Get all vocabulary items with review data
-    let vocabulary_items: Vec<Vocabulary> = vocabulary::table
+    vocabulary::table
         .filter(vocabulary::user_id.eq(user_id))
-        .filter(vocabulary::review_count.gt(0))
+        .filter(vocabulary::is_leech.eq(true))
+        .order((vocabulary::lapses.desc(), vocabulary::last_reviewed_at.desc()))
         .load(&mut *conn)
-        .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch vocabulary for accuracy stats").with_details(e.to_string()))?;
-    
-    // Calculate totals
-    let mut total_reviews = 0;
-    let mut total_correct = 0;
-    let words_reviewed = vocabulary_items.len() as i32;
-    
-    for item in vocabulary_items {
-        let reviews = item.review_count.unwrap_or(0);
-        let correct = item.correct_count.unwrap_or(0);
-        
-        total_reviews += reviews;
-        total_correct += correct;
+        .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch leeches").with_details(e.to_string()))
+}
+
+/// Saved forms of one lemma, e.g. "running" and "ran" under "run"
+#[derive(Serialize)]
+pub struct LemmaGroup {
+    pub lemma: String,
+    /// Distinct words as saved, oldest first
+    pub words: Vec<String>,
+    pub vocabulary_ids: Vec<String>,
+}
+
+/// The user's vocabulary grouped by lemma, the largest word families first
+#[tauri::command]
+pub fn get_vocabulary_lemma_groups(user_id: String) -> Result<Vec<LemmaGroup>> {
+    use crate::schema::vocabulary;
+
+    let mut conn = establish_connection()?;
+    let rows: Vec<(Option<String>, String, Option<String>)> = vocabulary::table
+        .filter(vocabulary::user_id.eq(user_id))
+        .order(vocabulary::created_at.asc())
+        .select((vocabulary::id, vocabulary::word, vocabulary::lemma))
+        .load(&mut *conn)
+        .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch vocabulary").with_details(e.to_string()))?;
+
+    let mut groups: Vec<LemmaGroup> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (id, word, lemma) in rows {
+        let lemma = lemma.unwrap_or_else(|| lemmatizer::lemmatize(&word));
+        let position = *positions.entry(lemma.clone()).or_insert_with(|| {
+            groups.push(LemmaGroup { lemma, words: Vec::new(), vocabulary_ids: Vec::new() });
+            groups.len() - 1
+        });
+        let group = &mut groups[position];
+        if !group.words.contains(&word) {
+            group.words.push(word);
+        }
+        group.vocabulary_ids.extend(id);
     }
+    groups.sort_by(|a, b| b.vocabulary_ids.len().cmp(&a.vocabulary_ids.len()).then_with(|| a.lemma.cmp(&b.lemma)));
+    Ok(groups)
+}
+
+/// The user's items that share a lemma with `word`, so "ran" finds "running" and "runs"
+#[tauri::command]
+pub fn get_word_family(user_id: String, word: String) -> Result<Vec<Vocabulary>> {
+    use crate::schema::vocabulary;
+
+    let mut conn = establish_connection()?;
+    vocabulary::table
+        .filter(vocabulary::user_id.eq(user_id))
+        .filter(vocabulary::lemma.eq(lemmatizer::lemmatize(&word)))
+        .order(vocabulary::created_at.desc())
+        .load(&mut *conn)
+        .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch vocabulary").with_details(e.to_string()))
+}
+
+/// How far along an item is, merges keep the schedule of the item furthest along
+fn review_progress(item: &Vocabulary) -> (i32, i32, i64) {
+    (
+        item.review_stage.unwrap_or(0),
+        item.consecutive_correct.unwrap_or(0),
+        item.last_reviewed_at.unwrap_or(i64::MIN),
+    )
+}
+
+/// Merge duplicates into the item with the most review progress, which keeps its
+/// schedule. The others' contexts become sightings of it, their review history,
+/// counts and speaking attempts move over, and they are deleted. Reviews made
+/// before the merge can no longer be undone.
+#[tauri::command]
+pub fn merge_vocabulary(ids: Vec<String>) -> Result<Vocabulary> {
+    let mut conn = establish_connection()?;
+    merge_items(&mut conn, ids)
+}
+
+/// `merge_vocabulary` on the given connection
+pub(crate) fn merge_items(conn: &mut SqliteConnection, ids: Vec<String>) -> Result<Vocabulary> {
+    use crate::schema::{review_log, speaking_attempts, vocabulary, vocabulary_contexts};
+
+    let mut unique_ids: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !unique_ids.contains(&id) {
+            unique_ids.push(id);
+        }
+    }
+    if unique_ids.len() < 2 {
+        return Err(app_error!("VALIDATION_ERROR", "Select at least two items to merge"));
+    }
+
+    conn.transaction::<_, AppError, _>(|conn| {
+        let mut items: Vec<Vocabulary> = vocabulary::table
+            .filter(vocabulary::id.eq_any(&unique_ids))
+            .select(Vocabulary::as_select())
+            .load(conn)?;
+        if items.len() != unique_ids.len() {
+            let found: HashSet<&str> = items.iter().filter_map(|item| item.id.as_deref()).collect();
+            let missing: Vec<&str> = unique_ids.iter().map(String::as_str).filter(|id| !found.contains(id)).collect();
+            return Err(AppError::new("VOCABULARY_NOT_FOUND", "Vocabulary item not found").with_details(missing.join(", ")));
+        }
+        if items.iter().any(|item| item.user_id != items[0].user_id) {
+            return Err(app_error!("VALIDATION_ERROR", "Only items of the same user can be merged"));
+        }
+
+        let survivor_index = (0..items.len()).max_by_key(|&i| review_progress(&items[i])).unwrap_or(0);
+        let survivor = items.swap_remove(survivor_index);
+        let survivor_id = survivor.id.clone().unwrap_or_default();
+        let merged_ids: Vec<String> = items.iter().filter_map(|item| item.id.clone()).collect();
+
+        // Each sighting once, the survivor's own context is already on its row
+        let mut seen: HashSet<(String, i32)> = HashSet::from([(survivor.video_id.clone(), survivor.timestamp)]);
+        for context in load_contexts(conn, &survivor_id)? {
+            seen.insert((context.video_id, context.timestamp));
+        }
+        let now_ms = timestamps::now_ms();
+        for item in &items {
+            let item_id = item.id.as_deref().unwrap_or_default();
+            let created_at = item
+                .created_at
+                .as_deref()
+                .and_then(normalize_created_at)
+                .and_then(|created_at| timestamps::parse_rfc3339(&created_at).ok())
+                .unwrap_or(now_ms);
+            let mut sightings = vec![VocabularyContext::from_vocabulary(item, &survivor_id, created_at)];
+            sightings.extend(load_contexts(conn, item_id)?);
+            delete_contexts_for_vocabulary(conn, item_id)?;
+
+            for sighting in sightings {
+                if seen.insert((sighting.video_id.clone(), sighting.timestamp)) {
+                    diesel::insert_into(vocabulary_contexts::table)
+                        .values(VocabularyContext { vocabulary_id: survivor_id.clone(), ..sighting })
+                        .execute(conn)?;
+                }
+            }
+            delete_session_items_for_vocabulary(conn, item_id)?;
+        }
+
+        diesel::update(review_log::table.filter(review_log::vocabulary_id.eq_any(&merged_ids)))
+            .set(review_log::vocabulary_id.eq(&survivor_id))
+            .execute(conn)?;
+        // Snapshots describe the items as they were before the merge
+        diesel::update(review_log::table.filter(review_log::vocabulary_id.eq(&survivor_id)))
+            .set(review_log::snapshot.eq(None::<String>))
+            .execute(conn)?;
+        diesel::update(speaking_attempts::table.filter(speaking_attempts::vocabulary_id.eq_any(&merged_ids)))
+            .set(speaking_attempts::vocabulary_id.eq(&survivor_id))
+            .execute(conn)?;
+
+        let all = || std::iter::once(&survivor).chain(&items);
+        let dictionary_response = all().find_map(|item| item.dictionary_response.clone().filter(|d| !d.trim().is_empty()));
+        diesel::update(vocabulary::table.filter(vocabulary::id.eq(&survivor_id)))
+            .set((
+                vocabulary::review_count.eq(all().map(|item| item.review_count.unwrap_or(0)).sum::<i32>()),
+                vocabulary::correct_count.eq(all().map(|item| item.correct_count.unwrap_or(0)).sum::<i32>()),
+                vocabulary::lapses.eq(all().map(|item| item.lapses).sum::<i32>()),
+                vocabulary::is_leech.eq(all().any(|item| item.is_leech)),
+                vocabulary::dictionary_response.eq(dictionary_response),
+            ))
+            .execute(conn)?;
+        diesel::delete(vocabulary::table.filter(vocabulary::id.eq_any(&merged_ids))).execute(conn)?;
+
+        Ok(vocabulary::table
+            .filter(vocabulary::id.eq(&survivor_id))
+            .first(conn)?)
+    })
+}
+
+/// Review totals over every reviewed item of a user, summed in SQL
+pub(crate) fn load_accuracy_stats(conn: &mut SqliteConnection, user_id: &str) -> QueryResult<AccuracyStats> {
+    use crate::schema::vocabulary;
+    use diesel::dsl::count_star;
+
+    let (total_reviews, total_correct, words_reviewed): (Option<i64>, Option<i64>, i64) = vocabulary::table
+        .filter(vocabulary::user_id.eq(user_id))
+        .filter(vocabulary::review_count.gt(0))
+        .select((diesel::dsl::sum(vocabulary::review_count), diesel::dsl::sum(vocabulary::correct_count), count_star()))
+        .first(conn)?;
+    let total_reviews = total_reviews.unwrap_or(0) as i32;
+    let total_correct = total_correct.unwrap_or(0) as i32;
     
     // Calculate accuracy percentage as integer
     let accuracy_percentage = if total_reviews > 0 {
@@ -289,6 +691,13 @@ pub fn get_vocabulary_accuracy_stats(user_id: String) -> Result<AccuracyStats> {
         total_reviews,
         total_correct,
         accuracy_percentage,
-        words_reviewed,
+        words_reviewed: words_reviewed as i32,
     })
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub fn get_vocabulary_accuracy_stats(user_id: String) -> Result<AccuracyStats> {
+    let mut conn = establish_connection()?;
+    load_accuracy_stats(&mut conn, &user_id)
+        .map_err(|e| AppError::new("VOCABULARY_FETCH_ERROR", "Failed to fetch vocabulary for accuracy stats").with_details(e.to_string()))
+}
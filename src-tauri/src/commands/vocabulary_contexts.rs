@@ -0,0 +1,163 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::{
+    app_error,
+    database::establish_connection,
+    error::{AppError, Result},
+    models::vocabulary::Vocabulary,
+    models::vocabulary_context::{AddVocabularyContextRequest, VocabularyContext},
+    scheduler::session,
+    timestamps,
+};
+
+/// Sightings of an item, oldest first
+pub(crate) fn load_contexts(conn: &mut SqliteConnection, vocabulary_id: &str) -> QueryResult<Vec<VocabularyContext>> {
+    use crate::schema::vocabulary_contexts;
+
+    vocabulary_contexts::table
+        .filter(vocabulary_contexts::vocabulary_id.eq(vocabulary_id))
+        .order(vocabulary_contexts::created_at.asc())
+        .select(VocabularyContext::as_select())
+        .load(conn)
+}
+
+/// Sightings of several items by item id, each oldest first
+pub(crate) fn load_contexts_by_vocabulary(
+    conn: &mut SqliteConnection,
+    vocabulary_ids: &[&str],
+) -> QueryResult<HashMap<String, Vec<VocabularyContext>>> {
+    use crate::schema::vocabulary_contexts;
+
+    let mut by_vocabulary: HashMap<String, Vec<VocabularyContext>> = HashMap::new();
+    for context in vocabulary_contexts::table
+        .filter(vocabulary_contexts::vocabulary_id.eq_any(vocabulary_ids))
+        .order(vocabulary_contexts::created_at.asc())
+        .select(VocabularyContext::as_select())
+        .load(conn)?
+    {
+        by_vocabulary.entry(context.vocabulary_id.clone()).or_default().push(context);
+    }
+    Ok(by_vocabulary)
+}
+
+/// The sighting the next review of `item` shows, `None` for the sentence it was saved from
+pub(crate) fn review_context(item: &Vocabulary, mut sightings: Vec<VocabularyContext>) -> Option<VocabularyContext> {
+    match session::rotate_context(item.review_count.unwrap_or(0), sightings.len()) {
+        0 => None,
+        n => Some(sightings.swap_remove(n - 1)),
+    }
+}
+
+/// Remove the sightings of a vocabulary item that is being deleted
+pub(crate) fn delete_contexts_for_vocabulary(conn: &mut SqliteConnection, vocabulary_id: &str) -> QueryResult<usize> {
+    use crate::schema::vocabulary_contexts;
+
+    diesel::delete(vocabulary_contexts::table.filter(vocabulary_contexts::vocabulary_id.eq(vocabulary_id)))
+        .execute(conn)
+}
+
+fn find_vocabulary(conn: &mut SqliteConnection, vocabulary_id: &str) -> Result<Vocabulary> {
+    use crate::schema::vocabulary;
+
+    vocabulary::table
+        .filter(vocabulary::id.eq(vocabulary_id))
+        .select(Vocabulary::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| app_error!("VOCABULARY_NOT_FOUND", "Vocabulary item not found", vocabulary_id.to_string()))
+}
+
+/// Add a sentence the item was seen in again, e.g. in another video
+#[tauri::command]
+pub fn add_vocabulary_context(vocabulary_id: String, request: AddVocabularyContextRequest) -> Result<VocabularyContext> {
+    let mut conn = establish_connection()?;
+    insert_context(&mut conn, vocabulary_id, request, timestamps::now_ms())
+}
+
+/// `add_vocabulary_context` with the sighting recorded at `now_ms`
+pub(crate) fn insert_context(
+    conn: &mut SqliteConnection,
+    vocabulary_id: String,
+    request: AddVocabularyContextRequest,
+    now_ms: i64,
+) -> Result<VocabularyContext> {
+    use crate::schema::vocabulary_contexts;
+
+    if request.target_en.trim().is_empty() {
+        return Err(app_error!("VALIDATION_ERROR", "target_en is required"));
+    }
+    if request.timestamp < 0 || request.word_start_index.is_some_and(|start| start < 0) {
+        return Err(app_error!("VALIDATION_ERROR", "Timestamps and word indices must not be negative"));
+    }
+
+    conn.immediate_transaction(|conn| {
+        let item = find_vocabulary(conn, &vocabulary_id)?;
+        let is_known = |video_id: &str, timestamp: i32| video_id == request.video_id && timestamp == request.timestamp;
+        if is_known(&item.video_id, item.timestamp)
+            || load_contexts(conn, &vocabulary_id)?
+                .iter()
+                .any(|context| is_known(&context.video_id, context.timestamp))
+        {
+            return Err(app_error!(
+                "DUPLICATE_CONTEXT",
+                "This sentence is already a context of the item",
+                format!("{} at {}", request.video_id, request.timestamp)
+            ));
+        }
+
+        let context = VocabularyContext {
+            id: Uuid::new_v4().to_string(),
+            vocabulary_id,
+            video_id: request.video_id,
+            timestamp: request.timestamp,
+            before_2_timestamp: request.before_2_timestamp,
+            before_2_en: request.before_2_en,
+            before_2_zh: request.before_2_zh,
+            before_1_en: request.before_1_en,
+            before_1_zh: request.before_1_zh,
+            target_en: request.target_en,
+            target_zh: request.target_zh,
+            word_start_index: request.word_start_index,
+            word_end_index: request.word_start_index.map(|start| start.saturating_add(item.word.len() as i32)),
+            created_at: now_ms,
+        };
+        diesel::insert_into(vocabulary_contexts::table)
+            .values(&context)
+            .execute(conn)?;
+
+        Ok(context)
+    })
+}
+
+/// Other sightings of an item, oldest first. The sentence it was saved from is on the item itself.
+#[tauri::command]
+pub fn get_vocabulary_contexts(vocabulary_id: String) -> Result<Vec<VocabularyContext>> {
+    let mut conn = establish_connection()?;
+    load_contexts(&mut conn, &vocabulary_id)
+        .map_err(|e| AppError::new("VOCABULARY_CONTEXT_FETCH_ERROR", "Failed to fetch vocabulary contexts").with_details(e.to_string()))
+}
+
+#[tauri::command]
+pub fn remove_vocabulary_context(context_id: String) -> Result<()> {
+    use crate::schema::vocabulary_contexts;
+
+    let mut conn = establish_connection()?;
+    let deleted = diesel::delete(vocabulary_contexts::table.find(&context_id)).execute(&mut *conn)?;
+    if deleted == 0 {
+        return Err(app_error!("VOCABULARY_CONTEXT_NOT_FOUND", "Vocabulary context not found", context_id));
+    }
+    Ok(())
+}
+
+/// The sentence to show when reviewing an item next, rotating through its contexts.
+/// `None` means the sentence the item was saved from.
+#[tauri::command]
+pub fn get_review_context(vocabulary_id: String) -> Result<Option<VocabularyContext>> {
+    let mut conn = establish_connection()?;
+    let item = find_vocabulary(&mut conn, &vocabulary_id)?;
+    let sightings = load_contexts(&mut conn, &vocabulary_id)?;
+    Ok(review_context(&item, sightings))
+}
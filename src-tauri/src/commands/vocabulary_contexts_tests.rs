@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::vocabulary::{insert_vocabulary, record_review};
+    use crate::commands::vocabulary_contexts::{insert_context, load_contexts, review_context};
+    use crate::models::vocabulary_context::AddVocabularyContextRequest;
+    use crate::scheduler::ReviewGrade;
+    use crate::test_support::{migrated_connection, new_vocabulary};
+    use chrono::Utc;
+
+    const NOW_MS: i64 = 1_792_315_800_000;
+
+    fn sighting(video_id: &str, timestamp: i32) -> AddVocabularyContextRequest {
+        AddVocabularyContextRequest {
+            video_id: video_id.to_string(),
+            timestamp,
+            before_2_timestamp: None,
+            before_2_en: None,
+            before_2_zh: None,
+            before_1_en: Some("Listen.".to_string()),
+            before_1_zh: Some("听。".to_string()),
+            target_en: "The dogs bark at night".to_string(),
+            target_zh: "狗在夜里叫".to_string(),
+            word_start_index: Some(9),
+        }
+    }
+
+    #[test]
+    fn test_added_context_round_trips() {
+        let mut conn = migrated_connection(":memory:");
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+
+        let added = insert_context(&mut conn, id.clone(), sighting("video-2", 2_000), NOW_MS).unwrap();
+        assert_eq!((added.word_start_index, added.word_end_index), (Some(9), Some(13)));
+
+        let contexts = load_contexts(&mut conn, &id).unwrap();
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].id, added.id);
+        assert_eq!((contexts[0].video_id.as_str(), contexts[0].timestamp), ("video-2", 2_000));
+        assert_eq!(contexts[0].before_1_zh.as_deref(), Some("听。"));
+        assert_eq!(contexts[0].created_at, NOW_MS);
+
+        // Saving the word again from the sighted sentence finds the item
+        let again = insert_vocabulary(&mut conn, &new_vocabulary("Bark", "video-2", 2_000)).unwrap();
+        assert_eq!(again.id.as_deref(), Some(id.as_str()));
+    }
+
+    #[test]
+    fn test_known_sentences_and_bad_requests_are_refused() {
+        let mut conn = migrated_connection(":memory:");
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+        insert_context(&mut conn, id.clone(), sighting("video-2", 2_000), NOW_MS).unwrap();
+
+        let refused = |conn: &mut _, vocabulary_id: &str, request| {
+            insert_context(conn, vocabulary_id.to_string(), request, NOW_MS).unwrap_err().code
+        };
+        assert_eq!(refused(&mut conn, &id, sighting("video-1", 1_000)), "DUPLICATE_CONTEXT");
+        assert_eq!(refused(&mut conn, &id, sighting("video-2", 2_000)), "DUPLICATE_CONTEXT");
+        assert_eq!(refused(&mut conn, "missing", sighting("video-3", 3_000)), "VOCABULARY_NOT_FOUND");
+        let blank = AddVocabularyContextRequest { target_en: " ".to_string(), ..sighting("video-3", 3_000) };
+        assert_eq!(refused(&mut conn, &id, blank), "VALIDATION_ERROR");
+        let negative = AddVocabularyContextRequest { word_start_index: Some(-1), ..sighting("video-3", 3_000) };
+        assert_eq!(refused(&mut conn, &id, negative), "VALIDATION_ERROR");
+        assert_eq!(load_contexts(&mut conn, &id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_reviews_rotate_through_the_contexts() {
+        let mut conn = migrated_connection(":memory:");
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+        let added = insert_context(&mut conn, id.clone(), sighting("video-2", 2_000), NOW_MS).unwrap();
+
+        let mut shown = Vec::new();
+        for _ in 0..2 {
+            let item = record_review(&mut conn, &id, ReviewGrade::Good, None, Utc::now()).unwrap();
+            let context = review_context(&item, load_contexts(&mut conn, &id).unwrap());
+            shown.push(context.map(|context| context.id));
+        }
+        shown.sort();
+        assert_eq!(shown, vec![None, Some(added.id)]);
+    }
+}
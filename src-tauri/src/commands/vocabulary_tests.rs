@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::review_log::undo_review;
+    use crate::commands::settings::load_user_settings;
+    use crate::commands::vocabulary::{insert_vocabulary, merge_items, record_review, reset_item, set_suspended};
+    use crate::scheduler::ReviewGrade;
+    use crate::commands::vocabulary_contexts::load_contexts;
+    use crate::models::vocabulary::{CreateVocabularyRequest, Vocabulary};
+    use crate::models::vocabulary_context::VocabularyContext;
+    use crate::test_support::{migrated_connection, new_vocabulary, vocabulary_request};
+    use diesel::prelude::*;
+    use chrono::{Duration, Utc};
+    use diesel::sqlite::SqliteConnection;
+
+    fn item_ids(conn: &mut SqliteConnection) -> Vec<String> {
+        use crate::schema::vocabulary;
+
+        vocabulary::table
+            .select(vocabulary::id.assume_not_null())
+            .order(vocabulary::id.asc())
+            .load(conn)
+            .unwrap()
+    }
+
+    fn add_attempt(conn: &mut SqliteConnection, vocabulary_id: &str) {
+        use crate::schema::speaking_attempts;
+
+        diesel::insert_into(speaking_attempts::table)
+            .values((
+                speaking_attempts::id.eq(format!("attempt-{}", vocabulary_id)),
+                speaking_attempts::user_id.eq("1"),
+                speaking_attempts::vocabulary_id.eq(vocabulary_id),
+                speaking_attempts::audio_path.eq("attempt.wav"),
+                speaking_attempts::audio_format.eq("wav"),
+                speaking_attempts::duration_ms.eq(1200),
+                speaking_attempts::created_at.eq("2026-10-18T09:30:00Z"),
+            ))
+            .execute(conn)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_duplicate_save_returns_existing_item() {
+        let mut conn = migrated_connection(":memory:");
+        let saved = insert_vocabulary(&mut conn, &new_vocabulary("Bark", "video-1", 754_560)).unwrap();
+
+        let again = insert_vocabulary(&mut conn, &new_vocabulary("bark ", "video-1", 754_560)).unwrap();
+        assert_eq!(again.id, saved.id);
+        assert_eq!(item_ids(&mut conn).len(), 1);
+
+        // Another sentence, or another word in the same sentence, is a new item
+        insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 800_000)).unwrap();
+        insert_vocabulary(&mut conn, &new_vocabulary("dog", "video-1", 754_560)).unwrap();
+        assert_eq!(item_ids(&mut conn).len(), 3);
+    }
+
+    #[test]
+    fn test_create_rejects_negative_word_index() {
+        let negative = CreateVocabularyRequest { word_start_index: Some(-1), ..vocabulary_request("bark", "video-1", 1_000) };
+        assert_eq!(negative.into_new_vocabulary().err().unwrap().code, "VALIDATION_ERROR");
+
+        let item = CreateVocabularyRequest { word_start_index: Some(6), ..vocabulary_request("bark", "video-1", 1_000) }
+            .into_new_vocabulary()
+            .unwrap();
+        assert_eq!((item.word_start_index, item.word_end_index), (Some(6), Some(10)));
+    }
+
+    #[test]
+    fn test_merge_keeps_most_advanced_item_and_moves_everything_to_it() {
+        use crate::schema::{speaking_attempts, vocabulary, vocabulary_contexts};
+
+        let mut conn = migrated_connection(":memory:");
+        let mut early = new_vocabulary("bark", "video-1", 1_000);
+        early.id = "a".to_string();
+        (early.review_stage, early.review_count, early.correct_count, early.lapses) = (1, 2, 1, 1);
+        let mut advanced = new_vocabulary("bark", "video-2", 2_000);
+        advanced.id = "b".to_string();
+        (advanced.review_stage, advanced.review_count, advanced.correct_count) = (3, 5, 4);
+        let mut fresh = new_vocabulary("bark", "video-3", 3_000);
+        fresh.id = "c".to_string();
+        fresh.dictionary_response = Some("**含义：** 吠叫".to_string());
+        for item in [&early, &advanced, &fresh] {
+            insert_vocabulary(&mut conn, item).unwrap();
+        }
+        let fresh_item: Vocabulary = vocabulary::table
+            .filter(vocabulary::id.eq("c"))
+            .select(Vocabulary::as_select())
+            .first(&mut conn)
+            .unwrap();
+        diesel::insert_into(vocabulary_contexts::table)
+            .values(VocabularyContext {
+                video_id: "video-4".to_string(),
+                timestamp: 4_000,
+                ..VocabularyContext::from_vocabulary(&fresh_item, "c", 1_792_315_800_000)
+            })
+            .execute(&mut conn)
+            .unwrap();
+        add_attempt(&mut conn, "a");
+        add_attempt(&mut conn, "c");
+
+        let ids = ["a", "b", "c", "a"].map(String::from).to_vec();
+        let merged = merge_items(&mut conn, ids).unwrap();
+
+        assert_eq!(merged.id.as_deref(), Some("b"));
+        assert_eq!(merged.review_stage, Some(3));
+        assert_eq!((merged.review_count, merged.correct_count, merged.lapses), (Some(7), Some(5), 1));
+        assert_eq!(merged.dictionary_response.as_deref(), Some("**含义：** 吠叫"));
+        assert_eq!(item_ids(&mut conn), vec!["b".to_string()]);
+
+        let mut sightings: Vec<(String, i32)> = load_contexts(&mut conn, "b")
+            .unwrap()
+            .into_iter()
+            .map(|context| (context.video_id, context.timestamp))
+            .collect();
+        sightings.sort();
+        assert_eq!(
+            sightings,
+            vec![("video-1".to_string(), 1_000), ("video-3".to_string(), 3_000), ("video-4".to_string(), 4_000)]
+        );
+        assert!(load_contexts(&mut conn, "c").unwrap().is_empty());
+
+        let attempts: Vec<String> = speaking_attempts::table
+            .select(speaking_attempts::vocabulary_id)
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(attempts, vec!["b".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_rejects_single_and_missing_items() {
+        let mut conn = migrated_connection(":memory:");
+        let item = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap();
+        let id = item.id.unwrap();
+
+        let single = merge_items(&mut conn, vec![id.clone(), id.clone()]).unwrap_err();
+        assert_eq!(single.code, "VALIDATION_ERROR");
+        let missing = merge_items(&mut conn, vec![id, "gone".to_string()]).unwrap_err();
+        assert_eq!(missing.code, "VOCABULARY_NOT_FOUND");
+        assert_eq!(item_ids(&mut conn).len(), 1);
+    }
+
+    fn set_leech_settings(conn: &mut SqliteConnection, threshold: i32, suspend: bool) {
+        use crate::schema::user_settings;
+
+        let settings = load_user_settings(conn, 1).unwrap();
+        diesel::update(user_settings::table.find(settings.id))
+            .set((user_settings::leech_threshold.eq(threshold), user_settings::suspend_leeches.eq(suspend)))
+            .execute(conn)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_failed_reviews_of_learned_items_count_as_lapses_until_leech() {
+        let mut conn = migrated_connection(":memory:");
+        set_leech_settings(&mut conn, 2, true);
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+        let start = Utc::now();
+        let mut review = |grade: ReviewGrade, minutes: i64| {
+            record_review(&mut conn, &id, grade, None, start + Duration::minutes(minutes)).unwrap()
+        };
+
+        // Failing an item that was never reviewed isn't a lapse
+        let item = review(ReviewGrade::Again, 0);
+        assert_eq!((item.lapses, item.is_leech), (0, false));
+        review(ReviewGrade::Good, 1);
+
+        let item = review(ReviewGrade::Again, 2);
+        assert_eq!((item.lapses, item.is_leech, item.suspended), (1, false, false));
+
+        let item = review(ReviewGrade::Again, 3);
+        assert_eq!((item.lapses, item.is_leech, item.suspended), (2, true, true));
+    }
+
+    #[test]
+    fn test_leeches_are_only_suspended_when_enabled() {
+        let mut conn = migrated_connection(":memory:");
+        set_leech_settings(&mut conn, 1, false);
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+        let start = Utc::now();
+
+        record_review(&mut conn, &id, ReviewGrade::Good, None, start).unwrap();
+        let item = record_review(&mut conn, &id, ReviewGrade::Again, None, start + Duration::minutes(1)).unwrap();
+        assert_eq!((item.lapses, item.is_leech, item.suspended), (1, true, false));
+    }
+
+    #[test]
+    fn test_suspend_and_unsuspend() {
+        let mut conn = migrated_connection(":memory:");
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+
+        assert!(set_suspended(&mut conn, id.clone(), true).unwrap().suspended);
+        assert!(!set_suspended(&mut conn, id, false).unwrap().suspended);
+        assert_eq!(set_suspended(&mut conn, "missing".to_string(), true).unwrap_err().code, "VOCABULARY_NOT_FOUND");
+    }
+
+    #[test]
+    fn test_reset_clears_progress_and_reviews_before_it_cannot_be_undone() {
+        use crate::schema::review_log;
+
+        let mut conn = migrated_connection(":memory:");
+        set_leech_settings(&mut conn, 1, true);
+        let id = insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap().id.unwrap();
+        let start = Utc::now();
+        record_review(&mut conn, &id, ReviewGrade::Good, None, start).unwrap();
+        let leech = record_review(&mut conn, &id, ReviewGrade::Again, None, start).unwrap();
+        assert!(leech.is_leech && leech.suspended);
+
+        let now_ms = start.timestamp_millis();
+        let item = reset_item(&mut conn, id.clone(), now_ms).unwrap();
+        assert_eq!(item.review_stage, Some(0));
+        assert_eq!(item.review_count, Some(0));
+        assert_eq!((item.lapses, item.is_leech, item.suspended), (0, false, false));
+        assert_eq!((item.next_review_at, item.last_reviewed_at), (now_ms, None));
+
+        let snapshots: Vec<Option<String>> = review_log::table
+            .filter(review_log::vocabulary_id.eq(&id))
+            .select(review_log::snapshot)
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(snapshots, vec![None, None]);
+        assert_eq!(undo_review(&mut conn, "1", now_ms).unwrap_err().code, "UNDO_ERROR");
+        assert_eq!(reset_item(&mut conn, "missing".to_string(), now_ms).unwrap_err().code, "VOCABULARY_NOT_FOUND");
+    }
+}
@@ -0,0 +1,254 @@
+use crate::app_error;
+use crate::commands::settings::{load_user_settings, settings_user_id};
+use crate::database::establish_connection;
+use crate::error::AppError;
+use crate::speech::models::{self, ChecksumStatus, DEFAULT_WHISPER_MODEL, WHISPER_MODELS};
+use diesel::prelude::*;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::Path;
+use tauri::Emitter;
+
+#[derive(Debug, Serialize)]
+pub struct InstalledWhisperModel {
+    pub name: String,
+    pub size_bytes: u64,
+    pub multilingual: bool,
+    pub quantization: String,
+    pub checksum_status: ChecksumStatus,
+    /// A user-supplied model rather than one published by whisper.cpp
+    pub is_custom: bool,
+    pub is_default: bool,
+}
+
+/// The model a user picked as default, or the app default when none is set
+pub(crate) fn default_model_for_user(user_id: Option<&str>) -> Result<String, AppError> {
+    let user_id = match user_id {
+        Some(id) => settings_user_id(id),
+        None => return Ok(DEFAULT_WHISPER_MODEL.to_string()),
+    };
+    
+    let mut conn = establish_connection()?;
+    let settings = load_user_settings(&mut conn, user_id)
+        .map_err(|e| app_error!("SETTINGS_FETCH_ERROR", "Failed to fetch user settings", e.to_string()))?;
+    
+    Ok(settings.whisper_model.unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string()))
+}
+
+fn describe_model(path: &Path, default_model: &str, verify: bool) -> Result<InstalledWhisperModel, AppError> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| app_error!("INVALID_MODEL_NAME", "Invalid Whisper model file name"))?
+        .to_string();
+    let size_bytes = fs::metadata(path)?.len();
+    let header = models::read_model_header(path);
+    
+    Ok(InstalledWhisperModel {
+        multilingual: header
+            .map(|h| h.is_multilingual())
+            .unwrap_or_else(|| !models::is_english_only(&name)),
+        quantization: header.map(|h| h.quantization()).unwrap_or("unknown").to_string(),
+        checksum_status: models::checksum_status(path, verify),
+        is_custom: models::find_model(&name).is_none(),
+        is_default: name == default_model,
+        size_bytes,
+        name,
+    })
+}
+
+/// Check if a Whisper model is available
+#[tauri::command]
+pub async fn check_whisper_model(model_name: Option<String>) -> Result<bool, AppError> {
+    let model = model_name.unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string());
+    models::validate_model_name(&model)?;
+    let model_path = models::model_dir()?.join(&model);
+    
+    Ok(model_path.exists() && model_path.is_file())
+}
+
+/// Download a Whisper model
+#[tauri::command]
+pub async fn download_whisper_model(
+    model_name: Option<String>,
+    window: tauri::Window,
+) -> Result<(), AppError> {
+    let model = model_name.unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string());
+    models::validate_model_name(&model)?;
+    let model_path = models::model_dir()?.join(&model);
+    
+    if model_path.exists() {
+        return Ok(());
+    }
+    
+    // Model URLs from Hugging Face
+    let spec = match models::find_model(&model) {
+        Some(spec) => spec,
+        None => return Err(app_error!("MODEL_ERROR", "Unknown model name", model)),
+    };
+    let model_url = models::download_url(spec);
+    
+    // Emit download progress events
+    window.emit("whisper-model-download-start", &model)
+        .map_err(|e| app_error!("EVENT_ERROR", "Failed to emit event", e.to_string()))?;
+    
+    // Download the model file
+    let response = reqwest::get(&model_url).await
+        .map_err(|e| app_error!("DOWNLOAD_ERROR", "Failed to download model", e.to_string()))?;
+    let total_size = response.content_length().unwrap_or(0);
+    
+    let mut downloaded = 0;
+    let mut stream = response.bytes_stream();
+    let mut file_content = Vec::new();
+    let mut hasher = Sha1::new();
+    
+    use futures_util::StreamExt;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk
+            .map_err(|e| app_error!("DOWNLOAD_ERROR", "Failed to download chunk", e.to_string()))?;
+        hasher.update(&chunk);
+        file_content.extend_from_slice(&chunk);
+        downloaded += chunk.len() as u64;
+        
+        // Emit progress
+        let progress = if total_size > 0 {
+            (downloaded as f64 / total_size as f64) * 100.0
+        } else {
+            0.0
+        };
+        
+        window.emit("whisper-model-download-progress", progress)
+            .map_err(|e| app_error!("EVENT_ERROR", "Failed to emit progress", e.to_string()))?;
+    }
+    
+    models::verify_download(spec, &format!("{:x}", hasher.finalize()))?;
+    
+    // Save the model file and remember its checksum for later integrity checks
+    fs::write(&model_path, &file_content)?;
+    models::record_checksum(&model_path)?;
+    
+    window.emit("whisper-model-download-complete", &model)
+        .map_err(|e| app_error!("EVENT_ERROR", "Failed to emit complete event", e.to_string()))?;
+    
+    Ok(())
+}
+
+
+/// Get available Whisper models, both multilingual and English-only (`.en`) variants
+#[tauri::command]
+pub fn get_available_whisper_models() -> Vec<&'static str> {
+    WHISPER_MODELS.iter().map(|spec| spec.name).collect()
+}
+
+/// List the models installed in the model directory with their size and integrity.
+///
+/// Hashing large models is slow, so checksums are only verified on request.
+#[tauri::command]
+pub async fn list_whisper_models(
+    user_id: Option<String>,
+    verify_checksums: Option<bool>,
+) -> Result<Vec<InstalledWhisperModel>, AppError> {
+    let default_model = default_model_for_user(user_id.as_deref())?;
+    let verify = verify_checksums.unwrap_or(false);
+    
+    let mut installed = Vec::new();
+    for entry in fs::read_dir(models::model_dir()?)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("bin") {
+            installed.push(describe_model(&path, &default_model, verify)?);
+        }
+    }
+    installed.sort_by(|a, b| a.name.cmp(&b.name));
+    
+    Ok(installed)
+}
+
+/// Delete an installed model and clear it as default wherever it was selected
+#[tauri::command]
+pub async fn delete_whisper_model(model_name: String) -> Result<(), AppError> {
+    use crate::schema::user_settings;
+    
+    models::validate_model_name(&model_name)?;
+    let model_path = models::model_dir()?.join(&model_name);
+    if !model_path.is_file() {
+        return Err(app_error!("MODEL_NOT_FOUND", "Whisper model is not installed", model_name));
+    }
+    
+    models::remove_model_files(&model_path)?;
+    
+    let mut conn = establish_connection()?;
+    diesel::update(user_settings::table.filter(user_settings::whisper_model.eq(&model_name)))
+        .set(user_settings::whisper_model.eq(None::<String>))
+        .execute(&mut *conn)?;
+    
+    Ok(())
+}
+
+/// Persist the model used when `transcribe_audio` is called without a model name
+#[tauri::command]
+pub async fn set_default_whisper_model(user_id: String, model_name: String) -> Result<(), AppError> {
+    use crate::schema::user_settings;
+    
+    models::validate_model_name(&model_name)?;
+    if !models::model_dir()?.join(&model_name).is_file() {
+        return Err(app_error!("MODEL_NOT_FOUND", "Whisper model is not installed", model_name));
+    }
+    
+    let mut conn = establish_connection()?;
+    let settings = load_user_settings(&mut conn, settings_user_id(&user_id))?;
+    diesel::update(user_settings::table.find(settings.id))
+        .set((
+            user_settings::whisper_model.eq(&model_name),
+            user_settings::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(&mut *conn)?;
+    
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_default_whisper_model(user_id: String) -> Result<String, AppError> {
+    default_model_for_user(Some(&user_id))
+}
+
+/// Install a user-supplied ggml `.bin` model, e.g. a fine-tuned or quantized variant
+#[tauri::command]
+pub async fn import_whisper_model(
+    source_path: String,
+    model_name: Option<String>,
+    user_id: Option<String>,
+) -> Result<InstalledWhisperModel, AppError> {
+    let source = Path::new(&source_path);
+    if !source.is_file() {
+        return Err(app_error!("FILE_NOT_FOUND", "The specified file does not exist", source_path));
+    }
+    
+    if models::read_model_header(source).is_none() {
+        return Err(app_error!(
+            "INVALID_MODEL",
+            "The file is not a whisper.cpp ggml model",
+            source_path
+        ));
+    }
+    
+    let model_name = match model_name {
+        Some(name) => name,
+        None => source
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string(),
+    };
+    models::validate_model_name(&model_name)?;
+    
+    let target = models::model_dir()?.join(&model_name);
+    if target.exists() {
+        return Err(app_error!("MODEL_EXISTS", "A Whisper model with this name is already installed", model_name));
+    }
+    
+    fs::copy(source, &target)?;
+    models::record_checksum(&target)?;
+    
+    describe_model(&target, &default_model_for_user(user_id.as_deref())?, false)
+}
@@ -4,6 +4,8 @@ use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use diesel::r2d2::{self, ConnectionManager};
 use std::sync::Mutex;
 use crate::error::{AppError, Result};
+use crate::lemmatizer;
+use crate::snapshots::{self, SnapshotKind};
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
@@ -12,8 +14,9 @@ pub type PooledConnection = r2d2::PooledConnection<ConnectionManager<SqliteConne
 
 static POOL: Mutex<Option<Pool>> = Mutex::new(None);
 
-fn get_database_url() -> String {
-    let database_url = if let Ok(app_dir) = std::env::var("CARGO_MANIFEST_DIR") {
+/// Location of `database.sqlite`
+pub fn database_path() -> std::path::PathBuf {
+    if let Ok(app_dir) = std::env::var("CARGO_MANIFEST_DIR") {
         // During development, use the manifest directory
         std::path::Path::new(&app_dir).join("database.sqlite")
     } else {
@@ -21,9 +24,11 @@ fn get_database_url() -> String {
         std::env::current_dir()
             .unwrap_or_else(|_| std::path::PathBuf::from("."))
             .join("database.sqlite")
-    };
-    
-    database_url.to_string_lossy().to_string()
+    }
+}
+
+fn get_database_url() -> String {
+    database_path().to_string_lossy().to_string()
 }
 
 pub fn init_pool() -> Result<()> {
@@ -67,9 +72,32 @@ pub fn init_pool() -> Result<()> {
             .map_err(|e| AppError::new("PRAGMA_ERROR", "Failed to set mmap size")
                 .with_details(e.to_string()))?;
         
+        // Corruption is only reported, the app still starts so a snapshot can be restored
+        let problems = snapshots::check_integrity(&mut conn)
+            .map_err(|e| AppError::new("INTEGRITY_CHECK_ERROR", "Failed to check database integrity")
+                .with_details(e.to_string()))?;
+        if !problems.is_empty() {
+            eprintln!("Database integrity check failed: {}", problems.join("; "));
+        }
+
+        // Migrations can't be undone, keep a copy of the database from before them
+        let has_pending_migrations = conn.has_pending_migration(MIGRATIONS)
+            .map_err(|e| AppError::new("MIGRATION_ERROR", "Failed to check for pending migrations")
+                .with_details(e.to_string()))?;
+        let is_new_database = conn.applied_migrations().map(|applied| applied.is_empty()).unwrap_or(true);
+        if has_pending_migrations && !is_new_database && problems.is_empty() {
+            if let Err(e) = snapshots::take_snapshot(SnapshotKind::PreMigration) {
+                eprintln!("Failed to snapshot database before migrations: {}", e);
+            }
+        }
+
         conn.run_pending_migrations(MIGRATIONS)
             .map_err(|e| AppError::new("MIGRATION_ERROR", "Failed to run database migrations")
                 .with_details(e.to_string()))?;
+
+        lemmatizer::backfill_lemmas(&mut conn)
+            .map_err(|e| AppError::new("MIGRATION_ERROR", "Failed to fill in vocabulary lemmas")
+                .with_details(e.to_string()))?;
     }
     
     let mut pool_guard = POOL.lock().unwrap();
@@ -78,6 +106,29 @@ pub fn init_pool() -> Result<()> {
     Ok(())
 }
 
+/// Drop the pool so the database file can be replaced, connections still checked
+/// out close when they are returned. `init_pool` opens it again.
+pub fn close_pool() {
+    let mut pool_guard = POOL.lock().unwrap();
+    *pool_guard = None;
+}
+
+/// Swap the database file for `replacement` and open the pool on it again. If the
+/// swap fails the pool is reopened on the old file before the error is returned.
+pub fn replace_database(replacement: &std::path::Path) -> Result<()> {
+    let db_path = database_path();
+    close_pool();
+    if let Err(e) = std::fs::rename(replacement, &db_path) {
+        init_pool()?;
+        return Err(e.into());
+    }
+    // The old file's write-ahead log must not be replayed into the new one
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+    }
+    init_pool()
+}
+
 pub fn get_connection() -> Result<PooledConnection> {
     let pool_guard = POOL.lock().unwrap();
     let pool = pool_guard.as_ref()
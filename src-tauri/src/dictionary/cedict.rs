@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use crate::dictionary::ParsedEntry;
+use crate::error::Result;
+
+/// English glosses longer than this many words aren't indexed for English lookups
+const MAX_REVERSE_GLOSS_WORDS: usize = 3;
+
+/// One CC-CEDICT line
+#[derive(Debug, Clone, PartialEq)]
+pub struct CedictLine {
+    pub traditional: String,
+    pub simplified: String,
+    pub pinyin: String,
+    pub glosses: Vec<String>,
+}
+
+/// Parse `中國 中国 [Zhong1 guo2] /China/Middle Kingdom/`, `None` for comments and
+/// malformed lines
+pub fn parse_line(line: &str) -> Option<CedictLine> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (traditional, rest) = line.split_once(' ')?;
+    let (simplified, rest) = rest.split_once(' ')?;
+    let (pinyin, rest) = rest.strip_prefix('[')?.split_once(']')?;
+    let glosses: Vec<String> = rest
+        .trim()
+        .trim_matches('/')
+        .split('/')
+        .map(str::trim)
+        .filter(|gloss| !gloss.is_empty())
+        .map(str::to_string)
+        .collect();
+    if glosses.is_empty() {
+        return None;
+    }
+    Some(CedictLine {
+        traditional: traditional.to_string(),
+        simplified: simplified.to_string(),
+        pinyin: pinyin.to_string(),
+        glosses,
+    })
+}
+
+/// The English word or short phrase a gloss translates, "to run (of a machine)" is
+/// "run". `None` for glosses that are descriptions rather than translations.
+pub fn gloss_headword(gloss: &str) -> Option<String> {
+    let without_notes = match gloss.find('(') {
+        Some(start) => &gloss[..start],
+        None => gloss,
+    };
+    let word = without_notes.trim();
+    let word = word.strip_prefix("to ").unwrap_or(word).trim();
+    let is_plain = !word.is_empty()
+        && word.split_whitespace().count() <= MAX_REVERSE_GLOSS_WORDS
+        && word.chars().all(|c| c.is_ascii_alphabetic() || matches!(c, ' ' | '-' | '\''));
+    is_plain.then(|| word.to_string())
+}
+
+/// Read CC-CEDICT. Every line is an entry under its simplified and, where it differs,
+/// traditional headword. Short English glosses also become entries of their own,
+/// listing the Chinese words they translate, so English words can be looked up too.
+pub fn read_entries(reader: impl BufRead, mut on_entry: impl FnMut(ParsedEntry) -> Result<()>) -> Result<()> {
+    let mut by_gloss: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for line in reader.lines() {
+        let Some(line) = parse_line(&line?) else {
+            continue;
+        };
+
+        let translation = format!("{} [{}]", line.simplified, line.pinyin);
+        for gloss in &line.glosses {
+            if let Some(english) = gloss_headword(gloss) {
+                let translations = by_gloss.entry(english.to_lowercase()).or_default();
+                if !translations.contains(&translation) {
+                    translations.push(translation.clone());
+                }
+            }
+        }
+
+        let entry = ParsedEntry {
+            headword: line.simplified.clone(),
+            pronunciation: Some(line.pinyin),
+            part_of_speech: None,
+            definitions: line.glosses,
+            translations: Vec::new(),
+        };
+        if line.traditional != line.simplified {
+            on_entry(ParsedEntry { headword: line.traditional, ..entry.clone() })?;
+        }
+        on_entry(entry)?;
+    }
+
+    for (headword, translations) in by_gloss {
+        on_entry(ParsedEntry {
+            headword,
+            translations,
+            ..ParsedEntry::default()
+        })?;
+    }
+    Ok(())
+}
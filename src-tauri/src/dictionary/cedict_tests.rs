@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::cedict::{gloss_headword, parse_line, read_entries};
+
+    #[test]
+    fn test_parse_line() {
+        let line = parse_line("中國 中国 [Zhong1 guo2] /China/Middle Kingdom/").unwrap();
+        assert_eq!(line.traditional, "中國");
+        assert_eq!(line.simplified, "中国");
+        assert_eq!(line.pinyin, "Zhong1 guo2");
+        assert_eq!(line.glosses, vec!["China", "Middle Kingdom"]);
+
+        assert!(parse_line("# CC-CEDICT").is_none());
+        assert!(parse_line("中国 中国 Zhong1 guo2").is_none());
+    }
+
+    #[test]
+    fn test_gloss_headword() {
+        assert_eq!(gloss_headword("to run (of a machine)").as_deref(), Some("run"));
+        assert_eq!(gloss_headword("Middle Kingdom").as_deref(), Some("Middle Kingdom"));
+        assert_eq!(gloss_headword("variant of 跑[pao3]"), None);
+        assert_eq!(gloss_headword("used in names of places in the south"), None);
+    }
+
+    #[test]
+    fn test_english_glosses_become_entries() {
+        let text = "跑 跑 [pao3] /to run/\n奔跑 奔跑 [ben1 pao3] /to run/to rush/\n";
+        let mut entries = Vec::new();
+        read_entries(text.as_bytes(), |entry| {
+            entries.push(entry);
+            Ok(())
+        })
+        .unwrap();
+
+        let headwords: Vec<&str> = entries.iter().map(|entry| entry.headword.as_str()).collect();
+        assert_eq!(headwords, vec!["跑", "奔跑", "run", "rush"]);
+        assert_eq!(entries[0].pronunciation.as_deref(), Some("pao3"));
+        assert_eq!(entries[2].translations, vec!["跑 [pao3]", "奔跑 [ben1 pao3]"]);
+    }
+}
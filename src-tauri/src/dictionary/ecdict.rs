@@ -0,0 +1,71 @@
+use std::io::Read;
+
+use crate::app_error;
+use crate::dictionary::ParsedEntry;
+use crate::error::Result;
+
+/// Columns of ECDICT's CSV that are read, the rest (frequency ranks, tags, ...) are ignored
+const WORD: &str = "word";
+const PHONETIC: &str = "phonetic";
+const DEFINITION: &str = "definition";
+const TRANSLATION: &str = "translation";
+const POS: &str = "pos";
+
+/// ECDICT keeps several senses in one field, separated by a literal `\n`
+fn split_senses(field: &str) -> Vec<String> {
+    field
+        .split("\\n")
+        .flat_map(str::lines)
+        .map(str::trim)
+        .filter(|sense| !sense.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `n:46/v:54` (share of each part of speech in the corpus) as `n, v`
+fn parts_of_speech(field: &str) -> Option<String> {
+    let parts: Vec<&str> = field
+        .split('/')
+        .filter_map(|part| part.split(':').next())
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+fn non_empty(field: Option<&str>) -> Option<String> {
+    field.map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+/// Read ECDICT's CSV. Rows without a word or any definition are skipped.
+pub fn read_entries(reader: impl Read, mut on_entry: impl FnMut(ParsedEntry) -> Result<()>) -> Result<()> {
+    let parse_error = |details: String| app_error!("DICTIONARY_PARSE_ERROR", "Failed to read ECDICT file", details);
+
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers = reader.headers().map_err(|e| parse_error(e.to_string()))?.clone();
+    let column = |name: &str| headers.iter().position(|header| header.trim_start_matches('\u{feff}') == name);
+    let (word, phonetic, definition, translation, pos) = (column(WORD), column(PHONETIC), column(DEFINITION), column(TRANSLATION), column(POS));
+    let Some(word) = word else {
+        return Err(parse_error(format!("Missing column `{}`", WORD)));
+    };
+
+    for row in reader.records() {
+        let row = row.map_err(|e| parse_error(e.to_string()))?;
+        let field = |index: Option<usize>| index.and_then(|index| row.get(index));
+        let Some(headword) = non_empty(row.get(word)) else {
+            continue;
+        };
+        let entry = ParsedEntry {
+            headword,
+            pronunciation: non_empty(field(phonetic)),
+            part_of_speech: field(pos).and_then(parts_of_speech),
+            definitions: field(definition).map(split_senses).unwrap_or_default(),
+            translations: field(translation).map(split_senses).unwrap_or_default(),
+        };
+        if entry.definitions.is_empty() && entry.translations.is_empty() {
+            continue;
+        }
+        on_entry(entry)?;
+    }
+    Ok(())
+}
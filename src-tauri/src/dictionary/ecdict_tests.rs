@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::ecdict::read_entries;
+    use crate::dictionary::ParsedEntry;
+
+    #[test]
+    fn test_reads_senses_and_parts_of_speech() {
+        let csv = "word,phonetic,definition,translation,pos,collins\n\
+                   run,rʌn,\"n. a race\\nv. move fast\",\"n. 跑步\\nv. 跑\",n:20/v:80,5\n\
+                   empty,,,,,\n";
+        let mut entries = Vec::new();
+        read_entries(csv.as_bytes(), |entry| {
+            entries.push(entry);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![ParsedEntry {
+                headword: "run".into(),
+                pronunciation: Some("rʌn".into()),
+                part_of_speech: Some("n, v".into()),
+                definitions: vec!["n. a race".into(), "v. move fast".into()],
+                translations: vec!["n. 跑步".into(), "v. 跑".into()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_requires_word_column() {
+        let err = read_entries("phonetic,definition\nx,y\n".as_bytes(), |_| Ok(())).unwrap_err();
+        assert_eq!(err.code, "DICTIONARY_PARSE_ERROR");
+    }
+}
@@ -0,0 +1,96 @@
+pub mod cedict;
+pub mod ecdict;
+pub mod stardict;
+pub mod store;
+
+#[cfg(test)]
+mod cedict_tests;
+#[cfg(test)]
+mod ecdict_tests;
+#[cfg(test)]
+mod stardict_tests;
+#[cfg(test)]
+mod store_tests;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::Result;
+
+/// An entry read from a dictionary file, before it's stored
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParsedEntry {
+    pub headword: String,
+    pub pronunciation: Option<String>,
+    pub part_of_speech: Option<String>,
+    /// English definitions, one per sense
+    pub definitions: Vec<String>,
+    /// Chinese glosses
+    pub translations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DictionaryFormat {
+    /// ECDICT's `ecdict.csv`, English with Chinese translations
+    Ecdict,
+    /// CC-CEDICT's `cedict_ts.u8`, Chinese with English glosses
+    Cedict,
+    /// A StarDict `.ifo` file with its `.idx` and `.dict` or `.dict.dz` next to it
+    Stardict,
+}
+
+impl DictionaryFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ecdict => "ecdict",
+            Self::Cedict => "cedict",
+            Self::Stardict => "stardict",
+        }
+    }
+
+    /// Guess the format from the file name
+    pub fn detect(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Ecdict),
+            "u8" => Some(Self::Cedict),
+            "txt" if path.file_name()?.to_string_lossy().to_lowercase().contains("cedict") => Some(Self::Cedict),
+            "ifo" => Some(Self::Stardict),
+            _ => None,
+        }
+    }
+}
+
+/// Read every entry of a dictionary file, handing them to `on_entry` one at a time
+pub fn read_entries(format: DictionaryFormat, path: &Path, on_entry: impl FnMut(ParsedEntry) -> Result<()>) -> Result<()> {
+    match format {
+        DictionaryFormat::Ecdict => ecdict::read_entries(std::fs::File::open(path)?, on_entry),
+        DictionaryFormat::Cedict => cedict::read_entries(std::io::BufReader::new(std::fs::File::open(path)?), on_entry),
+        DictionaryFormat::Stardict => stardict::read_entries(path, on_entry),
+    }
+}
+
+/// Lookup key of a headword or query: lowercase, single spaces
+pub fn headword_key(word: &str) -> String {
+    word.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Whether `text` has any CJK ideographs, used to tell Chinese glosses from English
+pub fn has_chinese(text: &str) -> bool {
+    text.chars().any(|c| matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}'))
+}
+
+/// Name to list a dictionary under when the user doesn't give one: a StarDict's own
+/// name, otherwise the file name
+pub fn default_name(format: DictionaryFormat, path: &Path) -> String {
+    let book_name = match format {
+        DictionaryFormat::Stardict => stardict::book_name(path),
+        _ => None,
+    };
+    book_name.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format.as_str().to_string())
+    })
+}
@@ -0,0 +1,231 @@
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::app_error;
+use crate::dictionary::{has_chinese, ParsedEntry};
+use crate::error::{AppError, Result};
+
+const IFO_MAGIC: &str = "StarDict's dict ifo file";
+
+/// Settings from a `.ifo` file that reading the dictionary needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct StardictInfo {
+    pub book_name: Option<String>,
+    /// Field types every article has, e.g. `m`. When unset each field is tagged with its type.
+    pub same_type_sequence: Option<String>,
+    /// 64 when index offsets are 8 bytes instead of 4
+    pub idx_offset_bits: u32,
+}
+
+impl StardictInfo {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.trim_start_matches('\u{feff}').lines();
+        if lines.next().map(str::trim) != Some(IFO_MAGIC) {
+            return Err(app_error!("DICTIONARY_PARSE_ERROR", "Not a StarDict .ifo file"));
+        }
+        let values: HashMap<&str, &str> = lines
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        Ok(Self {
+            book_name: values.get("bookname").map(|name| name.to_string()),
+            same_type_sequence: values.get("sametypesequence").map(|sequence| sequence.to_string()),
+            idx_offset_bits: values.get("idxoffsetbits").and_then(|bits| bits.parse().ok()).unwrap_or(32),
+        })
+    }
+}
+
+/// Where a word's article is in the `.dict` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub word: String,
+    pub offset: u64,
+    pub size: u32,
+}
+
+fn truncated(file: &str) -> AppError {
+    app_error!("DICTIONARY_PARSE_ERROR", "StarDict file is truncated", file.to_string())
+}
+
+fn read_be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+/// Parse a `.idx` file: NUL-terminated words, each followed by a big-endian offset and size
+pub fn parse_index(data: &[u8], idx_offset_bits: u32) -> Result<Vec<IndexEntry>> {
+    let offset_len = if idx_offset_bits == 64 { 8 } else { 4 };
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let word_end = data[pos..]
+            .iter()
+            .position(|&byte| byte == 0)
+            .map(|len| pos + len)
+            .ok_or_else(|| truncated(".idx"))?;
+        let fields = data.get(word_end + 1..word_end + 1 + offset_len + 4).ok_or_else(|| truncated(".idx"))?;
+        entries.push(IndexEntry {
+            word: String::from_utf8_lossy(&data[pos..word_end]).into_owned(),
+            offset: read_be(&fields[..offset_len]),
+            size: read_be(&fields[offset_len..]) as u32,
+        });
+        pos = word_end + 1 + offset_len + 4;
+    }
+    Ok(entries)
+}
+
+/// Content of the field of type `kind` starting at `pos` and where the next one starts.
+/// Lowercase types are NUL-terminated text, uppercase ones binary data with a size
+/// prefix; the last field of a `sametypesequence` article has neither.
+fn read_field(data: &[u8], pos: usize, kind: char, is_last: bool) -> Option<(&[u8], usize)> {
+    let rest = data.get(pos..)?;
+    if is_last {
+        return Some((rest, data.len()));
+    }
+    if kind.is_ascii_lowercase() {
+        let len = rest.iter().position(|&byte| byte == 0).unwrap_or(rest.len());
+        return Some((&rest[..len], pos + len + 1));
+    }
+    let size = read_be(rest.get(..4)?) as usize;
+    Some((rest.get(4..4 + size)?, pos + 4 + size))
+}
+
+/// Text fields of an article as `(type, text)`, binary fields (sounds, pictures) are left out
+pub fn parse_article(data: &[u8], same_type_sequence: Option<&str>) -> Vec<(char, String)> {
+    let mut fields = Vec::new();
+    let mut push = |kind: char, content: &[u8]| {
+        if kind.is_ascii_lowercase() {
+            fields.push((kind, String::from_utf8_lossy(content).into_owned()));
+        }
+    };
+
+    match same_type_sequence {
+        Some(sequence) => {
+            let kinds: Vec<char> = sequence.chars().collect();
+            let mut pos = 0;
+            for (i, &kind) in kinds.iter().enumerate() {
+                let Some((content, next)) = read_field(data, pos, kind, i + 1 == kinds.len()) else {
+                    break;
+                };
+                push(kind, content);
+                pos = next;
+            }
+        }
+        None => {
+            let mut pos = 0;
+            while let Some(&kind) = data.get(pos) {
+                let Some((content, next)) = read_field(data, pos + 1, kind as char, false) else {
+                    break;
+                };
+                push(kind as char, content);
+                pos = next;
+            }
+        }
+    }
+    fields
+}
+
+/// Plain text of HTML, XDXF or Pango markup, block-level tags become line breaks
+pub fn strip_markup(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut tag: Option<String> = None;
+    for c in text.chars() {
+        match (&mut tag, c) {
+            (None, '<') => tag = Some(String::new()),
+            (Some(name), '>') => {
+                let name = name.trim_start_matches('/').split([' ', '/']).next().unwrap_or("").to_lowercase();
+                if matches!(name.as_str(), "br" | "p" | "div" | "li" | "tr" | "def" | "blockquote") {
+                    plain.push('\n');
+                }
+                tag = None;
+            }
+            (Some(name), c) => name.push(c),
+            (None, c) => plain.push(c),
+        }
+    }
+    plain
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Turn an article's fields into an entry. Lines with Chinese in them are glosses,
+/// the rest definitions.
+pub fn entry_from_article(headword: String, fields: Vec<(char, String)>) -> ParsedEntry {
+    let mut entry = ParsedEntry { headword, ..ParsedEntry::default() };
+    for (kind, text) in fields {
+        let text = match kind {
+            't' | 'y' => {
+                entry.pronunciation.get_or_insert_with(|| text.trim().to_string());
+                continue;
+            }
+            'm' | 'l' => text,
+            'g' | 'x' | 'h' | 'k' | 'w' => strip_markup(&text),
+            _ => continue,
+        };
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if has_chinese(line) {
+                entry.translations.push(line.to_string());
+            } else {
+                entry.definitions.push(line.to_string());
+            }
+        }
+    }
+    entry
+}
+
+/// The first of `extensions` that exists next to the `.ifo` file
+fn sibling(ifo_path: &Path, extensions: &[&str]) -> Result<PathBuf> {
+    extensions
+        .iter()
+        .map(|extension| ifo_path.with_extension(extension))
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+            app_error!(
+                "DICTIONARY_FILE_MISSING",
+                "StarDict dictionary is incomplete",
+                format!("No .{} next to {}", extensions[0], ifo_path.display())
+            )
+        })
+}
+
+/// Read a file, decompressing it if it's gzipped (`.gz`) or dictzipped (`.dz`)
+fn read_maybe_compressed(path: &Path) -> Result<Vec<u8>> {
+    let compressed = path.extension().is_some_and(|extension| extension == "gz" || extension == "dz");
+    if !compressed {
+        return Ok(fs::read(path)?);
+    }
+    let mut data = Vec::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// The `bookname` of a `.ifo` file
+pub fn book_name(ifo_path: &Path) -> Option<String> {
+    StardictInfo::parse(&fs::read_to_string(ifo_path).ok()?).ok()?.book_name
+}
+
+/// Read a StarDict dictionary from its `.ifo` file and the `.idx` and `.dict` next to it.
+/// Words with nothing but binary fields are skipped.
+pub fn read_entries(ifo_path: &Path, mut on_entry: impl FnMut(ParsedEntry) -> Result<()>) -> Result<()> {
+    let info = StardictInfo::parse(&fs::read_to_string(ifo_path)?)?;
+    let index = parse_index(&read_maybe_compressed(&sibling(ifo_path, &["idx", "idx.gz"])?)?, info.idx_offset_bits)?;
+    let dict = read_maybe_compressed(&sibling(ifo_path, &["dict", "dict.dz"])?)?;
+
+    for item in index {
+        let start = item.offset as usize;
+        let article = dict.get(start..start + item.size as usize).ok_or_else(|| truncated(".dict"))?;
+        let entry = entry_from_article(item.word, parse_article(article, info.same_type_sequence.as_deref()));
+        if entry.definitions.is_empty() && entry.translations.is_empty() {
+            continue;
+        }
+        on_entry(entry)?;
+    }
+    Ok(())
+}
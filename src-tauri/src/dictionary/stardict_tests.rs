@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::stardict::{entry_from_article, parse_article, parse_index, strip_markup, StardictInfo};
+
+    #[test]
+    fn test_parse_info() {
+        let info = StardictInfo::parse("StarDict's dict ifo file\nversion=3.0.0\nbookname=Test Dict\nsametypesequence=tm\nidxoffsetbits=64\n").unwrap();
+        assert_eq!(info.book_name.as_deref(), Some("Test Dict"));
+        assert_eq!(info.same_type_sequence.as_deref(), Some("tm"));
+        assert_eq!(info.idx_offset_bits, 64);
+
+        assert!(StardictInfo::parse("bookname=x\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_index() {
+        let mut data = b"apple\0".to_vec();
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 12]);
+        data.extend_from_slice(b"b\0");
+        data.extend_from_slice(&[0, 0, 0, 12, 0, 0, 1, 0]);
+
+        let entries = parse_index(&data, 32).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].word.as_str(), entries[0].offset, entries[0].size), ("apple", 0, 12));
+        assert_eq!((entries[1].word.as_str(), entries[1].offset, entries[1].size), ("b", 12, 256));
+
+        assert_eq!(parse_index(b"apple\0\0\0", 32).unwrap_err().code, "DICTIONARY_PARSE_ERROR");
+    }
+
+    #[test]
+    fn test_articles() {
+        let fields = parse_article(b"\xcb\x88\xc3\xa6pl\0a round fruit\n\xe8\x8b\xb9\xe6\x9e\x9c", Some("tm"));
+        let entry = entry_from_article("apple".into(), fields);
+        assert_eq!(entry.pronunciation.as_deref(), Some("ˈæpl"));
+        assert_eq!(entry.definitions, vec!["a round fruit"]);
+        assert_eq!(entry.translations, vec!["苹果"]);
+
+        // Without sametypesequence every field is tagged, binary ones carry a size
+        let mut data = b"Wx".to_vec();
+        data.splice(1..1, [0, 0, 0, 1]);
+        data.extend_from_slice(b"hone<br>two &amp; three\0");
+        let fields = parse_article(&data, None);
+        assert_eq!(fields, vec![('h', "one<br>two &amp; three".to_string())]);
+        assert_eq!(strip_markup(&fields[0].1), "one\ntwo & three");
+    }
+}
@@ -0,0 +1,248 @@
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::app_error;
+use crate::dictionary::{self, headword_key, DictionaryFormat, ParsedEntry};
+use crate::error::{AppError, Result};
+use crate::paths;
+use crate::timestamps;
+
+/// Dictionaries live in their own database so that daily snapshots and backups of
+/// the user's data don't carry hundreds of megabytes of dictionary entries
+const STORE_FILE: &str = "dictionaries.sqlite";
+
+const INSERT_BATCH_SIZE: usize = 1000;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS dictionaries (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    format TEXT NOT NULL,
+    source_path TEXT NOT NULL,
+    entry_count BIGINT NOT NULL DEFAULT 0,
+    imported_at BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS dictionary_entries (
+    id INTEGER PRIMARY KEY NOT NULL,
+    dictionary_id TEXT NOT NULL,
+    headword TEXT NOT NULL,
+    headword_key TEXT NOT NULL,
+    pronunciation TEXT,
+    part_of_speech TEXT,
+    definitions TEXT NOT NULL,
+    translations TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_dictionary_entries_headword_key ON dictionary_entries(headword_key);
+CREATE INDEX IF NOT EXISTS idx_dictionary_entries_dictionary_id ON dictionary_entries(dictionary_id);
+";
+
+diesel::table! {
+    dictionaries (id) {
+        id -> Text,
+        name -> Text,
+        format -> Text,
+        source_path -> Text,
+        entry_count -> BigInt,
+        imported_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    dictionary_entries (id) {
+        id -> Integer,
+        dictionary_id -> Text,
+        headword -> Text,
+        headword_key -> Text,
+        pronunciation -> Nullable<Text>,
+        part_of_speech -> Nullable<Text>,
+        /// Senses separated by newlines
+        definitions -> Text,
+        translations -> Text,
+    }
+}
+
+diesel::joinable!(dictionary_entries -> dictionaries (dictionary_id));
+diesel::allow_tables_to_appear_in_same_query!(dictionaries, dictionary_entries);
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Debug, Clone)]
+#[diesel(table_name = dictionaries)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Dictionary {
+    pub id: String,
+    pub name: String,
+    pub format: String,
+    /// File the dictionary was imported from, importing it again replaces it
+    pub source_path: String,
+    pub entry_count: i64,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub imported_at: i64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = dictionary_entries)]
+struct NewDictionaryEntry<'a> {
+    dictionary_id: &'a str,
+    headword: String,
+    headword_key: String,
+    pronunciation: Option<String>,
+    part_of_speech: Option<String>,
+    definitions: String,
+    translations: String,
+}
+
+impl<'a> NewDictionaryEntry<'a> {
+    fn new(dictionary_id: &'a str, entry: ParsedEntry) -> Self {
+        Self {
+            dictionary_id,
+            headword_key: headword_key(&entry.headword),
+            headword: entry.headword,
+            pronunciation: entry.pronunciation,
+            part_of_speech: entry.part_of_speech,
+            definitions: entry.definitions.join("\n"),
+            translations: entry.translations.join("\n"),
+        }
+    }
+}
+
+/// What one dictionary says about a word
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DictionaryDefinition {
+    /// Name of the dictionary
+    pub dictionary: String,
+    pub headword: String,
+    pub pronunciation: Option<String>,
+    pub part_of_speech: Option<String>,
+    pub definitions: Vec<String>,
+    /// Chinese glosses
+    pub translations: Vec<String>,
+}
+
+/// Dictionary name, headword, pronunciation, part of speech, definitions, translations
+type DefinitionRow = (String, String, Option<String>, Option<String>, String, String);
+
+fn split_lines(text: String) -> Vec<String> {
+    text.lines().filter(|line| !line.is_empty()).map(str::to_string).collect()
+}
+
+fn store_path() -> Result<PathBuf> {
+    Ok(paths::app_subdir("dictionaries")?.join(STORE_FILE))
+}
+
+/// Whether a dictionary has ever been imported, checked without creating the store
+pub fn exists() -> Result<bool> {
+    Ok(paths::app_data_dir()?.join("dictionaries").join(STORE_FILE).exists())
+}
+
+/// Open the dictionary database, creating it on first use
+pub fn open() -> Result<SqliteConnection> {
+    let path = store_path()?;
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())
+        .map_err(|e| AppError::new("DICTIONARY_ERROR", "Failed to open dictionary database").with_details(e.to_string()))?;
+    create_tables(&mut conn)
+        .map_err(|e| AppError::new("DICTIONARY_ERROR", "Failed to create dictionary tables").with_details(e.to_string()))?;
+    Ok(conn)
+}
+
+/// Create the store's tables in `conn` unless they're already there
+pub(crate) fn create_tables(conn: &mut SqliteConnection) -> QueryResult<()> {
+    conn.batch_execute(SCHEMA)
+}
+
+fn delete_dictionary(conn: &mut SqliteConnection, dictionary_id: &str) -> QueryResult<usize> {
+    diesel::delete(dictionary_entries::table.filter(dictionary_entries::dictionary_id.eq(dictionary_id))).execute(conn)?;
+    diesel::delete(dictionaries::table.find(dictionary_id)).execute(conn)
+}
+
+/// Load a dictionary file into the store, replacing an earlier import of the same file
+pub fn import(conn: &mut SqliteConnection, format: DictionaryFormat, path: &Path, name: String) -> Result<Dictionary> {
+    let source_path = path.to_string_lossy().into_owned();
+    let mut dictionary = Dictionary {
+        id: Uuid::new_v4().to_string(),
+        name,
+        format: format.as_str().to_string(),
+        source_path,
+        entry_count: 0,
+        imported_at: timestamps::now_ms(),
+    };
+
+    conn.transaction::<_, AppError, _>(|conn| {
+        let previous: Vec<String> = dictionaries::table
+            .filter(dictionaries::source_path.eq(&dictionary.source_path))
+            .select(dictionaries::id)
+            .load(conn)?;
+        for id in previous {
+            delete_dictionary(conn, &id)?;
+        }
+        diesel::insert_into(dictionaries::table).values(&dictionary).execute(conn)?;
+
+        let mut batch = Vec::with_capacity(INSERT_BATCH_SIZE);
+        let mut entry_count = 0;
+        dictionary::read_entries(format, path, |entry| {
+            batch.push(NewDictionaryEntry::new(&dictionary.id, entry));
+            if batch.len() == INSERT_BATCH_SIZE {
+                entry_count += diesel::insert_into(dictionary_entries::table).values(&batch).execute(conn)?;
+                batch.clear();
+            }
+            Ok(())
+        })?;
+        if !batch.is_empty() {
+            entry_count += diesel::insert_into(dictionary_entries::table).values(&batch).execute(conn)?;
+        }
+        if entry_count == 0 {
+            return Err(app_error!("EMPTY_DICTIONARY", "No entries found in the dictionary file", dictionary.source_path.clone()));
+        }
+
+        dictionary.entry_count = entry_count as i64;
+        diesel::update(dictionaries::table.find(&dictionary.id))
+            .set(dictionaries::entry_count.eq(dictionary.entry_count))
+            .execute(conn)?;
+        Ok(())
+    })?;
+
+    Ok(dictionary)
+}
+
+/// Installed dictionaries in the order their definitions are listed
+pub fn list(conn: &mut SqliteConnection) -> QueryResult<Vec<Dictionary>> {
+    dictionaries::table
+        .order(dictionaries::imported_at.asc())
+        .select(Dictionary::as_select())
+        .load(conn)
+}
+
+pub fn remove(conn: &mut SqliteConnection, dictionary_id: &str) -> QueryResult<usize> {
+    conn.transaction(|conn| delete_dictionary(conn, dictionary_id))
+}
+
+/// Definitions of the headword with lookup key `key` from every installed dictionary
+pub fn lookup(conn: &mut SqliteConnection, key: &str) -> QueryResult<Vec<DictionaryDefinition>> {
+    let rows: Vec<DefinitionRow> = dictionary_entries::table
+        .inner_join(dictionaries::table)
+        .filter(dictionary_entries::headword_key.eq(key))
+        .order((dictionaries::imported_at.asc(), dictionary_entries::id.asc()))
+        .select((
+            dictionaries::name,
+            dictionary_entries::headword,
+            dictionary_entries::pronunciation,
+            dictionary_entries::part_of_speech,
+            dictionary_entries::definitions,
+            dictionary_entries::translations,
+        ))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(dictionary, headword, pronunciation, part_of_speech, definitions, translations)| DictionaryDefinition {
+            dictionary,
+            headword,
+            pronunciation,
+            part_of_speech,
+            definitions: split_lines(definitions),
+            translations: split_lines(translations),
+        })
+        .collect())
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::store::{create_tables, import, list, lookup, remove};
+    use crate::dictionary::DictionaryFormat;
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn store() -> SqliteConnection {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        create_tables(&mut conn).unwrap();
+        conn
+    }
+
+    fn write_ecdict(dir: &std::path::Path, name: &str, rows: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, format!("word,phonetic,definition,translation,pos\n{}", rows)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_import_and_lookup_round_trip() {
+        let dir = std::env::temp_dir().join(format!("loud-mouth-dictionary-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut conn = store();
+
+        let first = write_ecdict(&dir, "first.csv", "Run,rʌn,\"v. move fast\\nn. a race\",v. 跑,v\nbark,bɑːk,n. a dog's cry,n. 吠声,n\n");
+        let imported = import(&mut conn, DictionaryFormat::Ecdict, &first, "First".to_string()).unwrap();
+        assert_eq!((imported.entry_count, imported.format.as_str()), (2, "ecdict"));
+        let second = write_ecdict(&dir, "second.csv", "run,,v. to operate,,\n");
+        import(&mut conn, DictionaryFormat::Ecdict, &second, "Second".to_string()).unwrap();
+
+        let definitions = lookup(&mut conn, "run").unwrap();
+        let found: Vec<(&str, &str)> = definitions
+            .iter()
+            .map(|definition| (definition.dictionary.as_str(), definition.headword.as_str()))
+            .collect();
+        assert_eq!(found, vec![("First", "Run"), ("Second", "run")]);
+        assert_eq!(definitions[0].definitions, vec!["v. move fast", "n. a race"]);
+        assert_eq!(definitions[0].translations, vec!["v. 跑"]);
+        assert_eq!(definitions[0].pronunciation.as_deref(), Some("rʌn"));
+        assert!(lookup(&mut conn, "walk").unwrap().is_empty());
+
+        // Importing the same file again replaces its entries
+        write_ecdict(&dir, "first.csv", "bark,,v. to shout,,\n");
+        let replaced = import(&mut conn, DictionaryFormat::Ecdict, &first, "First".to_string()).unwrap();
+        assert_eq!(list(&mut conn).unwrap().len(), 2);
+        assert_eq!(lookup(&mut conn, "run").unwrap().len(), 1);
+        assert_eq!(lookup(&mut conn, "bark").unwrap()[0].definitions, vec!["v. to shout"]);
+
+        assert_eq!(remove(&mut conn, &replaced.id).unwrap(), 1);
+        assert!(lookup(&mut conn, "bark").unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_dictionary_is_not_kept() {
+        let dir = std::env::temp_dir().join(format!("loud-mouth-dictionary-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut conn = store();
+
+        let empty = write_ecdict(&dir, "empty.csv", "");
+        let error = import(&mut conn, DictionaryFormat::Ecdict, &empty, "Empty".to_string()).unwrap_err();
+        assert_eq!(error.code, "EMPTY_DICTIONARY");
+        assert!(list(&mut conn).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// `form lemma` pairs for irregular forms and words the suffix rules get wrong
+const EXCEPTIONS: &str = include_str!("../data/lemma_exceptions.txt");
+
+fn exceptions() -> &'static HashMap<&'static str, &'static str> {
+    static EXCEPTION_MAP: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    EXCEPTION_MAP.get_or_init(|| {
+        EXCEPTIONS
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                Some((parts.next()?, parts.next()?))
+            })
+            .collect()
+    })
+}
+
+/// Dictionary form of a saved word or phrase, lowercased. Phrases are lemmatized word
+/// by word, so "gave up" becomes "give up".
+pub fn lemmatize(text: &str) -> String {
+    text.split_whitespace()
+        .map(lemmatize_word)
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Dictionary form of a single English word: irregular forms come from the bundled
+/// exception list, regular plurals and `-s`/`-ed`/`-ing` forms are stripped by rule.
+/// Anything that isn't plain ASCII letters is only lowercased.
+pub fn lemmatize_word(word: &str) -> String {
+    let word = word.replace('\u{2019}', "'").to_lowercase();
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+    let word = word.strip_suffix("'s").unwrap_or(word);
+
+    if let Some(lemma) = exceptions().get(word) {
+        return lemma.to_string();
+    }
+    if !word.chars().all(|c| c.is_ascii_lowercase() || c == '\'' || c == '-') {
+        return word.to_string();
+    }
+    strip_inflection(word).unwrap_or_else(|| word.to_string())
+}
+
+fn strip_inflection(word: &str) -> Option<String> {
+    if let Some(stem) = word.strip_suffix("ing") {
+        return verb_stem(stem);
+    }
+    if let Some(stem) = word.strip_suffix("ied") {
+        return Some(ie_stem(stem));
+    }
+    if word.ends_with("eed") {
+        // "need" and "speed" aren't past tenses, the few that are are exceptions
+        return None;
+    }
+    if let Some(stem) = word.strip_suffix("ed") {
+        return verb_stem(stem);
+    }
+    if word.ends_with('s') {
+        return plural_stem(word);
+    }
+    None
+}
+
+/// Undo `-s` and `-es`
+fn plural_stem(word: &str) -> Option<String> {
+    if word.len() < 4 || ["ss", "us", "is", "'s"].iter().any(|suffix| word.ends_with(suffix)) {
+        return None;
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        return Some(ie_stem(stem));
+    }
+    if ["sses", "shes", "ches", "xes", "zzes", "oes"].iter().any(|suffix| word.ends_with(suffix)) {
+        return Some(word[..word.len() - 2].to_string());
+    }
+    Some(word[..word.len() - 1].to_string())
+}
+
+/// Restore the y of "studies" and "studied", or the ie of "dies" and "tied" whose
+/// stem is a single letter
+fn ie_stem(stem: &str) -> String {
+    if stem.chars().count() < 2 {
+        format!("{}ie", stem)
+    } else {
+        format!("{}y", stem)
+    }
+}
+
+/// Turn what's left of a word after `-ing` or `-ed` back into the verb: undo the
+/// doubled final consonant of "stopped" and restore the silent e of "hoped"
+fn verb_stem(stem: &str) -> Option<String> {
+    let chars: Vec<char> = stem.chars().collect();
+    if chars.len() < 2 || vowel_groups(&chars) == 0 {
+        return None;
+    }
+    if has_doubled_consonant(&chars) {
+        return Some(stem[..stem.len() - 1].to_string());
+    }
+    if needs_silent_e(&chars) {
+        return Some(format!("{}e", stem));
+    }
+    Some(stem.to_string())
+}
+
+/// `y` counts as a vowel after a consonant, as in "typ(ing)" but not "play"
+fn is_vowel(chars: &[char], index: usize) -> bool {
+    match chars[index] {
+        'a' | 'e' | 'i' | 'o' | 'u' => true,
+        'y' => index > 0 && !is_vowel(chars, index - 1),
+        _ => false,
+    }
+}
+
+/// Roughly the number of syllables
+fn vowel_groups(chars: &[char]) -> usize {
+    (0..chars.len())
+        .filter(|&i| is_vowel(chars, i) && (i == 0 || !is_vowel(chars, i - 1)))
+        .count()
+}
+
+/// "stopp", "beginn": consonant, single vowel, doubled consonant. Words that end in a
+/// double l, s, f or z ("call", "pass") keep it.
+fn has_doubled_consonant(chars: &[char]) -> bool {
+    let n = chars.len();
+    n >= 4
+        && chars[n - 1] == chars[n - 2]
+        && !is_vowel(chars, n - 1)
+        && !matches!(chars[n - 1], 'l' | 's' | 'f' | 'z')
+        && is_vowel(chars, n - 3)
+        && !is_vowel(chars, n - 4)
+}
+
+fn needs_silent_e(chars: &[char]) -> bool {
+    let n = chars.len();
+    let last = chars[n - 1];
+    let prev = chars[n - 2];
+    let before_single_vowel = n >= 3 && is_vowel(chars, n - 2) && !is_vowel(chars, n - 3);
+    let polysyllabic = vowel_groups(chars) >= 2;
+
+    let restores_e = match last {
+        // No English verb ends in these without an e
+        'v' | 'c' | 'u' => true,
+        's' => prev != 's',
+        'z' => prev != 'z',
+        'g' => matches!(prev, 'd' | 'r' | 'l'),
+        'l' => matches!(prev, 'b' | 'c' | 'd' | 'f' | 'g' | 'k' | 'p' | 't' | 'z'),
+        // "requir", "compar", "secur" but not "pair" or "pour"
+        'r' if prev == 'i' => n >= 3 && !matches!(chars[n - 3], 'a' | 'e' | 'o'),
+        'r' => matches!(prev, 'a' | 'u') && before_single_vowel,
+        // "relat", "comput", "decid", "includ", "examin", "becom", "describ", "provok", "escap"
+        't' | 'd' | 'n' | 'm' | 'b' | 'k' | 'p' if before_single_vowel && polysyllabic => {
+            let vowels: &[char] = match last {
+                't' => &['a', 'u'],
+                'd' => &['i', 'o', 'u'],
+                'n' | 'b' => &['i'],
+                'm' => &['o', 'u'],
+                'k' => &['a', 'o'],
+                _ => &['a'],
+            };
+            vowels.contains(&prev)
+        }
+        _ => false,
+    };
+    if restores_e {
+        return true;
+    }
+
+    // One syllable ending consonant, vowel, consonant: "hop(ing)" is "hope", "hop"
+    // would have doubled its p
+    let ends_in_single_vowel = is_vowel(chars, n - 2) && (n == 2 || !is_vowel(chars, n - 3));
+    !is_vowel(chars, n - 1) && !matches!(last, 'w' | 'x' | 'y') && ends_in_single_vowel && !polysyllabic
+}
+
+/// Fill in `lemma` for items saved before the column existed. Runs after migrations,
+/// SQL can't lemmatize.
+pub fn backfill_lemmas(conn: &mut SqliteConnection) -> QueryResult<usize> {
+    use crate::schema::vocabulary;
+
+    let words: Vec<String> = vocabulary::table
+        .filter(vocabulary::lemma.is_null())
+        .select(vocabulary::word)
+        .distinct()
+        .load(conn)?;
+    if words.is_empty() {
+        return Ok(0);
+    }
+
+    conn.transaction(|conn| {
+        let mut updated = 0;
+        for word in words {
+            updated += diesel::update(
+                vocabulary::table
+                    .filter(vocabulary::lemma.is_null())
+                    .filter(vocabulary::word.eq(&word)),
+            )
+            .set(vocabulary::lemma.eq(lemmatize(&word)))
+            .execute(conn)?;
+        }
+        Ok(updated)
+    })
+}
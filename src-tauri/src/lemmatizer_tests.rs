@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::vocabulary::insert_vocabulary;
+    use crate::lemmatizer::{backfill_lemmas, lemmatize, lemmatize_word};
+    use crate::test_support::{migrated_connection, new_vocabulary};
+    use diesel::prelude::*;
+
+    fn assert_lemmas(cases: &[(&str, &str)]) {
+        for (word, lemma) in cases {
+            assert_eq!(lemmatize_word(word), *lemma, "lemma of {:?}", word);
+        }
+    }
+
+    #[test]
+    fn test_regular_inflections() {
+        assert_lemmas(&[
+            ("running", "run"),
+            ("runs", "run"),
+            ("stopped", "stop"),
+            ("hoped", "hope"),
+            ("hoping", "hope"),
+            ("making", "make"),
+            ("studies", "study"),
+            ("studied", "study"),
+            ("studying", "study"),
+            ("played", "play"),
+            ("watches", "watch"),
+            ("boxes", "box"),
+            ("classes", "class"),
+            ("believed", "believe"),
+            ("caused", "cause"),
+            ("related", "relate"),
+            ("decided", "decide"),
+            ("becoming", "become"),
+            ("settled", "settle"),
+            ("beginning", "begin"),
+            ("listening", "listen"),
+            ("visited", "visit"),
+            ("called", "call"),
+            ("using", "use"),
+            ("lies", "lie"),
+            ("dies", "die"),
+            ("ties", "tie"),
+            ("vies", "vie"),
+            ("tied", "tie"),
+            ("cries", "cry"),
+            ("tries", "try"),
+        ]);
+    }
+
+    #[test]
+    fn test_exceptions_and_words_left_alone() {
+        assert_lemmas(&[
+            ("ran", "run"),
+            ("was", "be"),
+            ("children", "child"),
+            ("knives", "knife"),
+            ("better", "good"),
+            ("news", "news"),
+            ("morning", "morning"),
+            ("thing", "thing"),
+            ("bring", "bring"),
+            ("need", "need"),
+            ("red", "red"),
+            ("bus", "bus"),
+            ("famous", "famous"),
+            ("this", "this"),
+        ]);
+    }
+
+    #[test]
+    fn test_normalizes_case_punctuation_and_phrases() {
+        assert_eq!(lemmatize_word("Running,"), "run");
+        assert_eq!(lemmatize_word("dog\u{2019}s"), "dog");
+        assert_eq!(lemmatize_word("café"), "café");
+        assert_eq!(lemmatize("Gave  up"), "give up");
+        assert_eq!(lemmatize("  "), "");
+    }
+
+    #[test]
+    fn test_backfill_fills_only_missing_lemmas() {
+        use crate::schema::vocabulary;
+
+        let mut conn = migrated_connection(":memory:");
+        for (word, timestamp) in [("Running", 1_000), ("running", 2_000), ("geese", 3_000), ("barked", 4_000)] {
+            insert_vocabulary(&mut conn, &new_vocabulary(word, "video-1", timestamp)).unwrap();
+        }
+        // Saved before the column existed, and one the user corrected by hand
+        diesel::update(vocabulary::table.filter(vocabulary::word.ne("barked")))
+            .set(vocabulary::lemma.eq(None::<String>))
+            .execute(&mut conn)
+            .unwrap();
+        diesel::update(vocabulary::table.filter(vocabulary::word.eq("barked")))
+            .set(vocabulary::lemma.eq("bark (dog)"))
+            .execute(&mut conn)
+            .unwrap();
+
+        assert_eq!(backfill_lemmas(&mut conn).unwrap(), 3);
+        let mut lemmas: Vec<(String, Option<String>)> = vocabulary::table
+            .select((vocabulary::word, vocabulary::lemma))
+            .load(&mut conn)
+            .unwrap();
+        lemmas.sort();
+        let expected = [("Running", "run"), ("barked", "bark (dog)"), ("geese", "goose"), ("running", "run")];
+        assert_eq!(
+            lemmas,
+            expected.map(|(word, lemma)| (word.to_string(), Some(lemma.to_string()))).to_vec()
+        );
+        assert_eq!(backfill_lemmas(&mut conn).unwrap(), 0);
+    }
+}
@@ -1,12 +1,43 @@
+pub mod analytics;
+pub mod anki;
+pub mod audio_clip;
+pub mod backup;
 pub mod commands;
 pub mod database;
+pub mod dictionary;
 pub mod error;
+pub mod lemmatizer;
+pub mod llm;
 pub mod models;
+pub mod paths;
+pub mod scheduler;
 pub mod schema;
+pub mod snapshots;
+pub mod speech;
 pub mod thumbnail;
+pub mod timestamps;
+pub mod vocabulary_io;
 
+#[cfg(test)]
+mod analytics_tests;
+#[cfg(test)]
+mod anki_tests;
+#[cfg(test)]
+mod audio_clip_tests;
+#[cfg(test)]
+mod backup_tests;
 #[cfg(test)]
 mod error_tests;
+#[cfg(test)]
+mod lemmatizer_tests;
+#[cfg(test)]
+mod llm_tests;
+#[cfg(test)]
+mod snapshots_tests;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+mod vocabulary_io_tests;
 
 use error::AppError;
 
@@ -56,6 +87,7 @@ pub fn run() {
         eprintln!("Failed to initialize database pool: {}", e);
         return;
     }
+    snapshots::start_daily_snapshots();
     
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -79,15 +111,72 @@ pub fn run() {
             commands::get_all_vocabulary,
             commands::update_vocabulary_review,
             commands::update_vocabulary_review_with_result,
+            commands::review_vocabulary,
+            commands::get_review_log,
+            commands::get_review_history,
+            commands::undo_last_review,
+            commands::build_review_session,
+            commands::get_active_review_session,
+            commands::abandon_review_session,
             commands::delete_vocabulary,
             commands::get_vocabulary_due_for_review,
             commands::get_vocabulary_due_for_review_by_video,
             commands::get_overdue_vocabulary,
+            commands::get_leeches,
+            commands::suspend_vocabulary,
+            commands::unsuspend_vocabulary,
+            commands::reset_vocabulary,
             commands::get_vocabulary_accuracy_stats,
+            commands::get_review_forecast,
+            commands::get_retention_by_stage,
+            commands::get_accuracy_over_time,
+            commands::get_words_added,
+            commands::get_study_streaks,
+            commands::get_time_to_mastery,
+            commands::start_study_session,
+            commands::heartbeat_study_session,
+            commands::stop_study_session,
+            commands::get_daily_study_time,
+            commands::export_vocabulary_apkg,
+            commands::export_vocabulary,
+            commands::import_vocabulary,
+            commands::create_backup,
+            commands::restore_backup,
+            commands::list_database_snapshots,
+            commands::rollback_database_snapshot,
+            commands::check_database_integrity,
+            commands::merge_vocabulary,
+            commands::get_vocabulary_lemma_groups,
+            commands::get_word_family,
+            commands::add_vocabulary_context,
+            commands::get_vocabulary_contexts,
+            commands::remove_vocabulary_context,
+            commands::get_review_context,
+            commands::import_dictionary,
+            commands::list_dictionaries,
+            commands::remove_dictionary,
+            commands::lookup_word,
+            commands::get_llm_settings,
+            commands::update_llm_settings,
+            commands::lookup_with_llm,
+            commands::clear_llm_cache,
+            commands::get_sentence_audio_clip,
             commands::transcribe_audio,
             commands::check_whisper_model,
             commands::download_whisper_model,
             commands::get_available_whisper_models,
+            commands::list_whisper_models,
+            commands::delete_whisper_model,
+            commands::set_default_whisper_model,
+            commands::get_default_whisper_model,
+            commands::import_whisper_model,
+            commands::get_user_settings,
+            commands::update_user_settings,
+            commands::save_speaking_attempt,
+            commands::list_speaking_attempts,
+            commands::get_speaking_attempt_audio,
+            commands::delete_speaking_attempt,
+            commands::prune_speaking_attempts,
             commands::save_video_progress,
             commands::get_video_progress,
             commands::delete_video_progress
@@ -141,6 +230,7 @@ pub fn run() {
                 Some("webm") => "video/webm",
                 Some("ogg") => "video/ogg",
                 Some("mkv") => "video/x-matroska",
+                Some("m4a") => "audio/mp4",
                 _ => "application/octet-stream",
             };
             
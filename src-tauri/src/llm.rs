@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::app_error;
+use crate::dictionary::headword_key;
+use crate::error::{AppError, Result};
+use crate::paths;
+
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+const CONFIG_FILE: &str = "llm.json";
+
+/// Part of every cache key, bump it when the prompt changes so old answers aren't reused
+const PROMPT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LlmConfig {
+    /// Any OpenAI-compatible API, e.g. `http://localhost:11434/v1` for a local server
+    pub base_url: String,
+    /// Servers without authentication don't need one
+    pub api_key: Option<String>,
+    pub model: String,
+    /// How long a cached lookup is reused, 0 turns caching off
+    pub cache_ttl_days: i64,
+    /// Requests sent to the endpoint per minute, cached lookups don't count
+    pub requests_per_minute: u32,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_key: None,
+            model: DEFAULT_MODEL.to_string(),
+            cache_ttl_days: 30,
+            requests_per_minute: 20,
+        }
+    }
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(paths::app_data_dir()?.join(CONFIG_FILE))
+}
+
+/// Config from `~/.loud-mouth/llm.json`, the defaults until one is saved
+pub fn load_config() -> Result<LlmConfig> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(LlmConfig::default());
+    }
+    serde_json::from_str(&fs::read_to_string(&path)?)
+        .map_err(|e| app_error!("LLM_CONFIG_ERROR", "Invalid LLM config file", format!("{}: {}", path.display(), e)))
+}
+
+/// Write the config. It holds the API key, so on Unix only the user can read it.
+pub fn save_config(config: &LlmConfig) -> Result<()> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| app_error!("LLM_CONFIG_ERROR", "Failed to serialize LLM config", e.to_string()))?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)?.write_all(json.as_bytes())?;
+    // The mode only applies when the file is created, tighten one written before
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// What the model says about a word or phrase
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LlmLookup {
+    /// Chinese meaning of a word, or translation of a phrase
+    pub meaning: String,
+    /// Where a phrase or idiom is used, phrases only
+    #[serde(default)]
+    pub usage: Option<String>,
+    #[serde(default)]
+    pub example_en: Option<String>,
+    #[serde(default)]
+    pub example_zh: Option<String>,
+}
+
+impl LlmLookup {
+    /// The lookup in the markdown layout `dictionary_response` has always used, which
+    /// the frontend reads the translation back out of
+    pub fn to_markdown(&self, is_phrase: bool) -> String {
+        let fields = [
+            (if is_phrase { "译文" } else { "含义" }, Some(&self.meaning)),
+            ("用法", self.usage.as_ref().filter(|_| is_phrase)),
+            ("英文例句", self.example_en.as_ref()),
+            ("中文翻译", self.example_zh.as_ref()),
+        ];
+        fields
+            .into_iter()
+            .filter_map(|(label, value)| Some(format!("**{}：** {}", label, value?.trim())))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+pub fn is_phrase(word: &str) -> bool {
+    word.split_whitespace().nth(1).is_some()
+}
+
+/// Prompt asking for a JSON lookup of `word` as used in `context`
+pub fn build_prompt(word: &str, context: Option<&str>) -> String {
+    let in_context = context
+        .map(|sentence| format!("在句子\"{}\"中", sentence))
+        .unwrap_or_default();
+    if is_phrase(word) {
+        format!(
+            "解释短语\"{}\"{}的含义。只回复一个JSON对象，不要使用Markdown，译文中不要重复短语本身：\n\
+             {{\"meaning\": \"短语的中文含义\", \"usage\": \"此短语/俚语常见的使用场景\", \
+             \"example_en\": \"1个不同的简短英文例句\", \"example_zh\": \"例句的中文翻译\"}}",
+            word, in_context
+        )
+    } else {
+        format!(
+            "解释单词\"{}\"{}的含义。只回复一个JSON对象，不要使用Markdown：\n\
+             {{\"meaning\": \"1-2个中文释义\", \"example_en\": \"1个简短英文例句\", \"example_zh\": \"例句的中文翻译\"}}",
+            word, in_context
+        )
+    }
+}
+
+/// Cache key of a lookup: SHA-256 of the model, word and context, ignoring case and
+/// spacing of the word and spacing of the context
+pub fn cache_key(model: &str, word: &str, context: Option<&str>) -> String {
+    let context = context.map(|sentence| sentence.split_whitespace().collect::<Vec<_>>().join(" ")).unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}\n{}\n{}\n{}", PROMPT_VERSION, model, headword_key(word), context));
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn chat_completions_url(base_url: &str) -> String {
+    format!("{}/chat/completions", base_url.trim_end_matches('/'))
+}
+
+pub fn chat_request_body(model: &str, prompt: &str) -> serde_json::Value {
+    serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "temperature": 0.3,
+        "max_tokens": 300,
+    })
+}
+
+fn response_error(message: &str, details: &str) -> AppError {
+    app_error!("LLM_RESPONSE_ERROR", message, details.chars().take(500).collect::<String>())
+}
+
+/// `choices[0].message.content` of a chat completion
+pub fn completion_content(body: &str) -> Result<String> {
+    let response: serde_json::Value =
+        serde_json::from_str(body).map_err(|_| response_error("Lookup response isn't JSON", body))?;
+    response["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| response_error("Lookup response has no message", body))
+}
+
+/// Parse the model's answer. Models like to wrap JSON in a code fence or add a
+/// sentence around it, everything outside the outermost braces is ignored.
+pub fn parse_lookup(content: &str) -> Result<LlmLookup> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return Err(response_error("Lookup answer isn't JSON", content)),
+    };
+    let lookup: LlmLookup =
+        serde_json::from_str(json).map_err(|e| response_error("Lookup answer has unexpected fields", &format!("{}: {}", e, content)))?;
+    if lookup.meaning.trim().is_empty() {
+        return Err(response_error("Lookup answer has no meaning", content));
+    }
+    let non_empty = |field: Option<String>| field.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    Ok(LlmLookup {
+        meaning: lookup.meaning.trim().to_string(),
+        usage: non_empty(lookup.usage),
+        example_en: non_empty(lookup.example_en),
+        example_zh: non_empty(lookup.example_zh),
+    })
+}
+
+/// Allows a number of requests per sliding window
+pub struct RateLimiter {
+    window: Duration,
+    requests: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub const fn new(window: Duration) -> Self {
+        Self { window, requests: VecDeque::new() }
+    }
+
+    /// Count a request made at `now` if fewer than `limit` were made in the window,
+    /// otherwise how long until the next one is allowed
+    pub fn try_acquire(&mut self, limit: usize, now: Instant) -> std::result::Result<(), Duration> {
+        while self.requests.front().is_some_and(|&made| now.duration_since(made) >= self.window) {
+            self.requests.pop_front();
+        }
+        if let Some(&oldest) = self.requests.front().filter(|_| self.requests.len() >= limit) {
+            return Err(self.window - now.duration_since(oldest));
+        }
+        self.requests.push_back(now);
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::llm::{build_prompt, cache_key, completion_content, parse_lookup, LlmLookup, RateLimiter};
+
+    #[test]
+    fn test_parse_lookup_tolerates_code_fences() {
+        let content = "```json\n{\"meaning\": \" 跑 \", \"example_en\": \"I run daily.\", \"example_zh\": \"\"}\n```";
+        assert_eq!(
+            parse_lookup(content).unwrap(),
+            LlmLookup {
+                meaning: "跑".into(),
+                usage: None,
+                example_en: Some("I run daily.".into()),
+                example_zh: None,
+            }
+        );
+
+        assert_eq!(parse_lookup("Sorry, I can't help").unwrap_err().code, "LLM_RESPONSE_ERROR");
+        assert_eq!(parse_lookup("{\"meaning\": \"\"}").unwrap_err().code, "LLM_RESPONSE_ERROR");
+        assert_eq!(completion_content(r#"{"choices":[{"message":{"content":"hi"}}]}"#).unwrap(), "hi");
+        assert!(completion_content(r#"{"error":"bad key"}"#).is_err());
+    }
+
+    #[test]
+    fn test_markdown_matches_stored_layout() {
+        let lookup = LlmLookup {
+            meaning: "放弃".into(),
+            usage: Some("口语".into()),
+            example_en: Some("Don't give up.".into()),
+            example_zh: Some("别放弃。".into()),
+        };
+        assert_eq!(lookup.to_markdown(true), "**译文：** 放弃\n\n**用法：** 口语\n\n**英文例句：** Don't give up.\n\n**中文翻译：** 别放弃。");
+        assert!(lookup.to_markdown(false).starts_with("**含义：** 放弃\n\n**英文例句：**"));
+    }
+
+    #[test]
+    fn test_cache_key_and_prompt() {
+        assert_eq!(cache_key("m", "Run ", Some("I  run.")), cache_key("m", "run", Some("I run.")));
+        assert_ne!(cache_key("m", "run", Some("I run.")), cache_key("m", "run", None));
+        assert_ne!(cache_key("m", "run", None), cache_key("other", "run", None));
+
+        assert!(build_prompt("give up", Some("Never give up")).contains("短语\"give up\"在句子\"Never give up\"中"));
+        assert!(build_prompt("run", None).starts_with("解释单词\"run\"的含义"));
+    }
+
+    #[test]
+    fn test_rate_limiter_slides() {
+        let mut limiter = RateLimiter::new(Duration::from_secs(60));
+        let start = Instant::now();
+        assert!(limiter.try_acquire(2, start).is_ok());
+        assert!(limiter.try_acquire(2, start + Duration::from_secs(10)).is_ok());
+        assert_eq!(limiter.try_acquire(2, start + Duration::from_secs(20)), Err(Duration::from_secs(40)));
+        assert!(limiter.try_acquire(2, start + Duration::from_secs(60)).is_ok());
+    }
+}
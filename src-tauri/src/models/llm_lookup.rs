@@ -0,0 +1,15 @@
+use diesel::prelude::*;
+
+/// A cached `lookup_with_llm` answer
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = crate::schema::llm_lookup_cache)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct LlmCacheEntry {
+    pub cache_key: String,
+    pub model: String,
+    pub word: String,
+    /// `LlmLookup` as JSON
+    pub response: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
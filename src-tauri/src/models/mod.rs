@@ -1,2 +1,9 @@
 pub mod vocabulary;
-pub mod video_progress;
\ No newline at end of file
+pub mod video_progress;
+pub mod user_settings;
+pub mod speaking_attempt;
+pub mod review_log;
+pub mod review_session;
+pub mod study_session;
+pub mod vocabulary_context;
+pub mod llm_lookup;
\ No newline at end of file
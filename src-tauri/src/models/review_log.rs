@@ -0,0 +1,106 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::vocabulary::Vocabulary;
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::review_log)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReviewLogEntry {
+    pub id: String,
+    pub vocabulary_id: String,
+    pub user_id: String,
+    /// `None` for synthetic entries seeded from items reviewed before the log existed
+    pub grade: Option<String>,
+    pub previous_stage: i32,
+    pub new_stage: i32,
+    pub interval_days: f64,
+    pub days_late: i32,
+    pub was_late: bool,
+    pub response_ms: Option<i32>,
+    pub scheduler: String,
+    pub stability: Option<f64>,
+    pub difficulty: Option<f64>,
+    pub is_synthetic: bool,
+    /// JSON `ReviewSnapshot` of the item before this review
+    #[serde(skip)]
+    pub snapshot: Option<String>,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub reviewed_at: i64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::review_log)]
+pub struct NewReviewLogEntry {
+    pub id: String,
+    pub vocabulary_id: String,
+    pub user_id: String,
+    pub reviewed_at: i64,
+    pub grade: Option<String>,
+    pub previous_stage: i32,
+    pub new_stage: i32,
+    pub interval_days: f64,
+    pub days_late: i32,
+    pub was_late: bool,
+    pub response_ms: Option<i32>,
+    pub scheduler: String,
+    pub stability: Option<f64>,
+    pub difficulty: Option<f64>,
+    pub snapshot: Option<String>,
+}
+
+/// Scheduling fields of a vocabulary row, captured before a review so it can be undone
+#[derive(AsChangeset, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::vocabulary)]
+#[diesel(treat_none_as_null = true)]
+pub struct ReviewSnapshot {
+    pub review_stage: Option<i32>,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub next_review_at: i64,
+    #[serde(with = "crate::timestamps::option_rfc3339_ms")]
+    pub scheduled_review_at: Option<i64>,
+    #[serde(with = "crate::timestamps::option_rfc3339_ms")]
+    pub last_reviewed_at: Option<i64>,
+    pub review_count: Option<i32>,
+    pub consecutive_correct: Option<i32>,
+    pub was_late: Option<bool>,
+    pub ever_overdue: bool,
+    pub correct_count: Option<i32>,
+    pub stability: Option<f64>,
+    pub difficulty: Option<f64>,
+    pub last_grade: Option<String>,
+    pub last_response_ms: Option<i32>,
+    // Missing from snapshots taken before leech tracking, which leave them untouched
+    #[serde(default)]
+    #[diesel(treat_none_as_null = false)]
+    pub lapses: Option<i32>,
+    #[serde(default)]
+    #[diesel(treat_none_as_null = false)]
+    pub is_leech: Option<bool>,
+    #[serde(default)]
+    #[diesel(treat_none_as_null = false)]
+    pub suspended: Option<bool>,
+}
+
+impl From<&Vocabulary> for ReviewSnapshot {
+    fn from(vocab: &Vocabulary) -> Self {
+        Self {
+            review_stage: vocab.review_stage,
+            next_review_at: vocab.next_review_at,
+            scheduled_review_at: vocab.scheduled_review_at,
+            last_reviewed_at: vocab.last_reviewed_at,
+            review_count: vocab.review_count,
+            consecutive_correct: vocab.consecutive_correct,
+            was_late: vocab.was_late,
+            ever_overdue: vocab.ever_overdue,
+            correct_count: vocab.correct_count,
+            stability: vocab.stability,
+            difficulty: vocab.difficulty,
+            last_grade: vocab.last_grade.clone(),
+            last_response_ms: vocab.last_response_ms,
+            lapses: Some(vocab.lapses),
+            is_leech: Some(vocab.is_leech),
+            suspended: Some(vocab.suspended),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::vocabulary::Vocabulary;
+use crate::models::vocabulary_context::VocabularyContext;
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::review_sessions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReviewSession {
+    pub id: String,
+    pub user_id: String,
+    /// Study day the session was built for, `YYYY-MM-DD` in the user's timezone
+    pub study_date: String,
+    pub video_id: Option<String>,
+    /// `active`, `completed` or `abandoned`
+    pub status: String,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub created_at: i64,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub updated_at: i64,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = crate::schema::review_session_items)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReviewSessionItem {
+    pub session_id: String,
+    pub position: i32,
+    pub vocabulary_id: String,
+    pub is_new: bool,
+    pub completed_at: Option<i64>,
+}
+
+/// Options for `build_review_session`, each falling back to the user's settings
+#[derive(Deserialize, Debug, Default)]
+pub struct ReviewSessionOptions {
+    /// At most this many new items, capped by what the daily cap still allows
+    pub new_limit: Option<i32>,
+    /// At most this many reviews, capped by what the daily cap still allows
+    pub review_limit: Option<i32>,
+    /// Only review items from this video
+    pub video_id: Option<String>,
+    /// Abandon the active session and plan a new one instead of resuming it
+    pub rebuild: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReviewSessionEntry {
+    pub position: i32,
+    pub is_new: bool,
+    #[serde(with = "crate::timestamps::option_rfc3339_ms")]
+    pub completed_at: Option<i64>,
+    pub vocabulary: Vocabulary,
+    /// Sighting to show this time, `None` for the sentence the item was saved from
+    pub context: Option<VocabularyContext>,
+}
+
+/// A session with its items in review order
+#[derive(Serialize, Debug)]
+pub struct ReviewSessionDetails {
+    #[serde(flatten)]
+    pub session: ReviewSession,
+    pub items: Vec<ReviewSessionEntry>,
+    pub remaining: usize,
+}
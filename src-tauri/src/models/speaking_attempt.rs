@@ -0,0 +1,47 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::speaking_attempts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SpeakingAttempt {
+    pub id: String,
+    pub user_id: String,
+    pub vocabulary_id: String,
+    pub audio_path: String,
+    pub audio_format: String,
+    pub duration_ms: i32,
+    pub transcription: Option<String>,
+    pub score: Option<f64>,
+    pub created_at: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::speaking_attempts)]
+pub struct NewSpeakingAttempt {
+    pub id: String,
+    pub user_id: String,
+    pub vocabulary_id: String,
+    pub audio_path: String,
+    pub audio_format: String,
+    pub duration_ms: i32,
+    pub transcription: Option<String>,
+    pub score: Option<f64>,
+    pub created_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct SaveSpeakingAttemptRequest {
+    pub user_id: String,
+    pub vocabulary_id: String,
+    /// The same base64 WAV recording that was passed to `transcribe_audio`
+    pub audio_base64: String,
+    pub transcription: Option<String>,
+    pub score: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct SpeakingAttemptAudio {
+    pub mime_type: String,
+    pub audio_base64: String,
+}
@@ -0,0 +1,80 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest gap between heartbeats that still counts as study time. Anything
+/// longer means the app was idle, asleep or closed in between.
+pub const MAX_HEARTBEAT_GAP_MS: i64 = 2 * 60 * 1000;
+
+/// Sessions shorter than this when stopped are discarded
+pub const MIN_SESSION_MS: i64 = 3000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StudyActivity {
+    Watching,
+    Reviewing,
+    Speaking,
+}
+
+impl StudyActivity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Watching => "watching",
+            Self::Reviewing => "reviewing",
+            Self::Speaking => "speaking",
+        }
+    }
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::study_sessions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct StudySession {
+    pub id: String,
+    pub user_id: String,
+    /// `watching`, `reviewing` or `speaking`
+    pub activity: String,
+    pub video_id: Option<String>,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub started_at: i64,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub last_active_at: i64,
+    /// `None` while the session is running
+    #[serde(with = "crate::timestamps::option_rfc3339_ms")]
+    pub ended_at: Option<i64>,
+    pub duration_ms: i64,
+}
+
+impl StudySession {
+    /// A new running session started at `now_ms`
+    pub fn start(user_id: String, activity: StudyActivity, video_id: Option<String>, now_ms: i64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            activity: activity.as_str().to_string(),
+            video_id,
+            started_at: now_ms,
+            last_active_at: now_ms,
+            ended_at: None,
+            duration_ms: 0,
+        }
+    }
+
+    /// Duration after activity up to `now`, gaps longer than a heartbeat interval count
+    /// only up to that interval
+    pub fn duration_until(&self, now: i64) -> i64 {
+        self.duration_ms + (now - self.last_active_at).clamp(0, MAX_HEARTBEAT_GAP_MS)
+    }
+}
+
+/// Study time on one study day, by activity
+#[derive(Debug, Serialize, Default)]
+pub struct DailyStudyTime {
+    /// Study day, `YYYY-MM-DD` in the user's timezone
+    pub date: String,
+    pub total_ms: i64,
+    pub watching_ms: i64,
+    pub reviewing_ms: i64,
+    pub speaking_ms: i64,
+}
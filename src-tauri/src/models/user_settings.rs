@@ -0,0 +1,34 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::scheduler::StudyDay;
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::user_settings)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct UserSettings {
+    pub id: i32,
+    pub user_id: i32,
+    pub theme: Option<String>,
+    pub notifications_enabled: Option<bool>,
+    pub language: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub whisper_model: Option<String>,
+    pub save_speaking_attempts: bool,
+    pub scheduler: String,
+    pub undo_window_seconds: i32,
+    pub timezone: String,
+    pub day_start_hour: i32,
+    pub new_per_day: i32,
+    pub reviews_per_day: i32,
+    pub leech_threshold: i32,
+    pub suspend_leeches: bool,
+}
+
+impl UserSettings {
+    pub fn study_day(&self) -> Result<StudyDay, AppError> {
+        StudyDay::new(&self.timezone, self.day_start_hour)
+    }
+}
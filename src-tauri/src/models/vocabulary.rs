@@ -2,6 +2,10 @@ use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::error::AppError;
+use crate::lemmatizer;
+use crate::timestamps;
+
 #[derive(Queryable, Selectable, Serialize, Deserialize, Debug)]
 #[diesel(table_name = crate::schema::vocabulary)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
@@ -19,12 +23,9 @@ pub struct Vocabulary {
     pub target_zh: String,
     pub dictionary_response: Option<String>,
     pub review_stage: Option<i32>,
-    pub next_review_at: String,
-    pub last_reviewed_at: Option<String>,
     pub is_phrase: Option<bool>,
     pub created_at: Option<String>,
     pub before_2_timestamp: Option<i32>,
-    pub scheduled_review_at: Option<String>,
     pub review_count: Option<i32>,
     pub consecutive_correct: Option<i32>,
     pub was_late: Option<bool>,
@@ -32,6 +33,23 @@ pub struct Vocabulary {
     pub correct_count: Option<i32>,
     pub word_start_index: Option<i32>,
     pub word_end_index: Option<i32>,
+    pub stability: Option<f64>,
+    pub difficulty: Option<f64>,
+    pub last_grade: Option<String>,
+    pub last_response_ms: Option<i32>,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub next_review_at: i64,
+    #[serde(with = "crate::timestamps::option_rfc3339_ms")]
+    pub scheduled_review_at: Option<i64>,
+    #[serde(with = "crate::timestamps::option_rfc3339_ms")]
+    pub last_reviewed_at: Option<i64>,
+    /// Failed reviews after the item had been learned
+    pub lapses: i32,
+    pub is_leech: bool,
+    /// Left out of every due query until unsuspended
+    pub suspended: bool,
+    /// Dictionary form of `word`, shared by its inflections
+    pub lemma: Option<String>,
 }
 
 #[derive(Insertable, Deserialize)]
@@ -51,10 +69,10 @@ pub struct NewVocabulary {
     pub target_zh: String,
     pub dictionary_response: Option<String>,
     pub review_stage: i32,
-    pub next_review_at: String,
-    pub last_reviewed_at: Option<String>,
+    pub next_review_at: i64,
+    pub last_reviewed_at: Option<i64>,
     pub is_phrase: bool,
-    pub scheduled_review_at: Option<String>,
+    pub scheduled_review_at: Option<i64>,
     pub review_count: i32,
     pub consecutive_correct: i32,
     pub was_late: bool,
@@ -62,6 +80,22 @@ pub struct NewVocabulary {
     pub correct_count: i32,
     pub word_start_index: Option<i32>,
     pub word_end_index: Option<i32>,
+    #[serde(default)]
+    pub lapses: i32,
+    #[serde(default)]
+    pub is_leech: bool,
+    #[serde(default)]
+    pub suspended: bool,
+    /// Carried over when importing, new items start without a memory state
+    #[serde(default)]
+    pub stability: Option<f64>,
+    #[serde(default)]
+    pub difficulty: Option<f64>,
+    /// Set when importing, new items use the column default
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub lemma: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -84,11 +118,15 @@ pub struct CreateVocabularyRequest {
 }
 
 impl CreateVocabularyRequest {
-    pub fn into_new_vocabulary(self) -> NewVocabulary {
-        let next_review_at = self.next_review_at.clone();
+    pub fn into_new_vocabulary(self) -> Result<NewVocabulary, AppError> {
+        let next_review_at = timestamps::parse_rfc3339(&self.next_review_at)?;
+        if self.word_start_index.is_some_and(|start| start < 0) {
+            return Err(AppError::new("VALIDATION_ERROR", "word_start_index must not be negative"));
+        }
         let word_len = self.word.len() as i32;
-        let word_end_index = self.word_start_index.map(|start| start + word_len);
-        NewVocabulary {
+        let word_end_index = self.word_start_index.map(|start| start.saturating_add(word_len));
+        let lemma = lemmatizer::lemmatize(&self.word);
+        Ok(NewVocabulary {
             id: Uuid::new_v4().to_string(),
             user_id: self.user_id,
             video_id: self.video_id,
@@ -103,7 +141,7 @@ impl CreateVocabularyRequest {
             target_zh: self.target_zh,
             dictionary_response: self.dictionary_response,
             review_stage: 0,
-            next_review_at: next_review_at.clone(),
+            next_review_at,
             last_reviewed_at: None,
             is_phrase: self.is_phrase.unwrap_or(false),
             scheduled_review_at: Some(next_review_at),
@@ -114,6 +152,26 @@ impl CreateVocabularyRequest {
             correct_count: 0,
             word_start_index: self.word_start_index,
             word_end_index,
-        }
+            lapses: 0,
+            is_leech: false,
+            suspended: false,
+            stability: None,
+            difficulty: None,
+            created_at: None,
+            lemma: Some(lemma),
+        })
     }
-}
\ No newline at end of file
+}
+
+/// Which of a user's items an export or bulk operation covers, every field narrows it down
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct VocabularyFilter {
+    pub video_id: Option<String>,
+    /// Only these items
+    pub ids: Option<Vec<String>>,
+    pub min_stage: Option<i32>,
+    pub max_stage: Option<i32>,
+    pub leeches_only: Option<bool>,
+    /// Suspended items are included unless this is `false`
+    pub include_suspended: Option<bool>,
+}
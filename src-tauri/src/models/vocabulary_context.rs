@@ -0,0 +1,63 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::vocabulary::Vocabulary;
+
+/// Another sentence a vocabulary item was seen in, besides the one it was saved from
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::vocabulary_contexts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct VocabularyContext {
+    pub id: String,
+    pub vocabulary_id: String,
+    pub video_id: String,
+    pub timestamp: i32,
+    pub before_2_timestamp: Option<i32>,
+    pub before_2_en: Option<String>,
+    pub before_2_zh: Option<String>,
+    pub before_1_en: Option<String>,
+    pub before_1_zh: Option<String>,
+    pub target_en: String,
+    pub target_zh: String,
+    pub word_start_index: Option<i32>,
+    pub word_end_index: Option<i32>,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub created_at: i64,
+}
+
+impl VocabularyContext {
+    /// The context an item was saved from, as a sighting of `vocabulary_id`
+    pub fn from_vocabulary(item: &Vocabulary, vocabulary_id: &str, created_at: i64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            vocabulary_id: vocabulary_id.to_string(),
+            video_id: item.video_id.clone(),
+            timestamp: item.timestamp,
+            before_2_timestamp: item.before_2_timestamp,
+            before_2_en: item.before_2_en.clone(),
+            before_2_zh: item.before_2_zh.clone(),
+            before_1_en: item.before_1_en.clone(),
+            before_1_zh: item.before_1_zh.clone(),
+            target_en: item.target_en.clone(),
+            target_zh: item.target_zh.clone(),
+            word_start_index: item.word_start_index,
+            word_end_index: item.word_end_index,
+            created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AddVocabularyContextRequest {
+    pub video_id: String,
+    pub timestamp: i32,
+    pub before_2_timestamp: Option<i32>,
+    pub before_2_en: Option<String>,
+    pub before_2_zh: Option<String>,
+    pub before_1_en: Option<String>,
+    pub before_1_zh: Option<String>,
+    pub target_en: String,
+    pub target_zh: String,
+    pub word_start_index: Option<i32>,
+}
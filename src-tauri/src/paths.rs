@@ -0,0 +1,20 @@
+use crate::app_error;
+use crate::error::AppError;
+use std::fs;
+use std::path::PathBuf;
+
+/// Per-user application directory (`~/.loud-mouth`) for models, recordings and other app data
+pub fn app_data_dir() -> Result<PathBuf, AppError> {
+    let home_dir = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map_err(|_| app_error!("PATH_ERROR", "Failed to get home directory"))?;
+
+    Ok(PathBuf::from(home_dir).join(".loud-mouth"))
+}
+
+/// A subdirectory of the app data directory, created if missing
+pub fn app_subdir(name: &str) -> Result<PathBuf, AppError> {
+    let dir = app_data_dir()?.join(name);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
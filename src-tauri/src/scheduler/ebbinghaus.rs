@@ -0,0 +1,62 @@
+use chrono::Duration;
+
+use super::{MemoryState, Review, ReviewGrade, Schedule, Scheduler};
+
+/// Highest stage, items stay there once mastered
+pub const MAX_STAGE: i32 = 5;
+
+/// Fixed review ladder: 1, 3, 7, 14 and 30 days, resetting when reviewed too late.
+///
+/// `again` and `hard` repeat the current stage, `good` climbs one stage and `easy` two.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ebbinghaus;
+
+impl Ebbinghaus {
+    pub const NAME: &'static str = "ebbinghaus";
+}
+
+/// Review interval in days for a stage
+pub fn interval_days(stage: i32) -> i64 {
+    match stage {
+        0 => 1,   // Day 1
+        1 => 3,   // Day 3
+        2 => 7,   // Day 7
+        3 => 14,  // Day 14
+        4 => 30,  // Day 30
+        _ => 30,  // Mastered (keep at 30 days)
+    }
+}
+
+/// The ladder stage whose interval is closest to (not below) an interval in days
+pub fn stage_for_interval(days: f64) -> i32 {
+    (0..MAX_STAGE)
+        .take_while(|&stage| (interval_days(stage) as f64) < days)
+        .count() as i32
+}
+
+impl Scheduler for Ebbinghaus {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn schedule(&self, state: &MemoryState, review: &Review) -> Schedule {
+        let current_stage = state.review_stage;
+        let new_stage = if review.is_late() {
+            0 // Reset to beginning if late
+        } else {
+            match review.grade {
+                ReviewGrade::Again | ReviewGrade::Hard => current_stage, // Keep current stage
+                ReviewGrade::Good => (current_stage + 1).min(MAX_STAGE),
+                ReviewGrade::Easy => (current_stage + 2).min(MAX_STAGE),
+            }
+        };
+
+        Schedule {
+            state: MemoryState {
+                review_stage: new_stage,
+                ..*state
+            },
+            interval: Duration::days(interval_days(new_stage)),
+        }
+    }
+}
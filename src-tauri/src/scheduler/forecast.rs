@@ -0,0 +1,52 @@
+use super::ebbinghaus::{interval_days, MAX_STAGE};
+use super::fsrs::Fsrs;
+
+/// Number of stages on the review ladder
+pub const STAGE_COUNT: usize = MAX_STAGE as usize + 1;
+
+/// Share of reviews expected to be answered correctly: the user's record so far, or
+/// the retention FSRS schedules for while there are no reviews yet
+pub fn expected_accuracy(total_reviews: i32, total_correct: i32) -> f64 {
+    if total_reviews > 0 {
+        total_correct as f64 / total_reviews as f64
+    } else {
+        Fsrs::default().desired_retention
+    }
+}
+
+/// Expected reviews per day over the forecast window, starting from the items
+/// already scheduled (`scheduled[day][stage]`). Every review is assumed to be
+/// answered on its due day, correctly with probability `accuracy`: a correct
+/// answer climbs one stage, a wrong one repeats the stage. Follow-up reviews
+/// that land inside the window are added to their day.
+///
+/// This follows the fixed ladder; FSRS items are placed on the ladder stage
+/// matching their interval, so it is an estimate of the load, not a replay.
+pub fn simulate_reviews(scheduled: &[[u64; STAGE_COUNT]], accuracy: f64) -> Vec<f64> {
+    let accuracy = accuracy.clamp(0.0, 1.0);
+    let days = scheduled.len();
+    let mut expected: Vec<[f64; STAGE_COUNT]> = scheduled
+        .iter()
+        .map(|stages| stages.map(|count| count as f64))
+        .collect();
+
+    for day in 0..days {
+        for stage in 0..STAGE_COUNT {
+            let reviews = expected[day][stage];
+            if reviews == 0.0 {
+                continue;
+            }
+
+            let next_stage = (stage + 1).min(MAX_STAGE as usize);
+            let outcomes = [(next_stage, reviews * accuracy), (stage, reviews * (1.0 - accuracy))];
+            for (new_stage, count) in outcomes {
+                let due_day = day + interval_days(new_stage as i32) as usize;
+                if due_day < days && count > 0.0 {
+                    expected[due_day][new_stage] += count;
+                }
+            }
+        }
+    }
+
+    expected.iter().map(|stages| stages.iter().sum()).collect()
+}
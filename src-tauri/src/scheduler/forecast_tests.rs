@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::forecast::{expected_accuracy, simulate_reviews, STAGE_COUNT};
+
+    fn window(days: usize, scheduled: &[(usize, usize, u64)]) -> Vec<[u64; STAGE_COUNT]> {
+        let mut counts = vec![[0; STAGE_COUNT]; days];
+        for &(day, stage, count) in scheduled {
+            counts[day][stage] = count;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_perfect_accuracy_follows_the_ladder() {
+        // Stage 0 items answered correctly come back 3 days later at stage 1, then 7 days after that
+        let expected = simulate_reviews(&window(12, &[(0, 0, 10)]), 1.0);
+        assert_eq!(expected[0], 10.0);
+        assert_eq!(expected[3], 10.0);
+        assert_eq!(expected[10], 10.0);
+        assert_eq!(expected.iter().sum::<f64>(), 30.0);
+    }
+
+    #[test]
+    fn test_failed_reviews_repeat_their_stage() {
+        // Half of the stage 1 items fail and return after 3 days, the rest after 7
+        let expected = simulate_reviews(&window(8, &[(0, 1, 10)]), 0.5);
+        assert_eq!(expected[3], 5.0);
+        assert_eq!(expected[6], 2.5);
+        assert_eq!(expected[7], 5.0);
+    }
+
+    #[test]
+    fn test_reviews_beyond_the_window_are_dropped() {
+        let expected = simulate_reviews(&window(2, &[(1, 4, 3)]), 1.0);
+        assert_eq!(expected, vec![0.0, 3.0]);
+    }
+
+    #[test]
+    fn test_expected_accuracy_defaults_to_desired_retention() {
+        assert_eq!(expected_accuracy(0, 0), 0.9);
+        assert_eq!(expected_accuracy(8, 6), 0.75);
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::Duration;
+
+use super::ebbinghaus;
+use super::{MemoryState, Review, ReviewGrade, Schedule, Scheduler};
+
+const DECAY: f64 = -0.5;
+/// Chosen so that retrievability is 90% after `stability` days
+const FACTOR: f64 = 19.0 / 81.0;
+
+/// Default FSRS-5 parameters, fitted by the FSRS authors on a large review dataset
+pub const DEFAULT_WEIGHTS: [f64; 19] = [
+    0.40255, 1.18385, 3.173, 15.69105, 7.1949, 0.5345, 1.4604, 0.0046, 1.54575, 0.1192,
+    1.01925, 1.9395, 0.11, 0.29605, 2.2698, 0.2315, 2.9898, 0.51655, 0.6621,
+];
+
+const GOOD: f64 = 3.0;
+const EASY: f64 = 4.0;
+
+/// Free Spaced Repetition Scheduler: models each item's stability (days until
+/// recall probability drops to 90%) and difficulty, and schedules the next
+/// review when the predicted retrievability reaches the desired retention.
+#[derive(Debug, Clone, Copy)]
+pub struct Fsrs {
+    pub weights: [f64; 19],
+    pub desired_retention: f64,
+    pub maximum_interval_days: f64,
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Self {
+            weights: DEFAULT_WEIGHTS,
+            desired_retention: 0.9,
+            maximum_interval_days: 36500.0,
+        }
+    }
+}
+
+impl Fsrs {
+    pub const NAME: &'static str = "fsrs";
+
+    /// Probability of recalling an item `elapsed_days` after its last review
+    pub fn retrievability(elapsed_days: f64, stability: f64) -> f64 {
+        (1.0 + FACTOR * elapsed_days / stability).powf(DECAY)
+    }
+
+    fn initial_stability(&self, grade: f64) -> f64 {
+        self.weights[grade as usize - 1].max(0.1)
+    }
+
+    fn initial_difficulty(&self, grade: f64) -> f64 {
+        let w = &self.weights;
+        (w[4] - (w[5] * (grade - 1.0)).exp() + 1.0).clamp(1.0, 10.0)
+    }
+
+    fn next_difficulty(&self, difficulty: f64, grade: f64) -> f64 {
+        let w = &self.weights;
+        let delta = -w[6] * (grade - GOOD);
+        let damped = difficulty + delta * (10.0 - difficulty) / 9.0;
+        // Mean reversion towards the difficulty of an item first answered "easy"
+        (w[7] * self.initial_difficulty(EASY) + (1.0 - w[7]) * damped).clamp(1.0, 10.0)
+    }
+
+    fn recall_stability(&self, difficulty: f64, stability: f64, retrievability: f64, grade: ReviewGrade) -> f64 {
+        let w = &self.weights;
+        let modifier = match grade {
+            ReviewGrade::Hard => w[15],
+            ReviewGrade::Easy => w[16],
+            _ => 1.0,
+        };
+        stability
+            * (w[8].exp()
+                * (11.0 - difficulty)
+                * stability.powf(-w[9])
+                * ((w[10] * (1.0 - retrievability)).exp() - 1.0)
+                * modifier
+                + 1.0)
+    }
+
+    fn forget_stability(&self, difficulty: f64, stability: f64, retrievability: f64) -> f64 {
+        let w = &self.weights;
+        let next = w[11]
+            * difficulty.powf(-w[12])
+            * ((stability + 1.0).powf(w[13]) - 1.0)
+            * (w[14] * (1.0 - retrievability)).exp();
+        next.min(stability)
+    }
+
+    /// Reviews on the same day barely test long-term memory and use a separate formula
+    fn short_term_stability(&self, stability: f64, grade: f64) -> f64 {
+        let w = &self.weights;
+        stability * (w[17] * (grade - GOOD + w[18])).exp()
+    }
+
+    /// Interval in whole days at which retrievability falls to the desired retention
+    pub fn interval_days(&self, stability: f64) -> f64 {
+        let days = stability / FACTOR * (self.desired_retention.powf(1.0 / DECAY) - 1.0);
+        days.round().clamp(1.0, self.maximum_interval_days)
+    }
+}
+
+impl Scheduler for Fsrs {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn schedule(&self, state: &MemoryState, review: &Review) -> Schedule {
+        let grade = review.grade.value();
+
+        let prior = match (state.stability, state.difficulty) {
+            (Some(stability), Some(difficulty)) => Some((stability, difficulty)),
+            _ if review.last_reviewed_at.is_none() => None,
+            // Item previously scheduled by the ladder: treat its interval as the stability
+            _ => Some((ebbinghaus::interval_days(state.review_stage) as f64, self.initial_difficulty(GOOD))),
+        };
+
+        let (stability, difficulty) = match prior {
+            None => (self.initial_stability(grade), self.initial_difficulty(grade)),
+            Some((stability, difficulty)) => {
+                let elapsed = review.elapsed_days();
+                let stability = if elapsed < 1.0 {
+                    self.short_term_stability(stability, grade)
+                } else {
+                    let r = Self::retrievability(elapsed, stability);
+                    if review.grade.is_correct() {
+                        self.recall_stability(difficulty, stability, r, review.grade)
+                    } else {
+                        self.forget_stability(difficulty, stability, r)
+                    }
+                };
+                (stability, self.next_difficulty(difficulty, grade))
+            }
+        };
+
+        let days = self.interval_days(stability);
+        Schedule {
+            state: MemoryState {
+                review_stage: ebbinghaus::stage_for_interval(days),
+                stability: Some(stability),
+                difficulty: Some(difficulty),
+            },
+            interval: Duration::seconds((days * 86_400.0) as i64),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+pub mod ebbinghaus;
+pub mod forecast;
+pub mod fsrs;
+pub mod session;
+pub mod study_day;
+
+#[cfg(test)]
+mod forecast_tests;
+#[cfg(test)]
+mod scheduler_tests;
+#[cfg(test)]
+mod session_tests;
+#[cfg(test)]
+mod study_day_tests;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+pub use ebbinghaus::Ebbinghaus;
+pub use fsrs::Fsrs;
+pub use study_day::StudyDay;
+
+/// Scheduler used when a user hasn't picked one
+pub const DEFAULT_SCHEDULER: &str = Ebbinghaus::NAME;
+
+/// Names accepted by the `scheduler` user setting
+pub const SCHEDULER_NAMES: &[&str] = &[Ebbinghaus::NAME, Fsrs::NAME];
+
+/// Reviews more than this many days after the due date count as late
+pub const LATE_THRESHOLD_DAYS: i64 = 3;
+
+/// Answers slower than this are treated as one grade harder (but never as a lapse)
+pub const SLOW_RESPONSE_MS: i32 = 15_000;
+
+/// How well the learner recalled an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewGrade {
+    /// Forgotten
+    Again,
+    /// Recalled with serious difficulty
+    Hard,
+    Good,
+    /// Recalled instantly
+    Easy,
+}
+
+impl ReviewGrade {
+    pub const ALL: [ReviewGrade; 4] = [Self::Again, Self::Hard, Self::Good, Self::Easy];
+
+    pub fn from_correct(is_correct: bool) -> Self {
+        if is_correct {
+            Self::Good
+        } else {
+            Self::Again
+        }
+    }
+
+    pub fn is_correct(self) -> bool {
+        self != Self::Again
+    }
+
+    /// Numeric rating used by FSRS (1 = again ... 4 = easy)
+    pub fn value(self) -> f64 {
+        match self {
+            Self::Again => 1.0,
+            Self::Hard => 2.0,
+            Self::Good => 3.0,
+            Self::Easy => 4.0,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Again => "again",
+            Self::Hard => "hard",
+            Self::Good => "good",
+            Self::Easy => "easy",
+        }
+    }
+
+    /// A hesitant correct answer counts one grade lower
+    pub fn adjusted_for_response(self, response_ms: Option<i32>) -> Self {
+        match (self, response_ms) {
+            (Self::Easy, Some(ms)) if ms > SLOW_RESPONSE_MS => Self::Good,
+            (Self::Good, Some(ms)) if ms > SLOW_RESPONSE_MS => Self::Hard,
+            (grade, _) => grade,
+        }
+    }
+}
+
+/// Per-item memory state stored on the vocabulary row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryState {
+    pub review_stage: i32,
+    /// FSRS stability in days, `None` until the item was scheduled by FSRS
+    pub stability: Option<f64>,
+    /// FSRS difficulty between 1 and 10
+    pub difficulty: Option<f64>,
+}
+
+/// A single answer to a review prompt
+#[derive(Debug, Clone, Copy)]
+pub struct Review {
+    pub grade: ReviewGrade,
+    pub reviewed_at: DateTime<Utc>,
+    /// When the item was due
+    pub due_at: DateTime<Utc>,
+    /// The previous review, `None` for an item reviewed for the first time
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl Review {
+    pub fn days_late(&self) -> i64 {
+        (self.reviewed_at - self.due_at).num_days()
+    }
+
+    pub fn is_late(&self) -> bool {
+        self.days_late() > LATE_THRESHOLD_DAYS
+    }
+
+    /// Days since the previous review, falling back to the time since the item was due
+    pub fn elapsed_days(&self) -> f64 {
+        let since = self.last_reviewed_at.unwrap_or(self.due_at);
+        ((self.reviewed_at - since).num_seconds() as f64 / 86_400.0).max(0.0)
+    }
+}
+
+/// The new memory state of an item and when to show it next
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schedule {
+    pub state: MemoryState,
+    pub interval: Duration,
+}
+
+impl Schedule {
+    pub fn next_review_at(&self, reviewed_at: DateTime<Utc>) -> DateTime<Utc> {
+        reviewed_at + self.interval
+    }
+}
+
+pub trait Scheduler: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn schedule(&self, state: &MemoryState, review: &Review) -> Schedule;
+}
+
+/// Look up a scheduler by its settings name
+pub fn by_name(name: &str) -> Option<Box<dyn Scheduler>> {
+    match name {
+        Ebbinghaus::NAME => Some(Box::new(Ebbinghaus)),
+        Fsrs::NAME => Some(Box::new(Fsrs::default())),
+        _ => None,
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::*;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use proptest::prelude::*;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap()
+    }
+
+    fn new_item() -> MemoryState {
+        MemoryState { review_stage: 0, stability: None, difficulty: None }
+    }
+
+    /// Answer correctly `delays.len()` times, each `delay` days after the item was due,
+    /// and collect the scheduled intervals in days
+    fn correct_intervals(scheduler: &dyn Scheduler, delays: &[i64]) -> Vec<i64> {
+        let mut state = new_item();
+        let mut due_at = start();
+        let mut last_reviewed_at = None;
+        let mut intervals = Vec::new();
+
+        for &delay in delays {
+            let review = Review {
+                grade: ReviewGrade::Good,
+                reviewed_at: due_at + Duration::days(delay),
+                due_at,
+                last_reviewed_at,
+            };
+            let schedule = scheduler.schedule(&state, &review);
+            intervals.push(schedule.interval.num_days());
+            state = schedule.state;
+            last_reviewed_at = Some(review.reviewed_at);
+            due_at = schedule.next_review_at(review.reviewed_at);
+        }
+        intervals
+    }
+
+    #[test]
+    fn test_ebbinghaus_ladder() {
+        assert_eq!(correct_intervals(&Ebbinghaus, &[0; 6]), vec![3, 7, 14, 30, 30, 30]);
+    }
+
+    #[test]
+    fn test_ebbinghaus_resets_when_late() {
+        let state = MemoryState { review_stage: 3, ..new_item() };
+        let review = Review {
+            grade: ReviewGrade::Good,
+            reviewed_at: start() + Duration::days(4),
+            due_at: start(),
+            last_reviewed_at: None,
+        };
+        let schedule = Ebbinghaus.schedule(&state, &review);
+        assert_eq!(schedule.state.review_stage, 0);
+        assert_eq!(schedule.interval, Duration::days(1));
+    }
+
+    #[test]
+    fn test_fsrs_lapse_reduces_stability() {
+        let fsrs = Fsrs::default();
+        let state = MemoryState { review_stage: 3, stability: Some(20.0), difficulty: Some(5.0) };
+        let review = Review {
+            grade: ReviewGrade::Again,
+            reviewed_at: start() + Duration::days(20),
+            due_at: start() + Duration::days(20),
+            last_reviewed_at: Some(start()),
+        };
+        let schedule = fsrs.schedule(&state, &review);
+        assert!(schedule.state.stability.unwrap() < 20.0);
+        assert!(schedule.state.difficulty.unwrap() > 5.0);
+    }
+
+    #[test]
+    fn test_fsrs_adopts_ladder_items() {
+        let state = MemoryState { review_stage: 2, ..new_item() };
+        let review = Review {
+            grade: ReviewGrade::Good,
+            reviewed_at: start() + Duration::days(7),
+            due_at: start() + Duration::days(7),
+            last_reviewed_at: Some(start()),
+        };
+        let schedule = Fsrs::default().schedule(&state, &review);
+        assert!(schedule.interval > Duration::days(7));
+        assert!(schedule.state.review_stage >= 2);
+    }
+
+    #[test]
+    fn test_slow_answers_are_downgraded() {
+        assert_eq!(ReviewGrade::Easy.adjusted_for_response(Some(20_000)), ReviewGrade::Good);
+        assert_eq!(ReviewGrade::Good.adjusted_for_response(Some(20_000)), ReviewGrade::Hard);
+        assert_eq!(ReviewGrade::Hard.adjusted_for_response(Some(60_000)), ReviewGrade::Hard);
+        assert_eq!(ReviewGrade::Good.adjusted_for_response(Some(2_000)), ReviewGrade::Good);
+        assert_eq!(ReviewGrade::Good.adjusted_for_response(None), ReviewGrade::Good);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_better_grades_never_shorten_intervals(
+            stage in 0i32..=5,
+            stability in prop::option::of(0.5f64..400.0),
+            difficulty in 1.0f64..=10.0,
+            elapsed in 0i64..120,
+        ) {
+            let state = MemoryState { review_stage: stage, stability, difficulty: stability.map(|_| difficulty) };
+            let schedulers: [&dyn Scheduler; 2] = [&Ebbinghaus, &Fsrs::default()];
+            for scheduler in schedulers {
+                let intervals: Vec<Duration> = ReviewGrade::ALL
+                    .iter()
+                    .map(|&grade| {
+                        let review = Review {
+                            grade,
+                            reviewed_at: start() + Duration::days(elapsed),
+                            due_at: start() + Duration::days(elapsed),
+                            last_reviewed_at: Some(start()),
+                        };
+                        scheduler.schedule(&state, &review).interval
+                    })
+                    .collect();
+                prop_assert!(intervals.windows(2).all(|w| w[1] >= w[0]), "{}: {:?}", scheduler.name(), intervals);
+            }
+        }
+
+
+        #[test]
+        fn prop_ebbinghaus_intervals_grow_on_correct_answers(delays in prop::collection::vec(0i64..=LATE_THRESHOLD_DAYS, 1..12)) {
+            let intervals = correct_intervals(&Ebbinghaus, &delays);
+            prop_assert!(intervals.windows(2).all(|w| w[1] >= w[0]), "{:?}", intervals);
+        }
+
+        #[test]
+        fn prop_fsrs_intervals_grow_on_correct_answers(delays in prop::collection::vec(0i64..=10, 2..15)) {
+            let intervals = correct_intervals(&Fsrs::default(), &delays);
+            prop_assert!(intervals.windows(2).all(|w| w[1] >= w[0]), "{:?}", intervals);
+            prop_assert!(intervals.last() > intervals.first(), "{:?}", intervals);
+        }
+
+        #[test]
+        fn prop_fsrs_state_stays_in_bounds(answers in prop::collection::vec((prop::sample::select(&ReviewGrade::ALL[..]), 0i64..60), 1..20)) {
+            let fsrs = Fsrs::default();
+            let mut state = new_item();
+            let mut due_at = start();
+            let mut last_reviewed_at = None;
+            for (grade, delay) in answers {
+                let review = Review { grade, reviewed_at: due_at + Duration::days(delay), due_at, last_reviewed_at };
+                let schedule = fsrs.schedule(&state, &review);
+                let difficulty = schedule.state.difficulty.unwrap();
+                prop_assert!((1.0..=10.0).contains(&difficulty));
+                prop_assert!(schedule.state.stability.unwrap() > 0.0);
+                prop_assert!(schedule.interval >= Duration::days(1));
+                state = schedule.state;
+                last_reviewed_at = Some(review.reviewed_at);
+                due_at = schedule.next_review_at(review.reviewed_at);
+            }
+        }
+    }
+}
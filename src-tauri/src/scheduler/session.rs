@@ -0,0 +1,56 @@
+use std::collections::{HashMap, VecDeque};
+
+/// An item that could go into a review session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionCandidate {
+    pub vocabulary_id: String,
+    pub video_id: String,
+    /// Never reviewed before
+    pub is_new: bool,
+    /// Keeps being forgotten
+    pub is_leech: bool,
+    /// How long the item has been due, negative if it only comes due later today
+    pub overdue_ms: i64,
+}
+
+/// Pick and order a session's items. Reviews come first, leeches and the most
+/// overdue ahead of the rest; new items keep the order they were passed in.
+/// The chosen items are then spread across videos so consecutive items rarely
+/// share a sentence context.
+pub fn plan_session(candidates: Vec<SessionCandidate>, new_limit: usize, review_limit: usize) -> Vec<SessionCandidate> {
+    let (mut reviews, new): (Vec<_>, Vec<_>) = candidates.into_iter().partition(|c| !c.is_new);
+
+    reviews.sort_by(|a, b| b.is_leech.cmp(&a.is_leech).then(b.overdue_ms.cmp(&a.overdue_ms)));
+    reviews.truncate(review_limit);
+
+    interleave_by_video(reviews.into_iter().chain(new.into_iter().take(new_limit)).collect())
+}
+
+/// Round-robin over videos, each video's items keeping their relative order and
+/// videos taking turns in the order their first item appears
+pub fn interleave_by_video(items: Vec<SessionCandidate>) -> Vec<SessionCandidate> {
+    let total = items.len();
+    let mut queues: Vec<VecDeque<SessionCandidate>> = Vec::new();
+    let mut queue_by_video: HashMap<String, usize> = HashMap::new();
+
+    for item in items {
+        let index = *queue_by_video.entry(item.video_id.clone()).or_insert_with(|| {
+            queues.push(VecDeque::new());
+            queues.len() - 1
+        });
+        queues[index].push_back(item);
+    }
+
+    let mut ordered = Vec::with_capacity(total);
+    while ordered.len() < total {
+        ordered.extend(queues.iter_mut().filter_map(VecDeque::pop_front));
+    }
+    ordered
+}
+
+/// Which of an item's contexts a review shows: 0 for the sentence it was saved from,
+/// `n` for its n-th other sighting. Moves on with every review so each session
+/// meets the word in a different sentence.
+pub fn rotate_context(review_count: i32, sightings: usize) -> usize {
+    review_count.max(0) as usize % (sightings + 1)
+}
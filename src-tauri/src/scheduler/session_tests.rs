@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::session::{interleave_by_video, plan_session, rotate_context, SessionCandidate};
+
+    fn review(id: &str, video: &str, overdue_ms: i64) -> SessionCandidate {
+        SessionCandidate {
+            vocabulary_id: id.to_string(),
+            video_id: video.to_string(),
+            is_new: false,
+            is_leech: false,
+            overdue_ms,
+        }
+    }
+
+    fn new_item(id: &str, video: &str) -> SessionCandidate {
+        SessionCandidate { is_new: true, ..review(id, video, 0) }
+    }
+
+    fn ids(items: &[SessionCandidate]) -> Vec<&str> {
+        items.iter().map(|c| c.vocabulary_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_limits_apply_separately_to_new_and_review_items() {
+        let candidates = vec![
+            review("r1", "a", 10),
+            review("r2", "a", 20),
+            review("r3", "a", 30),
+            new_item("n1", "a"),
+            new_item("n2", "a"),
+        ];
+        let planned = plan_session(candidates, 1, 2);
+        assert_eq!(ids(&planned), vec!["r3", "r2", "n1"]);
+    }
+
+    #[test]
+    fn test_leeches_and_overdue_items_survive_the_review_cap() {
+        let mut leech = review("leech", "a", -1000);
+        leech.is_leech = true;
+        let candidates = vec![review("due-today", "a", 0), review("overdue", "a", 86_400_000), leech];
+        let planned = plan_session(candidates, 0, 2);
+        assert_eq!(ids(&planned), vec!["leech", "overdue"]);
+    }
+
+    #[test]
+    fn test_interleave_alternates_videos_in_priority_order() {
+        let items = vec![
+            review("a1", "a", 0),
+            review("a2", "a", 0),
+            review("a3", "a", 0),
+            review("b1", "b", 0),
+            review("c1", "c", 0),
+            review("b2", "b", 0),
+        ];
+        assert_eq!(ids(&interleave_by_video(items)), vec!["a1", "b1", "c1", "a2", "b2", "a3"]);
+    }
+
+    #[test]
+    fn test_rotate_context() {
+        assert_eq!(rotate_context(0, 0), 0);
+        assert_eq!(rotate_context(5, 0), 0);
+        let shown: Vec<usize> = (0..5).map(|reviews| rotate_context(reviews, 2)).collect();
+        assert_eq!(shown, vec![0, 1, 2, 0, 1]);
+    }
+}
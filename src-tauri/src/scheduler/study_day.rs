@@ -0,0 +1,52 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::app_error;
+use crate::error::AppError;
+
+/// The learner's day: a local calendar day in their timezone that starts at
+/// `day_start_hour` instead of midnight, so late-night reviews count for the day before
+#[derive(Debug, Clone, Copy)]
+pub struct StudyDay {
+    timezone: Tz,
+    day_start_hour: u32,
+}
+
+impl StudyDay {
+    pub fn new(timezone: &str, day_start_hour: i32) -> Result<Self, AppError> {
+        let timezone = parse_timezone(timezone)?;
+        if !(0..24).contains(&day_start_hour) {
+            return Err(app_error!("VALIDATION_ERROR", "Day start hour must be between 0 and 23", day_start_hour.to_string()));
+        }
+        Ok(Self { timezone, day_start_hour: day_start_hour as u32 })
+    }
+
+    /// The study day `instant` falls in, named by the local date it starts on
+    pub fn date_of(&self, instant: DateTime<Utc>) -> NaiveDate {
+        let local = instant.with_timezone(&self.timezone).naive_local();
+        (local - Duration::hours(self.day_start_hour as i64)).date()
+    }
+
+    /// When the study day for `date` starts. If the start hour doesn't exist locally
+    /// (skipped by a DST change) the day starts at the first valid time after it.
+    pub fn start_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let mut local = date.and_time(NaiveTime::from_hms_opt(self.day_start_hour, 0, 0).unwrap());
+        loop {
+            if let Some(start) = self.timezone.from_local_datetime(&local).earliest() {
+                return start.with_timezone(&Utc);
+            }
+            local += Duration::minutes(15);
+        }
+    }
+
+    /// End (exclusive) of the study day containing `now`, the cutoff for "due today"
+    pub fn end_of_day(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.start_of(self.date_of(now) + Duration::days(1))
+    }
+}
+
+pub fn parse_timezone(timezone: &str) -> Result<Tz, AppError> {
+    timezone
+        .parse::<Tz>()
+        .map_err(|_| app_error!("INVALID_TIMEZONE", "Unknown IANA timezone", timezone.to_string()))
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::StudyDay;
+    use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_day_rolls_over_at_local_midnight() {
+        let day = StudyDay::new("Asia/Shanghai", 0).unwrap();
+        // 07:59 UTC is 15:59 in Shanghai, the next local day starts at 16:00 UTC
+        assert_eq!(day.end_of_day(utc(2025, 6, 1, 7, 59)), utc(2025, 6, 1, 16, 0));
+        assert_eq!(day.date_of(utc(2025, 6, 1, 16, 0)), date(2025, 6, 2));
+    }
+
+    #[test]
+    fn test_day_start_hour_keeps_late_night_on_previous_day() {
+        let day = StudyDay::new("Asia/Shanghai", 4).unwrap();
+        // 03:00 local on June 2nd still belongs to June 1st
+        assert_eq!(day.date_of(utc(2025, 6, 1, 19, 0)), date(2025, 6, 1));
+        assert_eq!(day.end_of_day(utc(2025, 6, 1, 19, 0)), utc(2025, 6, 1, 20, 0));
+    }
+
+    #[test]
+    fn test_spring_forward_day_is_shorter() {
+        // Clocks jump from 02:00 to 03:00 on 2025-03-09 in New York
+        let day = StudyDay::new("America/New_York", 0).unwrap();
+        let length = day.start_of(date(2025, 3, 10)) - day.start_of(date(2025, 3, 9));
+        assert_eq!(length, Duration::hours(23));
+    }
+
+    #[test]
+    fn test_fall_back_day_is_longer() {
+        // Clocks go back from 02:00 to 01:00 on 2025-11-02 in New York
+        let day = StudyDay::new("America/New_York", 0).unwrap();
+        let length = day.start_of(date(2025, 11, 3)) - day.start_of(date(2025, 11, 2));
+        assert_eq!(length, Duration::hours(25));
+    }
+
+    #[test]
+    fn test_skipped_start_hour_uses_next_valid_time() {
+        // 02:00 doesn't exist on the spring-forward day, the day starts at 03:00 EDT
+        let day = StudyDay::new("America/New_York", 2).unwrap();
+        assert_eq!(day.start_of(date(2025, 3, 9)), utc(2025, 3, 9, 7, 0));
+        // An instant just before the jump still belongs to the previous study day
+        assert_eq!(day.date_of(utc(2025, 3, 9, 6, 59)), date(2025, 3, 8));
+    }
+
+    #[test]
+    fn test_ambiguous_start_hour_uses_first_occurrence() {
+        // 01:00 happens twice on the fall-back day, the day starts at the first (EDT) one
+        let day = StudyDay::new("America/New_York", 1).unwrap();
+        assert_eq!(day.start_of(date(2025, 11, 2)), utc(2025, 11, 2, 5, 0));
+    }
+
+    #[test]
+    fn test_invalid_settings_are_rejected() {
+        assert_eq!(StudyDay::new("Mars/Olympus", 0).unwrap_err().code, "INVALID_TIMEZONE");
+        assert_eq!(StudyDay::new("UTC", 24).unwrap_err().code, "VALIDATION_ERROR");
+    }
+}
@@ -13,6 +13,87 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    llm_lookup_cache (cache_key) {
+        cache_key -> Text,
+        model -> Text,
+        word -> Text,
+        response -> Text,
+        created_at -> BigInt,
+        expires_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    review_log (id) {
+        id -> Text,
+        vocabulary_id -> Text,
+        user_id -> Text,
+        grade -> Nullable<Text>,
+        previous_stage -> Integer,
+        new_stage -> Integer,
+        interval_days -> Double,
+        days_late -> Integer,
+        was_late -> Bool,
+        response_ms -> Nullable<Integer>,
+        scheduler -> Text,
+        stability -> Nullable<Double>,
+        difficulty -> Nullable<Double>,
+        is_synthetic -> Bool,
+        snapshot -> Nullable<Text>,
+        reviewed_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    review_session_items (session_id, position) {
+        session_id -> Text,
+        position -> Integer,
+        vocabulary_id -> Text,
+        is_new -> Bool,
+        completed_at -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    review_sessions (id) {
+        id -> Text,
+        user_id -> Text,
+        study_date -> Text,
+        video_id -> Nullable<Text>,
+        status -> Text,
+        created_at -> BigInt,
+        updated_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    speaking_attempts (id) {
+        id -> Text,
+        user_id -> Text,
+        vocabulary_id -> Text,
+        audio_path -> Text,
+        audio_format -> Text,
+        duration_ms -> Integer,
+        transcription -> Nullable<Text>,
+        score -> Nullable<Double>,
+        created_at -> Text,
+    }
+}
+
+diesel::table! {
+    study_sessions (id) {
+        id -> Text,
+        user_id -> Text,
+        activity -> Text,
+        video_id -> Nullable<Text>,
+        started_at -> BigInt,
+        last_active_at -> BigInt,
+        ended_at -> Nullable<BigInt>,
+        duration_ms -> BigInt,
+    }
+}
+
 diesel::table! {
     subtitles (id) {
         id -> Text,
@@ -45,6 +126,16 @@ diesel::table! {
         language -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        whisper_model -> Nullable<Text>,
+        save_speaking_attempts -> Bool,
+        scheduler -> Text,
+        undo_window_seconds -> Integer,
+        timezone -> Text,
+        day_start_hour -> Integer,
+        new_per_day -> Integer,
+        reviews_per_day -> Integer,
+        leech_threshold -> Integer,
+        suspend_leeches -> Bool,
     }
 }
 
@@ -102,12 +193,9 @@ diesel::table! {
         target_zh -> Text,
         dictionary_response -> Nullable<Text>,
         review_stage -> Nullable<Integer>,
-        next_review_at -> Text,
-        last_reviewed_at -> Nullable<Text>,
         is_phrase -> Nullable<Bool>,
         created_at -> Nullable<Text>,
         before_2_timestamp -> Nullable<Integer>,
-        scheduled_review_at -> Nullable<Text>,
         review_count -> Nullable<Integer>,
         consecutive_correct -> Nullable<Integer>,
         was_late -> Nullable<Bool>,
@@ -115,10 +203,44 @@ diesel::table! {
         correct_count -> Nullable<Integer>,
         word_start_index -> Nullable<Integer>,
         word_end_index -> Nullable<Integer>,
+        stability -> Nullable<Double>,
+        difficulty -> Nullable<Double>,
+        last_grade -> Nullable<Text>,
+        last_response_ms -> Nullable<Integer>,
+        next_review_at -> BigInt,
+        scheduled_review_at -> Nullable<BigInt>,
+        last_reviewed_at -> Nullable<BigInt>,
+        lapses -> Integer,
+        is_leech -> Bool,
+        suspended -> Bool,
+        lemma -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    vocabulary_contexts (id) {
+        id -> Text,
+        vocabulary_id -> Text,
+        video_id -> Text,
+        timestamp -> Integer,
+        before_2_timestamp -> Nullable<Integer>,
+        before_2_en -> Nullable<Text>,
+        before_2_zh -> Nullable<Text>,
+        before_1_en -> Nullable<Text>,
+        before_1_zh -> Nullable<Text>,
+        target_en -> Text,
+        target_zh -> Text,
+        word_start_index -> Nullable<Integer>,
+        word_end_index -> Nullable<Integer>,
+        created_at -> BigInt,
     }
 }
 
 diesel::joinable!(file_integrity_checks -> videos (video_id));
+diesel::joinable!(review_log -> vocabulary (vocabulary_id));
+diesel::joinable!(review_session_items -> review_sessions (session_id));
+diesel::joinable!(review_session_items -> vocabulary (vocabulary_id));
+diesel::joinable!(speaking_attempts -> vocabulary (vocabulary_id));
 diesel::joinable!(subtitles -> videos (video_id));
 diesel::joinable!(user_profiles -> users (user_id));
 diesel::joinable!(user_settings -> users (user_id));
@@ -126,9 +248,16 @@ diesel::joinable!(video_progress -> users (user_id));
 diesel::joinable!(video_progress -> videos (video_id));
 diesel::joinable!(videos -> users (user_id));
 diesel::joinable!(vocabulary -> videos (video_id));
+diesel::joinable!(vocabulary_contexts -> vocabulary (vocabulary_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     file_integrity_checks,
+    llm_lookup_cache,
+    review_log,
+    review_session_items,
+    review_sessions,
+    speaking_attempts,
+    study_sessions,
     subtitles,
     user_profiles,
     user_settings,
@@ -136,4 +265,5 @@ diesel::allow_tables_to_appear_in_same_query!(
     video_progress,
     videos,
     vocabulary,
+    vocabulary_contexts,
 );
@@ -0,0 +1,220 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use diesel::sqlite::SqliteConnection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::app_error;
+use crate::backup;
+use crate::database;
+use crate::error::{AppError, Result};
+use crate::paths;
+use crate::timestamps;
+
+/// Snapshots kept of each kind, older ones are deleted when a new one is taken
+pub const SNAPSHOTS_TO_KEEP: usize = 7;
+
+/// How often the running app checks whether today's snapshot is still missing
+const DAILY_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub(crate) const FILE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+/// Snapshots named before milliseconds were added to the file name
+const LEGACY_FILE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SnapshotKind {
+    Daily,
+    /// Taken before pending migrations run
+    PreMigration,
+    /// The database as it was before a rollback replaced it
+    PreRollback,
+}
+
+impl SnapshotKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::PreMigration => "pre-migration",
+            Self::PreRollback => "pre-rollback",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Daily, Self::PreMigration, Self::PreRollback]
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatabaseSnapshot {
+    /// File name, identifies the snapshot when rolling back
+    pub name: String,
+    pub kind: SnapshotKind,
+    #[serde(with = "crate::timestamps::rfc3339_ms")]
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// What `PRAGMA integrity_check` found, empty when the database is fine
+    pub problems: Vec<String>,
+}
+
+/// `daily-20261018T093000.250Z.sqlite`, millisecond precision so snapshots taken in
+/// the same second don't overwrite each other
+pub fn snapshot_file_name(kind: SnapshotKind, created_at: i64) -> String {
+    format!("{}-{}.sqlite", kind.as_str(), timestamps::to_datetime(created_at).format(FILE_TIME_FORMAT))
+}
+
+/// Kind and creation time of a snapshot file, `None` for files that aren't snapshots
+pub fn parse_snapshot_file_name(name: &str) -> Option<(SnapshotKind, i64)> {
+    let stem = name.strip_suffix(".sqlite")?;
+    let (kind, time) = stem.rsplit_once('-')?;
+    let created_at = NaiveDateTime::parse_from_str(time, FILE_TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(time, LEGACY_FILE_TIME_FORMAT))
+        .ok()?
+        .and_utc();
+    Some((SnapshotKind::parse(kind)?, created_at.timestamp_millis()))
+}
+
+/// Snapshots beyond the newest `keep` of each kind
+pub fn snapshots_to_prune(snapshots: &[DatabaseSnapshot], keep: usize) -> Vec<&DatabaseSnapshot> {
+    let mut sorted: Vec<&DatabaseSnapshot> = snapshots.iter().collect();
+    sorted.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+    [SnapshotKind::Daily, SnapshotKind::PreMigration, SnapshotKind::PreRollback]
+        .into_iter()
+        .flat_map(|kind| {
+            sorted
+                .iter()
+                .copied()
+                .filter(move |snapshot| snapshot.kind == kind)
+                .skip(keep)
+        })
+        .collect()
+}
+
+fn snapshots_dir() -> Result<PathBuf> {
+    paths::app_subdir("snapshots")
+}
+
+/// Snapshots on disk, newest first
+pub fn list_snapshots() -> Result<Vec<DatabaseSnapshot>> {
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(snapshots_dir()?)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some((kind, created_at)) = parse_snapshot_file_name(&name) {
+            snapshots.push(DatabaseSnapshot {
+                name,
+                kind,
+                created_at,
+                size_bytes: entry.metadata()?.len(),
+            });
+        }
+    }
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.created_at));
+    Ok(snapshots)
+}
+
+/// Copy the live database into a new snapshot and prune old ones of the same kind
+pub fn take_snapshot(kind: SnapshotKind) -> Result<DatabaseSnapshot> {
+    let created_at = timestamps::now_ms();
+    let name = snapshot_file_name(kind, created_at);
+    let path = snapshots_dir()?.join(&name);
+    backup::backup_database(&database::database_path(), &path)?;
+
+    for old in snapshots_to_prune(&list_snapshots()?, SNAPSHOTS_TO_KEEP) {
+        if let Err(e) = fs::remove_file(snapshots_dir()?.join(&old.name)) {
+            eprintln!("Failed to delete old snapshot {}: {}", old.name, e);
+        }
+    }
+
+    Ok(DatabaseSnapshot {
+        name,
+        kind,
+        created_at,
+        size_bytes: fs::metadata(&path)?.len(),
+    })
+}
+
+/// Take today's daily snapshot (UTC) unless there already is one
+pub fn take_daily_snapshot_if_due() -> Result<Option<DatabaseSnapshot>> {
+    let today = Utc::now().date_naive();
+    let taken_today = list_snapshots()?
+        .iter()
+        .any(|snapshot| snapshot.kind == SnapshotKind::Daily && timestamps::to_datetime(snapshot.created_at).date_naive() == today);
+    if taken_today {
+        return Ok(None);
+    }
+    take_snapshot(SnapshotKind::Daily).map(Some)
+}
+
+/// Take the daily snapshot now and keep checking for as long as the app runs
+pub fn start_daily_snapshots() {
+    std::thread::spawn(|| loop {
+        if let Err(e) = take_daily_snapshot_if_due() {
+            eprintln!("Failed to take daily database snapshot: {}", e);
+        }
+        std::thread::sleep(DAILY_CHECK_INTERVAL);
+    });
+}
+
+#[derive(QueryableByName)]
+struct IntegrityRow {
+    #[diesel(sql_type = Text)]
+    integrity_check: String,
+}
+
+/// Run `PRAGMA integrity_check`, returns the problems found
+pub fn check_integrity(conn: &mut SqliteConnection) -> QueryResult<Vec<String>> {
+    let rows: Vec<IntegrityRow> = diesel::sql_query("PRAGMA integrity_check").load(conn)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| row.integrity_check)
+        .filter(|row| row != "ok")
+        .collect())
+}
+
+/// Copy a snapshot to `staged` and check its integrity, nothing is left at `staged`
+/// when it fails
+pub(crate) fn stage_snapshot(snapshot_path: &Path, staged: &Path) -> Result<()> {
+    let checked = backup::backup_database(snapshot_path, staged).and_then(|_| {
+        let mut conn = SqliteConnection::establish(&staged.to_string_lossy())
+            .map_err(|e| AppError::new("SNAPSHOT_ERROR", "Failed to open snapshot").with_details(e.to_string()))?;
+        let problems = check_integrity(&mut conn)?;
+        if !problems.is_empty() {
+            return Err(app_error!("SNAPSHOT_CORRUPT", "Snapshot failed the integrity check", problems.join("; ")));
+        }
+        Ok(())
+    });
+    if checked.is_err() {
+        let _ = fs::remove_file(staged);
+    }
+    checked
+}
+
+/// Replace the database with a snapshot. The current database is snapshotted first
+/// so a rollback can itself be undone.
+pub fn rollback_to_snapshot(name: &str) -> Result<DatabaseSnapshot> {
+    let snapshot = list_snapshots()?
+        .into_iter()
+        .find(|snapshot| snapshot.name == name)
+        .ok_or_else(|| app_error!("SNAPSHOT_NOT_FOUND", "Snapshot not found", name.to_string()))?;
+
+    let staged = database::database_path().with_extension("sqlite.restore");
+    stage_snapshot(&snapshots_dir()?.join(&snapshot.name), &staged)?;
+
+    // A corrupt database may not copy cleanly, that mustn't block getting out of it
+    if let Err(e) = take_snapshot(SnapshotKind::PreRollback) {
+        eprintln!("Failed to snapshot database before rollback: {}", e);
+    }
+    database::replace_database(&staged)?;
+    Ok(snapshot)
+}
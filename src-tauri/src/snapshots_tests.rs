@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use crate::backup;
+    use crate::commands::vocabulary::insert_vocabulary;
+    use crate::snapshots::*;
+    use crate::test_support::{migrated_connection, new_vocabulary};
+    use diesel::prelude::*;
+    use diesel::sqlite::SqliteConnection;
+    use std::fs;
+    use std::path::Path;
+    use uuid::Uuid;
+
+    fn snapshot(kind: SnapshotKind, created_at: i64) -> DatabaseSnapshot {
+        DatabaseSnapshot {
+            name: snapshot_file_name(kind, created_at),
+            kind,
+            created_at,
+            size_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_file_name_round_trip() {
+        // 2026-10-18T09:30:00.250Z
+        let created_at = 1_792_315_800_250;
+        let name = snapshot_file_name(SnapshotKind::PreMigration, created_at);
+        assert_eq!(name, "pre-migration-20261018T093000.250Z.sqlite");
+        assert_eq!(parse_snapshot_file_name(&name), Some((SnapshotKind::PreMigration, created_at)));
+        assert_ne!(snapshot_file_name(SnapshotKind::PreMigration, created_at + 1), name);
+        assert_eq!(parse_snapshot_file_name("weekly-20261018T093000.250Z.sqlite"), None);
+        assert_eq!(parse_snapshot_file_name("daily-20261018T093000.250Z.sqlite-wal"), None);
+    }
+
+    #[test]
+    fn test_parses_names_without_milliseconds() {
+        assert_eq!(
+            parse_snapshot_file_name("daily-20261018T093000Z.sqlite"),
+            Some((SnapshotKind::Daily, 1_792_315_800_000))
+        );
+    }
+
+    #[test]
+    fn test_snapshots_to_prune_keeps_newest_of_each_kind() {
+        let day = 86_400_000;
+        let mut snapshots: Vec<DatabaseSnapshot> = (0..5).map(|i| snapshot(SnapshotKind::Daily, i * day)).collect();
+        snapshots.push(snapshot(SnapshotKind::PreMigration, 0));
+
+        let pruned: Vec<i64> = snapshots_to_prune(&snapshots, 3).iter().map(|s| s.created_at).collect();
+        assert_eq!(pruned, vec![day, 0]);
+    }
+
+    fn vocabulary_count(path: &Path) -> i64 {
+        use crate::schema::vocabulary;
+
+        let mut conn = SqliteConnection::establish(&path.to_string_lossy()).unwrap();
+        vocabulary::table.count().get_result(&mut conn).unwrap()
+    }
+
+    #[test]
+    fn test_staged_snapshot_brings_back_its_data() {
+        use crate::schema::vocabulary;
+
+        let dir = std::env::temp_dir().join(format!("loud-mouth-snapshot-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.sqlite");
+        let snapshot_path = dir.join(snapshot_file_name(SnapshotKind::Daily, 1_792_315_800_250));
+        let staged = dir.join("database.sqlite.restore");
+        {
+            let mut conn = migrated_connection(&db_path.to_string_lossy());
+            insert_vocabulary(&mut conn, &new_vocabulary("bark", "video-1", 1_000)).unwrap();
+        }
+        backup::backup_database(&db_path, &snapshot_path).unwrap();
+        {
+            let mut conn = SqliteConnection::establish(&db_path.to_string_lossy()).unwrap();
+            diesel::delete(vocabulary::table).execute(&mut conn).unwrap();
+        }
+
+        stage_snapshot(&snapshot_path, &staged).unwrap();
+        assert_eq!(vocabulary_count(&staged), 1);
+        assert_eq!(vocabulary_count(&db_path), 0);
+
+        let damaged = dir.join("damaged.sqlite");
+        fs::write(&damaged, vec![0x5a; 8192]).unwrap();
+        assert!(stage_snapshot(&damaged, &staged).is_err());
+        assert!(!staged.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
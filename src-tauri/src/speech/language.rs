@@ -0,0 +1,118 @@
+/// Value accepted in place of a language code to let Whisper detect the language
+pub const AUTO_DETECT: &str = "auto";
+
+/// Normalize a user or settings supplied language into a Whisper language code.
+///
+/// Accepts ISO codes with region subtags (`zh-CN`, `en_US`) and common English
+/// names. Returns `None` when the language should be auto-detected.
+pub fn normalize_language(input: &str) -> Option<String> {
+    let lower = input.trim().to_lowercase();
+    if lower.is_empty() || lower == AUTO_DETECT {
+        return None;
+    }
+
+    let code = match lower.as_str() {
+        "english" => "en",
+        "chinese" | "mandarin" | "cmn" => "zh",
+        "japanese" => "ja",
+        "korean" => "ko",
+        "spanish" => "es",
+        "french" => "fr",
+        "german" => "de",
+        "italian" => "it",
+        "portuguese" => "pt",
+        "russian" => "ru",
+        other => other.split(['-', '_']).next().unwrap_or(other),
+    };
+
+    Some(code.to_string())
+}
+
+/// Languages written without spaces between words, where characters are counted instead
+pub fn is_cjk(language: &str) -> bool {
+    matches!(language, "zh" | "ja" | "yue")
+}
+
+/// Whether the character-entropy sanity check (tuned on English) applies to this language
+pub fn has_alphabetic_script(language: &str) -> bool {
+    !matches!(language, "zh" | "ja" | "ko" | "yue")
+}
+
+/// Count words, or characters for languages that don't separate words with spaces
+pub fn count_words(text: &str, language: &str) -> usize {
+    if is_cjk(language) {
+        text.chars().filter(|c| c.is_alphanumeric()).count()
+    } else {
+        text.split_whitespace().count()
+    }
+}
+
+/// Plausible speaking rate range in words (or characters for CJK) per second
+pub fn speech_rate_bounds(language: &str) -> (f32, f32) {
+    if is_cjk(language) {
+        (0.5, 15.0)
+    } else {
+        (0.3, 8.0)
+    }
+}
+
+fn is_cjk_char(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x3000..=0x303F   // CJK punctuation
+        | 0xFF00..=0xFFEF   // Full-width forms
+    )
+}
+
+/// Language-specific cleanup of the raw Whisper output
+pub fn post_process(text: &str, language: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !is_cjk(language) {
+        return collapsed;
+    }
+
+    // Whisper inserts spaces between segments, which don't belong between CJK characters
+    let chars: Vec<char> = collapsed.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            !(c == ' ' && i > 0 && i + 1 < chars.len() && is_cjk_char(chars[i - 1]) && is_cjk_char(chars[i + 1]))
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+/// Phrases Whisper is known to hallucinate on silence or noise, per language.
+///
+/// Most come from subtitle credits and video outros in the training data.
+pub fn hallucination_phrases(language: &str) -> &'static [&'static str] {
+    match language {
+        "zh" => &[
+            "谢谢观看", "谢谢大家观看", "谢谢收看", "感谢观看",
+            "请不吝点赞 订阅 转发 打赏支持明镜与点点栏目",
+            "字幕由amara.org社区提供", "小编字幕由amara.org社区提供",
+        ],
+        "ja" => &[
+            "ご視聴ありがとうございました", "ご視聴ありがとうございました。",
+            "チャンネル登録をお願いします",
+        ],
+        "ko" => &["시청해주셔서 감사합니다", "시청해주셔서 감사합니다."],
+        "es" => &[
+            "gracias por ver", "¡gracias por ver!", "gracias por ver el video",
+            "subtítulos realizados por la comunidad de amara.org",
+        ],
+        "fr" => &[
+            "merci d'avoir regardé", "merci d'avoir regardé cette vidéo",
+            "sous-titres réalisés par la communauté d'amara.org",
+        ],
+        "de" => &[
+            "vielen dank fürs zuschauen", "untertitel im auftrag des zdf",
+            "untertitel der amara.org-community",
+        ],
+        _ => &[],
+    }
+}
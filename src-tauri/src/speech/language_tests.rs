@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use crate::speech::language::*;
+    use crate::speech::models::is_english_only;
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("en"), Some("en".to_string()));
+        assert_eq!(normalize_language("zh-CN"), Some("zh".to_string()));
+        assert_eq!(normalize_language("en_US"), Some("en".to_string()));
+        assert_eq!(normalize_language("Chinese"), Some("zh".to_string()));
+        assert_eq!(normalize_language("auto"), None);
+        assert_eq!(normalize_language("  "), None);
+    }
+
+    #[test]
+    fn test_count_words_by_language() {
+        assert_eq!(count_words("I like this show", "en"), 4);
+        assert_eq!(count_words("我喜欢这个节目。", "zh"), 7);
+    }
+
+    #[test]
+    fn test_post_process_removes_spaces_between_cjk() {
+        assert_eq!(post_process(" 我 喜欢 这个节目 ", "zh"), "我喜欢这个节目");
+        assert_eq!(post_process("我喜欢 Friends 这个节目", "zh"), "我喜欢 Friends 这个节目");
+        assert_eq!(post_process("  I  like   it ", "en"), "I like it");
+    }
+
+    #[test]
+    fn test_hallucination_phrases_are_language_specific() {
+        assert!(hallucination_phrases("zh").contains(&"谢谢观看"));
+        assert!(hallucination_phrases("en").is_empty());
+    }
+
+    #[test]
+    fn test_english_only_models() {
+        assert!(is_english_only("ggml-small.en.bin"));
+        assert!(!is_english_only("ggml-small.bin"));
+        assert!(is_english_only("my-custom.en.bin"));
+    }
+}
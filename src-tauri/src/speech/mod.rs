@@ -0,0 +1,16 @@
+pub mod language;
+pub mod models;
+pub mod recording;
+pub mod vad;
+
+#[cfg(test)]
+mod language_tests;
+#[cfg(test)]
+mod models_tests;
+#[cfg(test)]
+mod recording_tests;
+#[cfg(test)]
+mod vad_tests;
+
+/// Sample rate Whisper expects its input audio in
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
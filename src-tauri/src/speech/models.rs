@@ -0,0 +1,199 @@
+use crate::app_error;
+use crate::error::AppError;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A Whisper model published in the whisper.cpp repository
+#[derive(Debug, Clone, Copy)]
+pub struct WhisperModelSpec {
+    pub name: &'static str,
+    pub size_mb: u32,
+    /// `.en` models only understand English, the others cover all Whisper languages
+    pub multilingual: bool,
+    /// SHA-1 of the file as listed in whisper.cpp's `models/README.md`
+    pub sha1: &'static str,
+}
+
+pub const DEFAULT_WHISPER_MODEL: &str = "ggml-small.bin";
+
+pub const WHISPER_MODELS: &[WhisperModelSpec] = &[
+    WhisperModelSpec { name: "ggml-tiny.bin", size_mb: 39, multilingual: true, sha1: "bd577a113a864445d4c299885e0cb97d4ba92b5f" },      // Fastest, lower accuracy
+    WhisperModelSpec { name: "ggml-tiny.en.bin", size_mb: 39, multilingual: false, sha1: "c78c86eb1a8faa21b369bcd33207cc90d64ae9df" },
+    WhisperModelSpec { name: "ggml-base.bin", size_mb: 74, multilingual: true, sha1: "465707469ff3a37a2b9b8d8f89f2f99de7299dac" },      // Good balance
+    WhisperModelSpec { name: "ggml-base.en.bin", size_mb: 74, multilingual: false, sha1: "137c40403d78fd54d454da0f9bd998f78703390c" },
+    WhisperModelSpec { name: "ggml-small.bin", size_mb: 244, multilingual: true, sha1: "55356645c2b361a969dfd0ef2c5a50d530afd8d5" },    // Best accuracy for most use cases (default)
+    WhisperModelSpec { name: "ggml-small.en.bin", size_mb: 244, multilingual: false, sha1: "db8a495a91d927739e50b3fc1cc4c6b8f6c2d022" },
+    WhisperModelSpec { name: "ggml-medium.bin", size_mb: 769, multilingual: true, sha1: "fd9727b6e1217c2f614f9b698455c4ffd82463b4" },   // Even better accuracy, slower
+    WhisperModelSpec { name: "ggml-medium.en.bin", size_mb: 769, multilingual: false, sha1: "8c30f0e44ce9560643ebd10bbe50cd20eafd3723" },
+];
+
+pub fn find_model(name: &str) -> Option<&'static WhisperModelSpec> {
+    WHISPER_MODELS.iter().find(|spec| spec.name == name)
+}
+
+pub fn download_url(spec: &WhisperModelSpec) -> String {
+    format!("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}", spec.name)
+}
+
+/// Check a downloaded model against the hash whisper.cpp publishes for it, so a
+/// truncated or tampered download is never installed
+pub fn verify_download(spec: &WhisperModelSpec, sha1_hex: &str) -> Result<(), AppError> {
+    if sha1_hex.eq_ignore_ascii_case(spec.sha1) {
+        Ok(())
+    } else {
+        Err(app_error!(
+            "CHECKSUM_MISMATCH",
+            "Downloaded Whisper model is corrupted",
+            format!("{}: expected SHA-1 {}, got {}", spec.name, spec.sha1, sha1_hex)
+        ))
+    }
+}
+
+/// Whether a model file name refers to an English-only (`.en`) variant
+pub fn is_english_only(model_name: &str) -> bool {
+    match find_model(model_name) {
+        Some(spec) => !spec.multilingual,
+        None => model_name.contains(".en."),
+    }
+}
+
+/// Directory the Whisper models are stored in (`~/.loud-mouth/whisper-models`)
+pub fn model_dir() -> Result<PathBuf, AppError> {
+    crate::paths::app_subdir("whisper-models")
+}
+
+/// Reject names that would escape the model directory or aren't ggml `.bin` files
+pub fn validate_model_name(model_name: &str) -> Result<(), AppError> {
+    let valid = model_name.ends_with(".bin")
+        && !model_name.starts_with('.')
+        && !model_name.contains(['/', '\\'])
+        && !model_name.contains("..");
+
+    if valid {
+        Ok(())
+    } else {
+        Err(app_error!("INVALID_MODEL_NAME", "Invalid Whisper model name", model_name.to_string()))
+    }
+}
+
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// The hyperparameters from a ggml model header we care about
+#[derive(Debug, Clone, Copy)]
+pub struct ModelHeader {
+    pub n_vocab: i32,
+    pub ftype: i32,
+}
+
+impl ModelHeader {
+    /// English-only models have one token less than the multilingual vocabulary (51865)
+    pub fn is_multilingual(&self) -> bool {
+        self.n_vocab >= 51865
+    }
+
+    /// Weight format, quantized models store `ftype + version * 1000`
+    pub fn quantization(&self) -> &'static str {
+        match self.ftype % 1000 {
+            0 => "f32",
+            1 => "f16",
+            2 => "q4_0",
+            3 => "q4_1",
+            7 => "q8_0",
+            8 => "q5_0",
+            9 => "q5_1",
+            10 => "q2_k",
+            11 => "q3_k",
+            12 => "q4_k",
+            13 => "q5_k",
+            14 => "q6_k",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Read the ggml header of a model file, `None` if it isn't a whisper.cpp model
+pub fn read_model_header(path: &Path) -> Option<ModelHeader> {
+    let mut header = [0u8; 48];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+
+    let field = |offset: usize| i32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+    if field(0) as u32 != GGML_MAGIC {
+        return None;
+    }
+
+    // magic, n_vocab, 9 more audio/text dimensions, then ftype
+    Some(ModelHeader {
+        n_vocab: field(4),
+        ftype: field(44),
+    })
+}
+
+/// Integrity of a model file compared with the checksum recorded when it was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumStatus {
+    /// The file hash matches the recorded checksum
+    Verified,
+    /// The file was modified or truncated since it was installed
+    Mismatch,
+    /// A checksum is recorded but the file wasn't hashed
+    Unverified,
+    /// No checksum was recorded, e.g. the model predates the model manager
+    Unknown,
+}
+
+fn checksum_path(model_path: &Path) -> PathBuf {
+    let mut name = model_path.as_os_str().to_owned();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hash an installed model and store the digest next to it
+pub fn record_checksum(model_path: &Path) -> std::io::Result<String> {
+    let digest = sha256_file(model_path)?;
+    fs::write(checksum_path(model_path), &digest)?;
+    Ok(digest)
+}
+
+pub fn checksum_status(model_path: &Path, verify: bool) -> ChecksumStatus {
+    let recorded = match fs::read_to_string(checksum_path(model_path)) {
+        Ok(digest) => digest.trim().to_string(),
+        Err(_) => return ChecksumStatus::Unknown,
+    };
+
+    if !verify {
+        return ChecksumStatus::Unverified;
+    }
+
+    match sha256_file(model_path) {
+        Ok(digest) if digest == recorded => ChecksumStatus::Verified,
+        _ => ChecksumStatus::Mismatch,
+    }
+}
+
+/// Remove a model file together with its recorded checksum
+pub fn remove_model_files(model_path: &Path) -> std::io::Result<()> {
+    fs::remove_file(model_path)?;
+    let sidecar = checksum_path(model_path);
+    if sidecar.exists() {
+        fs::remove_file(sidecar)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests {
+    use crate::speech::models::*;
+
+    #[test]
+    fn test_every_catalog_model_has_a_published_hash() {
+        for spec in WHISPER_MODELS {
+            assert_eq!(spec.sha1.len(), 40, "{}", spec.name);
+            assert!(spec.sha1.chars().all(|c| c.is_ascii_hexdigit()), "{}", spec.name);
+        }
+    }
+
+    #[test]
+    fn test_verify_download_rejects_other_hashes() {
+        let spec = find_model("ggml-tiny.en.bin").unwrap();
+        assert!(verify_download(spec, "c78c86eb1a8faa21b369bcd33207cc90d64ae9df").is_ok());
+        assert!(verify_download(spec, "C78C86EB1A8FAA21B369BCD33207CC90D64AE9DF").is_ok());
+
+        let error = verify_download(spec, "bd577a113a864445d4c299885e0cb97d4ba92b5f").unwrap_err();
+        assert_eq!(error.code, "CHECKSUM_MISMATCH");
+    }
+}
@@ -0,0 +1,92 @@
+use crate::app_error;
+use crate::error::AppError;
+use crate::speech::vad::{self, VadConfig};
+use crate::speech::WHISPER_SAMPLE_RATE;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Opus bitrate for archived attempts, plenty for a single speaker
+const OPUS_BITRATE: &str = "24k";
+
+/// Directory archived speaking attempts are stored in (`~/.loud-mouth/recordings`)
+pub fn recordings_dir() -> Result<PathBuf, AppError> {
+    crate::paths::app_subdir("recordings")
+}
+
+pub fn mime_type(audio_format: &str) -> &'static str {
+    match audio_format {
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Cut leading and trailing silence so the archive only holds the spoken part
+/// (plus the padding the voice activity detector already keeps around speech)
+pub fn trim_to_speech(samples: &[f32]) -> &[f32] {
+    let regions = vad::detect_speech_regions(samples, WHISPER_SAMPLE_RATE, &VadConfig::default());
+    let (first, last) = match (regions.first(), regions.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return samples,
+    };
+
+    let to_index = |ms: u32| (ms as usize * WHISPER_SAMPLE_RATE as usize / 1000).min(samples.len());
+    &samples[to_index(first.start_ms)..to_index(last.end_ms)]
+}
+
+pub fn duration_ms(samples: &[f32]) -> i32 {
+    (samples.len() as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i32
+}
+
+fn write_wav(samples: &[f32], path: &Path) -> Result<(), AppError> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: WHISPER_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| app_error!("AUDIO_ERROR", "Failed to create WAV file", e.to_string()))?;
+    for &sample in samples {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .map_err(|e| app_error!("AUDIO_ERROR", "Failed to write WAV samples", e.to_string()))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| app_error!("AUDIO_ERROR", "Failed to finalize WAV file", e.to_string()))
+}
+
+fn encode_opus(wav_path: &Path, ogg_path: &Path) -> bool {
+    Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(wav_path)
+        .arg("-c:a")
+        .arg("libopus")
+        .arg("-b:a")
+        .arg(OPUS_BITRATE)
+        .arg(ogg_path)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Store 16kHz mono samples as `<dir>/<stem>.ogg` (Opus), falling back to WAV when
+/// FFmpeg or its Opus encoder isn't available. Returns the file path and its format.
+pub fn save_compressed(samples: &[f32], dir: &Path, stem: &str) -> Result<(PathBuf, &'static str), AppError> {
+    fs::create_dir_all(dir)?;
+    let wav_path = dir.join(format!("{}.wav", stem));
+    write_wav(samples, &wav_path)?;
+
+    let ogg_path = dir.join(format!("{}.ogg", stem));
+    if encode_opus(&wav_path, &ogg_path) {
+        fs::remove_file(&wav_path)?;
+        return Ok((ogg_path, "ogg"));
+    }
+
+    eprintln!("FFmpeg Opus encoding unavailable, keeping WAV recording");
+    let _ = fs::remove_file(&ogg_path);
+    Ok((wav_path, "wav"))
+}
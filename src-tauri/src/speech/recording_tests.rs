@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod tests {
+    use crate::speech::recording::*;
+    use crate::speech::WHISPER_SAMPLE_RATE;
+
+    #[test]
+    fn test_trim_to_speech_drops_surrounding_silence() {
+        let rate = WHISPER_SAMPLE_RATE as usize;
+        let mut samples = vec![0.0f32; rate];
+        samples.extend((0..rate).map(|i| (i as f32 * 0.05).sin() * 0.4));
+        samples.extend(vec![0.0f32; rate]);
+
+        let trimmed = trim_to_speech(&samples);
+        let ms = duration_ms(trimmed);
+        assert!((1000..1500).contains(&ms), "trimmed to {}ms", ms);
+    }
+
+    #[test]
+    fn test_trim_to_speech_keeps_silent_audio() {
+        let samples = vec![0.0f32; WHISPER_SAMPLE_RATE as usize];
+        assert_eq!(trim_to_speech(&samples).len(), samples.len());
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+/// A span of the original recording that contains speech, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeechRegion {
+    pub start_ms: u32,
+    pub end_ms: u32,
+}
+
+impl SpeechRegion {
+    pub fn duration_ms(&self) -> u32 {
+        self.end_ms - self.start_ms
+    }
+}
+
+/// Tuning knobs for the energy / zero-crossing voice activity detector
+#[derive(Debug, Clone)]
+pub struct VadConfig {
+    /// Analysis frame length
+    pub frame_ms: u32,
+    /// Absolute RMS below which a frame is never considered speech
+    pub energy_floor: f32,
+    /// A frame is voiced when its RMS exceeds the estimated noise floor by this factor
+    pub energy_ratio: f32,
+    /// Highest noise floor estimate used. Without a pause the quietest frames are still
+    /// speech, and treating them as noise would leave nothing voiced.
+    pub noise_floor_ceiling: f32,
+    /// Zero-crossing rate above which quieter frames are kept as unvoiced consonants (s, f, th)
+    pub fricative_zcr: f32,
+    /// Pauses shorter than this are bridged, longer ones split the audio into separate regions
+    pub split_pause_ms: u32,
+    /// Voiced bursts shorter than this are dropped as clicks or breaths
+    pub min_speech_ms: u32,
+    /// Context kept around each region so word onsets and tails are not clipped
+    pub padding_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            frame_ms: 30,
+            energy_floor: 0.005,
+            energy_ratio: 3.0,
+            noise_floor_ceiling: 0.02,
+            fricative_zcr: 0.25,
+            split_pause_ms: 600,
+            min_speech_ms: 120,
+            padding_ms: 150,
+        }
+    }
+}
+
+/// Per-frame features used for the voiced/unvoiced decision
+struct Frame {
+    rms: f32,
+    zcr: f32,
+}
+
+fn analyze_frames(samples: &[f32], frame_len: usize) -> Vec<Frame> {
+    samples
+        .chunks(frame_len)
+        .map(|chunk| {
+            let sum: f32 = chunk.iter().map(|&x| x * x).sum();
+            let rms = (sum / chunk.len() as f32).sqrt();
+
+            let crossings = chunk
+                .windows(2)
+                .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+                .count();
+            let zcr = crossings as f32 / chunk.len().max(1) as f32;
+
+            Frame { rms, zcr }
+        })
+        .collect()
+}
+
+/// Estimate the background noise level as a low percentile of frame energies
+fn estimate_noise_floor(frames: &[Frame]) -> f32 {
+    let mut energies: Vec<f32> = frames.iter().map(|f| f.rms).collect();
+    energies.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    energies[energies.len() / 10]
+}
+
+/// Detect the regions of `samples` that contain speech.
+///
+/// Leading and trailing silence is excluded, short pauses inside an utterance are
+/// bridged and long pauses split the recording into separate regions.
+pub fn detect_speech_regions(samples: &[f32], sample_rate: u32, config: &VadConfig) -> Vec<SpeechRegion> {
+    let frame_len = (sample_rate * config.frame_ms / 1000) as usize;
+    if samples.is_empty() || frame_len == 0 {
+        return Vec::new();
+    }
+
+    let frames = analyze_frames(samples, frame_len);
+    let noise_floor = estimate_noise_floor(&frames).min(config.noise_floor_ceiling);
+    let threshold = (noise_floor * config.energy_ratio).max(config.energy_floor);
+
+    let voiced: Vec<bool> = frames
+        .iter()
+        .map(|f| f.rms >= threshold || (f.rms >= threshold * 0.5 && f.zcr >= config.fricative_zcr))
+        .collect();
+
+    // Group consecutive voiced frames, bridging pauses shorter than the split threshold
+    let max_gap_frames = (config.split_pause_ms / config.frame_ms) as usize;
+    let mut frame_regions: Vec<(usize, usize)> = Vec::new();
+    for (idx, _) in voiced.iter().enumerate().filter(|(_, &v)| v) {
+        match frame_regions.last_mut() {
+            Some((_, end)) if idx - *end <= max_gap_frames => *end = idx + 1,
+            _ => frame_regions.push((idx, idx + 1)),
+        }
+    }
+
+    let total_ms = (samples.len() as u64 * 1000 / sample_rate as u64) as u32;
+    let mut regions: Vec<SpeechRegion> = Vec::new();
+
+    for (start_frame, end_frame) in frame_regions {
+        let duration_ms = (end_frame - start_frame) as u32 * config.frame_ms;
+        if duration_ms < config.min_speech_ms {
+            continue;
+        }
+
+        let start_ms = (start_frame as u32 * config.frame_ms).saturating_sub(config.padding_ms);
+        let end_ms = (end_frame as u32 * config.frame_ms + config.padding_ms).min(total_ms);
+
+        // Padding can make neighbouring regions overlap, merge them in that case
+        match regions.last_mut() {
+            Some(prev) if start_ms <= prev.end_ms => prev.end_ms = end_ms,
+            _ => regions.push(SpeechRegion { start_ms, end_ms }),
+        }
+    }
+
+    regions
+}
+
+/// Concatenate the voiced regions of `samples`, separated by `gap_ms` of silence.
+///
+/// The result is padded with trailing silence up to `min_total_ms` because Whisper
+/// refuses to decode inputs that are too short.
+pub fn extract_voiced_samples(
+    samples: &[f32],
+    sample_rate: u32,
+    regions: &[SpeechRegion],
+    gap_ms: u32,
+    min_total_ms: u32,
+) -> Vec<f32> {
+    let ms_to_len = |ms: u32| (ms as u64 * sample_rate as u64 / 1000) as usize;
+    let to_index = |ms: u32| ms_to_len(ms).min(samples.len());
+    let gap_len = ms_to_len(gap_ms);
+
+    let mut voiced = Vec::new();
+    for (i, region) in regions.iter().enumerate() {
+        if i > 0 {
+            voiced.resize(voiced.len() + gap_len, 0.0);
+        }
+        voiced.extend_from_slice(&samples[to_index(region.start_ms)..to_index(region.end_ms)]);
+    }
+
+    let min_len = ms_to_len(min_total_ms);
+    if !voiced.is_empty() && voiced.len() < min_len {
+        voiced.resize(min_len, 0.0);
+    }
+
+    voiced
+}
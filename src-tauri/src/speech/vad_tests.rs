@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use crate::speech::vad::{detect_speech_regions, extract_voiced_samples, SpeechRegion, VadConfig};
+    use crate::speech::WHISPER_SAMPLE_RATE;
+
+    fn silence(ms: u32) -> Vec<f32> {
+        vec![0.0; (WHISPER_SAMPLE_RATE * ms / 1000) as usize]
+    }
+
+    fn tone(ms: u32) -> Vec<f32> {
+        (0..(WHISPER_SAMPLE_RATE * ms / 1000))
+            .map(|i| (i as f32 * 2.0 * std::f32::consts::PI * 220.0 / WHISPER_SAMPLE_RATE as f32).sin() * 0.3)
+            .collect()
+    }
+
+    #[test]
+    fn test_vad_silence_has_no_regions() {
+        let regions = detect_speech_regions(&silence(2000), WHISPER_SAMPLE_RATE, &VadConfig::default());
+        assert!(regions.is_empty());
+    }
+
+    #[test]
+    fn test_vad_trims_leading_and_trailing_silence() {
+        let samples = [silence(1000), tone(900), silence(1200)].concat();
+        let regions = detect_speech_regions(&samples, WHISPER_SAMPLE_RATE, &VadConfig::default());
+
+        assert_eq!(regions.len(), 1);
+        let region = regions[0];
+        // Speech is at 1000..1900ms, padded by 150ms on each side
+        assert!(region.start_ms >= 800 && region.start_ms <= 1000, "start was {}", region.start_ms);
+        assert!(region.end_ms >= 1900 && region.end_ms <= 2100, "end was {}", region.end_ms);
+    }
+
+    #[test]
+    fn test_vad_splits_on_long_pause_and_bridges_short_pause() {
+        let config = VadConfig::default();
+        let short_pause = [tone(600), silence(200), tone(600)].concat();
+        assert_eq!(detect_speech_regions(&short_pause, WHISPER_SAMPLE_RATE, &config).len(), 1);
+
+        let long_pause = [tone(600), silence(1500), tone(600)].concat();
+        assert_eq!(detect_speech_regions(&long_pause, WHISPER_SAMPLE_RATE, &config).len(), 2);
+    }
+
+    #[test]
+    fn test_vad_keeps_continuous_speech_without_pauses() {
+        // Loudness varies like speech but never drops to a pause
+        let samples: Vec<f32> = tone(3000)
+            .iter()
+            .enumerate()
+            .map(|(i, x)| x * (0.75 + 0.25 * (i as f32 * 2.0 * std::f32::consts::PI * 3.0 / WHISPER_SAMPLE_RATE as f32).sin()))
+            .collect();
+        let regions = detect_speech_regions(&samples, WHISPER_SAMPLE_RATE, &VadConfig::default());
+
+        assert_eq!(regions, vec![SpeechRegion { start_ms: 0, end_ms: 3000 }]);
+    }
+
+    #[test]
+    fn test_vad_drops_short_clicks() {
+        let samples = [silence(500), tone(30), silence(1000)].concat();
+        let regions = detect_speech_regions(&samples, WHISPER_SAMPLE_RATE, &VadConfig::default());
+        assert!(regions.is_empty());
+    }
+
+    #[test]
+    fn test_extract_voiced_samples_concatenates_with_gap_and_min_length() {
+        let samples = [tone(500), silence(2000), tone(500)].concat();
+        let regions = vec![
+            SpeechRegion { start_ms: 0, end_ms: 500 },
+            SpeechRegion { start_ms: 2500, end_ms: 3000 },
+        ];
+
+        let voiced = extract_voiced_samples(&samples, WHISPER_SAMPLE_RATE, &regions, 200, 0);
+        assert_eq!(voiced.len(), (WHISPER_SAMPLE_RATE * 1200 / 1000) as usize);
+
+        let padded = extract_voiced_samples(&samples, WHISPER_SAMPLE_RATE, &regions[..1], 200, 1500);
+        assert_eq!(padded.len(), (WHISPER_SAMPLE_RATE * 1500 / 1000) as usize);
+    }
+}
@@ -0,0 +1,64 @@
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::MigrationHarness;
+
+use crate::database::MIGRATIONS;
+use crate::models::vocabulary::{CreateVocabularyRequest, NewVocabulary};
+
+/// Tables that predate the migrations, installs already have them when the first
+/// migration runs
+const BASE_TABLES: &str = "
+    CREATE TABLE vocabulary (
+        id TEXT PRIMARY KEY, user_id TEXT NOT NULL, video_id TEXT NOT NULL, word TEXT NOT NULL,
+        timestamp INTEGER NOT NULL, before_2_en TEXT, before_2_zh TEXT, before_1_en TEXT, before_1_zh TEXT,
+        target_en TEXT NOT NULL, target_zh TEXT NOT NULL, dictionary_response TEXT, review_stage INTEGER DEFAULT 0,
+        next_review_at TEXT NOT NULL, last_reviewed_at TEXT, is_phrase BOOLEAN DEFAULT 0,
+        created_at TEXT DEFAULT CURRENT_TIMESTAMP, before_2_timestamp INTEGER, scheduled_review_at TEXT,
+        review_count INTEGER DEFAULT 0, consecutive_correct INTEGER DEFAULT 0, was_late BOOLEAN DEFAULT 0,
+        ever_overdue BOOLEAN NOT NULL DEFAULT 0, correct_count INTEGER DEFAULT 0,
+        word_start_index INTEGER, word_end_index INTEGER
+    );
+    CREATE TABLE subtitles (
+        id TEXT PRIMARY KEY NOT NULL, video_id TEXT NOT NULL, language TEXT NOT NULL,
+        file_path TEXT NOT NULL, extracted_date TEXT
+    );
+    CREATE TABLE video_progress (
+        id TEXT PRIMARY KEY NOT NULL, user_id INTEGER NOT NULL, video_id TEXT NOT NULL,
+        position INTEGER NOT NULL, duration INTEGER NOT NULL, updated_at TEXT NOT NULL
+    );
+";
+
+/// A database at `url` with the current schema, `":memory:"` for one that only lives
+/// as long as the connection
+pub fn migrated_connection(url: &str) -> SqliteConnection {
+    let mut conn = SqliteConnection::establish(url).expect("open test database");
+    conn.batch_execute(BASE_TABLES).expect("create base tables");
+    conn.run_pending_migrations(MIGRATIONS).expect("run migrations");
+    conn
+}
+
+/// A request to save `word` for user `"1"` from the sentence at `timestamp`
+pub fn vocabulary_request(word: &str, video_id: &str, timestamp: i32) -> CreateVocabularyRequest {
+    CreateVocabularyRequest {
+        user_id: "1".to_string(),
+        video_id: video_id.to_string(),
+        word: word.to_string(),
+        timestamp,
+        before_2_en: None,
+        before_2_zh: None,
+        before_2_timestamp: None,
+        before_1_en: None,
+        before_1_zh: None,
+        target_en: format!("I saw the {} today", word),
+        target_zh: "我今天看到了".to_string(),
+        dictionary_response: None,
+        next_review_at: "2026-10-20T08:00:00Z".to_string(),
+        is_phrase: None,
+        word_start_index: None,
+    }
+}
+
+pub fn new_vocabulary(word: &str, video_id: &str, timestamp: i32) -> NewVocabulary {
+    vocabulary_request(word, video_id, timestamp).into_new_vocabulary().unwrap()
+}
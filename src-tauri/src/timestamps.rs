@@ -0,0 +1,74 @@
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+
+use crate::app_error;
+use crate::error::AppError;
+
+pub fn now_ms() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+pub fn to_datetime(ms: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(ms).single().unwrap_or_default()
+}
+
+/// Format as RFC 3339 in UTC with millisecond precision (`2025-01-01T09:00:00.000Z`)
+pub fn to_rfc3339(ms: i64) -> String {
+    to_datetime(ms).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Parse an RFC 3339 timestamp with any offset into UTC epoch milliseconds
+pub fn parse_rfc3339(value: &str) -> Result<i64, AppError> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|e| app_error!("INVALID_TIMESTAMP", "Timestamp must be RFC 3339, e.g. 2025-01-01T09:00:00Z", format!("{}: {}", value, e)))
+}
+
+/// Serde adapter for review timestamps, which are stored as UTC epoch milliseconds
+/// but exchanged with the frontend as RFC 3339 strings. Either form deserializes,
+/// so review snapshots written before the switch to integers still load.
+pub mod rfc3339_ms {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Timestamp {
+        Millis(i64),
+        Text(String),
+    }
+
+    impl Timestamp {
+        pub(super) fn into_ms<E: de::Error>(self) -> Result<i64, E> {
+            match self {
+                Timestamp::Millis(ms) => Ok(ms),
+                Timestamp::Text(text) => super::parse_rfc3339(&text).map_err(|e| E::custom(e.message)),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(ms: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::to_rfc3339(*ms))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        Timestamp::deserialize(deserializer)?.into_ms()
+    }
+}
+
+/// `rfc3339_ms` for optional timestamps
+pub mod option_rfc3339_ms {
+    use super::rfc3339_ms::Timestamp;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ms: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match ms {
+            Some(ms) => serializer.serialize_str(&super::to_rfc3339(*ms)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+        Option::<Timestamp>::deserialize(deserializer)?
+            .map(Timestamp::into_ms)
+            .transpose()
+    }
+}
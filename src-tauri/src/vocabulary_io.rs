@@ -0,0 +1,274 @@
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app_error;
+use crate::error::{AppError, Result};
+use crate::lemmatizer;
+use crate::models::vocabulary::{NewVocabulary, Vocabulary};
+use crate::scheduler::ebbinghaus::MAX_STAGE;
+use crate::timestamps;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Json,
+}
+
+/// One vocabulary item as it appears in CSV, TSV and JSON files. The field names
+/// are the column names and must stay stable so older exports keep importing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VocabularyRecord {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub video_id: Option<String>,
+    /// Used to find the video when `video_id` is unknown, e.g. on another install
+    #[serde(default)]
+    pub video_title: Option<String>,
+    pub word: String,
+    #[serde(default)]
+    pub is_phrase: Option<bool>,
+    /// Subtitle time of the sentence in milliseconds
+    pub timestamp: i32,
+    #[serde(default)]
+    pub word_start_index: Option<i32>,
+    pub target_en: String,
+    #[serde(default)]
+    pub target_zh: Option<String>,
+    #[serde(default)]
+    pub before_1_en: Option<String>,
+    #[serde(default)]
+    pub before_1_zh: Option<String>,
+    #[serde(default)]
+    pub before_2_en: Option<String>,
+    #[serde(default)]
+    pub before_2_zh: Option<String>,
+    #[serde(default)]
+    pub before_2_timestamp: Option<i32>,
+    #[serde(default)]
+    pub dictionary_response: Option<String>,
+    #[serde(default)]
+    pub review_stage: Option<i32>,
+    #[serde(default)]
+    pub review_count: Option<i32>,
+    #[serde(default)]
+    pub correct_count: Option<i32>,
+    #[serde(default)]
+    pub lapses: Option<i32>,
+    #[serde(default)]
+    pub is_leech: Option<bool>,
+    #[serde(default)]
+    pub suspended: Option<bool>,
+    /// FSRS memory state, empty for items the scheduler hasn't seen yet
+    #[serde(default)]
+    pub stability: Option<f64>,
+    #[serde(default)]
+    pub difficulty: Option<f64>,
+    /// RFC 3339, due immediately when empty
+    #[serde(default)]
+    pub next_review_at: Option<String>,
+    #[serde(default)]
+    pub last_reviewed_at: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// `created_at` is RFC 3339 for new rows but SQLite's `CURRENT_TIMESTAMP` format for
+/// older ones, accept both and normalize to RFC 3339
+pub fn normalize_created_at(value: &str) -> Option<String> {
+    let value = value.trim();
+    let parsed = DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|dt| dt.and_utc()))
+        .ok()?;
+    Some(parsed.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+impl VocabularyRecord {
+    pub fn from_vocabulary(item: Vocabulary, video_title: Option<String>) -> Self {
+        Self {
+            id: item.id,
+            video_id: Some(item.video_id),
+            video_title,
+            word: item.word,
+            is_phrase: item.is_phrase,
+            timestamp: item.timestamp,
+            word_start_index: item.word_start_index,
+            target_en: item.target_en,
+            target_zh: Some(item.target_zh),
+            before_1_en: item.before_1_en,
+            before_1_zh: item.before_1_zh,
+            before_2_en: item.before_2_en,
+            before_2_zh: item.before_2_zh,
+            before_2_timestamp: item.before_2_timestamp,
+            dictionary_response: item.dictionary_response,
+            review_stage: item.review_stage,
+            review_count: item.review_count,
+            correct_count: item.correct_count,
+            lapses: Some(item.lapses),
+            is_leech: Some(item.is_leech),
+            suspended: Some(item.suspended),
+            stability: item.stability,
+            difficulty: item.difficulty,
+            next_review_at: Some(timestamps::to_rfc3339(item.next_review_at)),
+            last_reviewed_at: item.last_reviewed_at.map(timestamps::to_rfc3339),
+            created_at: item.created_at.as_deref().and_then(normalize_created_at),
+        }
+    }
+
+    /// Validate the record and turn it into a row for `user_id` on `video_id`. Missing
+    /// review fields start the item fresh, due at `now_ms`.
+    pub fn into_new_vocabulary(self, user_id: &str, video_id: &str, now_ms: i64) -> Result<NewVocabulary> {
+        let invalid = |message: &str| app_error!("VALIDATION_ERROR", message);
+
+        let word = self.word.trim().to_string();
+        if word.is_empty() {
+            return Err(invalid("word is required"));
+        }
+        if self.target_en.trim().is_empty() {
+            return Err(invalid("target_en is required"));
+        }
+        if self.timestamp < 0 || self.before_2_timestamp.is_some_and(|t| t < 0) {
+            return Err(invalid("Timestamps must not be negative"));
+        }
+        if self.word_start_index.is_some_and(|start| start < 0) {
+            return Err(invalid("word_start_index must not be negative"));
+        }
+        let review_stage = self.review_stage.unwrap_or(0);
+        if !(0..=MAX_STAGE).contains(&review_stage) {
+            return Err(app_error!(
+                "VALIDATION_ERROR",
+                "review_stage is out of range",
+                format!("{} is not between 0 and {}", review_stage, MAX_STAGE)
+            ));
+        }
+        let review_count = self.review_count.unwrap_or(0);
+        let correct_count = self.correct_count.unwrap_or(0);
+        let lapses = self.lapses.unwrap_or(0);
+        if review_count < 0 || correct_count < 0 || lapses < 0 {
+            return Err(invalid("Review counts must not be negative"));
+        }
+        if correct_count > review_count {
+            return Err(invalid("correct_count can't exceed review_count"));
+        }
+        if self.stability.is_some_and(|stability| !(stability.is_finite() && stability > 0.0)) {
+            return Err(invalid("stability must be a positive number"));
+        }
+        if self.difficulty.is_some_and(|difficulty| !(1.0..=10.0).contains(&difficulty)) {
+            return Err(invalid("difficulty must be between 1 and 10"));
+        }
+
+        let next_review_at = non_empty(self.next_review_at)
+            .map(|value| timestamps::parse_rfc3339(&value))
+            .transpose()?
+            .unwrap_or(now_ms);
+        let last_reviewed_at = non_empty(self.last_reviewed_at)
+            .map(|value| timestamps::parse_rfc3339(&value))
+            .transpose()?;
+        let created_at = match non_empty(self.created_at) {
+            Some(value) => Some(normalize_created_at(&value).ok_or_else(|| {
+                app_error!("INVALID_TIMESTAMP", "created_at must be RFC 3339, e.g. 2025-01-01T09:00:00Z", value)
+            })?),
+            None => None,
+        };
+
+        let word_end_index = self.word_start_index.map(|start| start + word.len() as i32);
+        let lemma = lemmatizer::lemmatize(&word);
+        Ok(NewVocabulary {
+            id: non_empty(self.id).unwrap_or_else(|| Uuid::new_v4().to_string()),
+            user_id: user_id.to_string(),
+            video_id: video_id.to_string(),
+            word,
+            timestamp: self.timestamp,
+            before_2_en: self.before_2_en,
+            before_2_zh: self.before_2_zh,
+            before_2_timestamp: self.before_2_timestamp,
+            before_1_en: self.before_1_en,
+            before_1_zh: self.before_1_zh,
+            target_en: self.target_en,
+            target_zh: self.target_zh.unwrap_or_default(),
+            dictionary_response: non_empty(self.dictionary_response),
+            review_stage,
+            next_review_at,
+            last_reviewed_at,
+            is_phrase: self.is_phrase.unwrap_or(false),
+            scheduled_review_at: Some(next_review_at),
+            review_count,
+            consecutive_correct: 0,
+            was_late: false,
+            ever_overdue: false,
+            correct_count,
+            word_start_index: self.word_start_index,
+            word_end_index,
+            lapses,
+            is_leech: self.is_leech.unwrap_or(false),
+            suspended: self.suspended.unwrap_or(false),
+            stability: self.stability,
+            difficulty: self.difficulty,
+            created_at,
+            lemma: Some(lemma),
+        })
+    }
+}
+
+fn delimiter(format: ExportFormat) -> u8 {
+    if format == ExportFormat::Tsv {
+        b'\t'
+    } else {
+        b','
+    }
+}
+
+pub fn write_records(format: ExportFormat, records: &[VocabularyRecord]) -> Result<String> {
+    let export_error = |e: String| AppError::new("EXPORT_ERROR", "Failed to write vocabulary export").with_details(e);
+
+    if format == ExportFormat::Json {
+        return serde_json::to_string_pretty(records).map_err(|e| export_error(e.to_string()));
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter(format))
+        .from_writer(Vec::new());
+    for record in records {
+        writer.serialize(record).map_err(|e| export_error(e.to_string()))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| export_error(e.to_string()))?;
+    String::from_utf8(bytes).map_err(|e| export_error(e.to_string()))
+}
+
+/// Parse an export back into records. A file that can't be read at all is an error,
+/// a row that doesn't fit the columns is returned as that row's error message.
+pub fn read_records(format: ExportFormat, content: &str) -> Result<Vec<std::result::Result<VocabularyRecord, String>>> {
+    let parse_error = |e: String| AppError::new("IMPORT_PARSE_ERROR", "Failed to read vocabulary file").with_details(e);
+
+    if format == ExportFormat::Json {
+        let rows: Vec<serde_json::Value> = serde_json::from_str(content).map_err(|e| parse_error(e.to_string()))?;
+        return Ok(rows
+            .into_iter()
+            .map(|row| serde_json::from_value(row).map_err(|e| e.to_string()))
+            .collect());
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter(format))
+        .trim(csv::Trim::Headers)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+    let headers = reader.headers().map_err(|e| parse_error(e.to_string()))?.clone();
+    for column in ["word", "timestamp", "target_en"] {
+        if !headers.iter().any(|header| header == column) {
+            return Err(parse_error(format!("Missing column `{}`", column)));
+        }
+    }
+
+    Ok(reader
+        .deserialize::<VocabularyRecord>()
+        .map(|row| row.map_err(|e| e.to_string()))
+        .collect())
+}
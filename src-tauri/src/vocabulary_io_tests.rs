@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::vocabulary_io::*;
+
+    fn record() -> VocabularyRecord {
+        VocabularyRecord {
+            id: Some("v-1".to_string()),
+            video_id: Some("video".to_string()),
+            word: "run".to_string(),
+            // 12:34.560 into the video
+            timestamp: 754_560,
+            target_en: "I run, \"fast\"\nevery day".to_string(),
+            target_zh: Some("我每天跑步".to_string()),
+            review_stage: Some(2),
+            review_count: Some(3),
+            correct_count: Some(2),
+            stability: Some(4.93),
+            difficulty: Some(5.12),
+            next_review_at: Some("2026-10-20T08:00:00.000Z".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_records_round_trip_in_every_format() {
+        for format in [ExportFormat::Csv, ExportFormat::Tsv, ExportFormat::Json] {
+            let content = write_records(format, &[record()]).unwrap();
+            let rows = read_records(format, &content).unwrap();
+            assert_eq!(rows, vec![Ok(record())], "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_read_records_reports_bad_rows() {
+        let content = "word,timestamp,target_en\nrun,754560,I run\nwalk,soon,I walk\n";
+        let rows = read_records(ExportFormat::Csv, content).unwrap();
+        assert!(rows[0].is_ok());
+        assert!(rows[1].is_err());
+
+        let missing_column = read_records(ExportFormat::Csv, "word,target_en\nrun,I run\n").unwrap_err();
+        assert_eq!(missing_column.code, "IMPORT_PARSE_ERROR");
+    }
+
+    #[test]
+    fn test_into_new_vocabulary_validates() {
+        let item = record().into_new_vocabulary("user", "video", 0).unwrap();
+        assert_eq!(item.next_review_at, 1_792_483_200_000);
+        assert_eq!((item.stability, item.difficulty), (Some(4.93), Some(5.12)));
+        assert_eq!(item.scheduled_review_at, Some(item.next_review_at));
+
+        let too_many_correct = VocabularyRecord { correct_count: Some(9), ..record() };
+        assert!(too_many_correct.into_new_vocabulary("user", "video", 0).is_err());
+        let bad_stage = VocabularyRecord { review_stage: Some(99), ..record() };
+        assert!(bad_stage.into_new_vocabulary("user", "video", 0).is_err());
+        let bad_stability = VocabularyRecord { stability: Some(-1.0), ..record() };
+        assert!(bad_stability.into_new_vocabulary("user", "video", 0).is_err());
+        let bad_difficulty = VocabularyRecord { difficulty: Some(11.0), ..record() };
+        assert!(bad_difficulty.into_new_vocabulary("user", "video", 0).is_err());
+        assert_eq!(normalize_created_at("2026-10-18 09:30:00"), Some("2026-10-18T09:30:00Z".to_string()));
+    }
+}
@@ -328,61 +328,18 @@ export default function VideoPlayer() {
   };
 
   const fetchWordInfo = async (words: SelectedWord[]) => {
-    const apiKey = import.meta.env.VITE_OPENAI_API_KEY;
-    if (!apiKey || apiKey === "your_openai_api_key_here") {
-      console.error("OpenAI API key not configured");
-      return null;
-    }
-
-    // Extract just the cleaned text for API, sorted by position
-    const wordTexts = words
+    // Look up the cleaned text, sorted by position, in the sentence it was picked from
+    const text = words
       .sort((a, b) => a.index - b.index)
-      .map((w) => w.text);
-    const text = wordTexts.join(" ");
-    const prompt =
-      wordTexts.length > 1
-        ? `解释短语"${text}"在语境中的含义。请使用Markdown格式回复，不要在译文中重复短语本身：
-
-**译文：** [直接提供中文含义，不要包含英文短语]
-**用法：** 解释此短语/俚语常见使用场景
-**英文例句：** 提供1个不同的简短例句
-**中文翻译：** 例句的中文翻译`
-        : `解释单词"${text}"。请使用Markdown格式回复：
-
-**含义：** [直接提供1-2个中文释义]
-**英文例句：** 提供1个简短例句
-**中文翻译：** 例句的中文翻译`;
+      .map((w) => w.text)
+      .join(" ");
 
     try {
-      const response = await fetch(
-        "https://api.openai.com/v1/chat/completions",
-        {
-          method: "POST",
-          headers: {
-            "Content-Type": "application/json",
-            Authorization: `Bearer ${apiKey}`,
-          },
-          body: JSON.stringify({
-            model: "gpt-4o-mini",
-            messages: [
-              {
-                role: "user",
-                content: prompt,
-              },
-            ],
-            temperature: 0.3,
-            max_tokens: 200,
-          }),
-        },
-      );
-
-      if (!response.ok) {
-        throw new Error(`API request failed: ${response.status}`);
-      }
-
-      const data = await response.json();
-      let content = data.choices[0].message.content;
-      return content;
+      const result = await invoke<{ markdown: string }>("lookup_with_llm", {
+        word: text,
+        context: getCurrentSubtitle()?.text,
+      });
+      return result.markdown;
     } catch (error) {
       console.error("Error fetching word info:", error);
       return null;